@@ -2,14 +2,35 @@
 //!
 //! Central application state management for tgcp.
 
+use crate::bucket::BucketView;
+use crate::chain::OperationChain;
 use crate::config::Config;
+use crate::describe::{
+    ancestor_pointers, container_pointers, display_path, find_matches, line_index, parent_pointer,
+    render_lines, value_to_text, DocFormat, DocLine,
+};
+use crate::diff::{diff_values, DiffResult};
+use crate::edit::{self, EditKind, PendingEdit};
+use crate::firewall::FirewallView;
+use crate::form::{FormPurpose, FormStage, FormState};
 use crate::gcp::client::{GcpClient, OperationStatus};
 use crate::notification::{DetailLevel, NotificationManager, OperationType, SoundConfig};
+use crate::preview::ObjectPreview;
+use crate::resource::sdk_dispatch::{execute_action, invoke_sdk};
 use crate::resource::{
-    enrich_with_metrics, extract_json_value, fetch_resources_paginated, get_all_resource_keys,
-    get_resource, MetricsHistory, ResourceDef, ResourceFilter,
+    enrich_with_metrics, extract_json_value, fetch_resource_detail, fetch_resources_paginated,
+    get_all_resource_keys, get_resource, parent_only_hint, resolve_self_link, MetricsHistory,
+    ResourceDef, ResourceFilter, SelfLink,
 };
+use crate::serial::SerialState;
+use crate::shell::run::{validate_parallelism, RunState};
+use crate::shell::{self, ScpDirection, ShellResult};
+use crate::storage::ObjectBrowser;
 use crate::theme::ThemeManager;
+use crate::transfer::{TransferKind, TransferManager, TransferUpdate};
+use crate::tunnel::TunnelManager;
+use crate::versions::VersionsView;
+use crate::wizard;
 use anyhow::Result;
 use crossterm::event::KeyCode;
 use serde_json::Value;
@@ -38,6 +59,16 @@ pub enum Mode {
     Describe,      // Viewing JSON details of selected item
     Notifications, // Notifications history panel
     ColumnConfig,  // Column visibility configuration
+    Diff,          // Structural diff of two resources
+    Yank,          // Copy-to-clipboard menu
+    Form,          // Multi-step input form (e.g. create wizard)
+    Serial,        // Serial port output of an instance
+    Firewall,      // Effective firewall of an instance
+    Tunnels,       // Running IAP tunnels
+    RunOutput,     // Output of a command run on instances
+    Preview,       // Content of a Cloud Storage object
+    Bucket,        // Settings and IAM bindings of a bucket
+    Versions,      // Generations of a Cloud Storage object
 }
 
 /// State for column configuration overlay
@@ -64,6 +95,8 @@ pub struct PendingAction {
     pub service: String,
     pub sdk_method: String,
     pub resource_id: String,
+    /// Parameters of the action (e.g. the zone of the resource)
+    pub params: Value,
     pub message: String,
     pub destructive: bool,
     pub selected_yes: bool,
@@ -75,6 +108,11 @@ pub struct ParentContext {
     pub resource_key: String,
     pub item: Value,
     pub display_name: String,
+    /// Entered by following a resource link rather than a sub-resource
+    /// shortcut, so no parent filter applies
+    pub via_link: bool,
+    /// Project and zone before following the link, restored on `b`
+    pub scope: Option<(String, String)>,
 }
 
 /// Pagination state
@@ -290,13 +328,314 @@ impl SelectorState {
     }
 }
 
+/// Search state within the describe view
+#[derive(Debug, Clone, Default)]
+pub struct DescribeSearch {
+    /// Whether the search query is being typed
+    pub input_active: bool,
+    /// Current query (case-insensitive)
+    pub query: String,
+    /// Pointers of matching lines
+    pub matches: Vec<String>,
+    /// Index of the current match
+    pub current: usize,
+}
+
+/// Value that can be copied from the yank menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YankField {
+    Name,
+    Id,
+    SelfLink,
+    InternalIp,
+    ConsoleUrl,
+    Json,
+}
+
+impl YankField {
+    pub const ALL: [YankField; 6] = [
+        Self::Name,
+        Self::Id,
+        Self::SelfLink,
+        Self::InternalIp,
+        Self::ConsoleUrl,
+        Self::Json,
+    ];
+
+    pub fn key(self) -> char {
+        match self {
+            Self::Name => 'n',
+            Self::Id => 'i',
+            Self::SelfLink => 's',
+            Self::InternalIp => 'p',
+            Self::ConsoleUrl => 'c',
+            Self::Json => 'J',
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Id => "ID",
+            Self::SelfLink => "selfLink",
+            Self::InternalIp => "Internal IP",
+            Self::ConsoleUrl => "Console URL",
+            Self::Json => "Full JSON",
+        }
+    }
+}
+
+/// State for the yank (copy) menu
+#[derive(Debug, Clone)]
+pub struct YankMenuState {
+    /// Available fields with a preview of the first value
+    pub options: Vec<(YankField, String)>,
+    pub selected: usize,
+    /// Number of rows the copy applies to
+    pub item_count: usize,
+}
+
+/// State for the diff view
+#[derive(Debug, Clone)]
+pub struct DiffState {
+    /// Label of the left side (e.g. resource name or "previous refresh")
+    pub left_label: String,
+    /// Label of the right side
+    pub right_label: String,
+    pub result: DiffResult,
+    pub scroll: usize,
+}
+
 /// Describe/detail view state
 #[derive(Debug, Clone, Default)]
 pub struct DescribeState {
     /// Scroll position in the describe view
     pub scroll: usize,
-    /// Cached data for describe view
+    /// Cached data for describe view (the list row)
     pub data: Option<Value>,
+    /// Full details fetched via the resource's detail_sdk_method
+    pub detail: Option<Value>,
+    /// Whether the detail payload is shown instead of the list row
+    pub show_detail: bool,
+    /// Line under the cursor
+    pub cursor: usize,
+    /// Visible lines (updated during render)
+    pub viewport_height: usize,
+    /// JSON pointers of folded containers
+    pub collapsed: HashSet<String>,
+    /// Render as JSON or YAML
+    pub format: DocFormat,
+    /// In-view search
+    pub search: DescribeSearch,
+}
+
+impl DescribeState {
+    /// Clear cached payloads
+    pub fn clear(&mut self) {
+        self.data = None;
+        self.detail = None;
+        self.show_detail = false;
+        self.reset_view();
+    }
+
+    /// Reset cursor, folds and search (format is kept between items)
+    fn reset_view(&mut self) {
+        self.scroll = 0;
+        self.cursor = 0;
+        self.collapsed.clear();
+        self.search = DescribeSearch::default();
+    }
+
+    /// Payload currently displayed (detail if available and selected)
+    pub fn current(&self) -> Option<&Value> {
+        if self.show_detail {
+            if let Some(ref detail) = self.detail {
+                return Some(detail);
+            }
+        }
+        self.data.as_ref()
+    }
+
+    /// Toggle between list and detail payloads (no-op without details)
+    pub fn toggle_detail(&mut self) {
+        if self.detail.is_some() {
+            self.show_detail = !self.show_detail;
+            self.reset_view();
+        }
+    }
+
+    /// Rendered lines of the current payload
+    pub fn lines(&self) -> Vec<DocLine> {
+        self.current()
+            .map(|v| render_lines(v, self.format, &self.collapsed))
+            .unwrap_or_default()
+    }
+
+    /// Line under the cursor
+    pub fn cursor_line(&self) -> Option<DocLine> {
+        self.lines().into_iter().nth(self.cursor)
+    }
+
+    /// Move the cursor by a relative amount, keeping it visible
+    pub fn move_cursor(&mut self, delta: isize) {
+        let count = self.lines().len();
+        if count == 0 {
+            return;
+        }
+        let target = (self.cursor as isize + delta).clamp(0, count as isize - 1);
+        self.cursor = target as usize;
+        self.ensure_cursor_visible();
+    }
+
+    pub fn cursor_to_top(&mut self) {
+        self.cursor = 0;
+        self.ensure_cursor_visible();
+    }
+
+    pub fn cursor_to_bottom(&mut self) {
+        self.cursor = self.lines().len().saturating_sub(1);
+        self.ensure_cursor_visible();
+    }
+
+    /// Adjust scroll so the cursor line is inside the viewport
+    pub fn ensure_cursor_visible(&mut self) {
+        let height = self.viewport_height.max(1);
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + height {
+            self.scroll = self.cursor + 1 - height;
+        }
+    }
+
+    /// Place the cursor on the line of a pointer, if rendered
+    fn move_cursor_to_pointer(&mut self, pointer: &str) {
+        if let Some(index) = line_index(&self.lines(), pointer) {
+            self.cursor = index;
+        }
+        self.ensure_cursor_visible();
+    }
+
+    /// Fold or unfold the container under the cursor (`za`). On a leaf line
+    /// the enclosing container is folded.
+    pub fn toggle_fold(&mut self) {
+        let Some(line) = self.cursor_line() else {
+            return;
+        };
+        let pointer = if line.is_foldable() {
+            line.pointer
+        } else {
+            parent_pointer(&line.pointer)
+        };
+        if pointer.is_empty() {
+            return;
+        }
+        if !self.collapsed.remove(&pointer) {
+            self.collapsed.insert(pointer.clone());
+        }
+        self.move_cursor_to_pointer(&pointer);
+    }
+
+    /// Unfold everything (`zR`)
+    pub fn expand_all(&mut self) {
+        let pointer = self.cursor_line().map(|l| l.pointer);
+        self.collapsed.clear();
+        if let Some(pointer) = pointer {
+            self.move_cursor_to_pointer(&pointer);
+        }
+    }
+
+    /// Fold every container (`zM`)
+    pub fn collapse_all(&mut self) {
+        if let Some(value) = self.current() {
+            self.collapsed = container_pointers(value).into_iter().collect();
+        }
+        self.cursor = 0;
+        self.scroll = 0;
+    }
+
+    /// Switch between JSON and YAML, keeping the cursor on the same value
+    pub fn toggle_format(&mut self) {
+        let pointer = self.cursor_line().map(|l| l.pointer);
+        self.format = self.format.toggle();
+        if !self.search.query.is_empty() {
+            self.update_search();
+        }
+        match pointer {
+            Some(pointer) => self.move_cursor_to_pointer(&pointer),
+            None => self.cursor_to_top(),
+        }
+    }
+
+    /// Dot path of the value under the cursor
+    pub fn cursor_path(&self) -> Option<String> {
+        self.cursor_line().map(|l| display_path(&l.pointer))
+    }
+
+    /// Text of the value under the cursor
+    pub fn cursor_value_text(&self) -> Option<String> {
+        let line = self.cursor_line()?;
+        let value = self.current()?.pointer(&line.pointer)?;
+        Some(value_to_text(value, self.format))
+    }
+
+    /// Recompute matches for the current query
+    pub fn update_search(&mut self) {
+        self.search.matches = match self.current() {
+            Some(value) => find_matches(value, self.format, &self.search.query),
+            None => Vec::new(),
+        };
+        self.search.current = 0;
+    }
+
+    /// Clear the search query and highlights
+    pub fn clear_search(&mut self) {
+        self.search = DescribeSearch::default();
+    }
+
+    /// Jump to the first match at or after the cursor
+    pub fn jump_to_first_match(&mut self) {
+        if self.search.matches.is_empty() {
+            return;
+        }
+        let full = self
+            .current()
+            .map(|v| render_lines(v, self.format, &HashSet::new()))
+            .unwrap_or_default();
+        let cursor_pointer = self.cursor_line().map(|l| l.pointer).unwrap_or_default();
+        let cursor_pos = line_index(&full, &cursor_pointer).unwrap_or(0);
+        self.search.current = self
+            .search
+            .matches
+            .iter()
+            .position(|p| line_index(&full, p).unwrap_or(0) >= cursor_pos)
+            .unwrap_or(0);
+        self.show_current_match();
+    }
+
+    /// Jump to the next (`n`) or previous (`N`) match, wrapping around
+    pub fn jump_to_match(&mut self, forward: bool) {
+        let count = self.search.matches.len();
+        if count == 0 {
+            return;
+        }
+        self.search.current = if forward {
+            (self.search.current + 1) % count
+        } else {
+            (self.search.current + count - 1) % count
+        };
+        self.show_current_match();
+    }
+
+    /// Unfold the ancestors of the current match and move the cursor to it
+    fn show_current_match(&mut self) {
+        let Some(pointer) = self.search.matches.get(self.search.current).cloned() else {
+            return;
+        };
+        for ancestor in ancestor_pointers(&pointer) {
+            self.collapsed.remove(&ancestor);
+        }
+        self.move_cursor_to_pointer(&pointer);
+    }
 }
 
 /// Main application state
@@ -335,6 +674,8 @@ pub struct App {
     // UI state
     pub loading: bool,
     pub error_message: Option<String>,
+    /// Transient info message shown in the status bar (e.g. "Copied ...")
+    pub status_message: Option<(String, std::time::Instant)>,
 
     // Auto-refresh
     pub last_refresh: std::time::Instant,
@@ -366,6 +707,147 @@ pub struct App {
 
     // Column configuration state
     pub column_config_state: Option<ColumnConfigState>,
+
+    // Diff view state
+    pub diff_state: Option<DiffState>,
+
+    // Yank menu state
+    pub yank_menu: Option<YankMenuState>,
+
+    // Open input form (create wizards)
+    pub form: Option<FormState>,
+
+    // Running operation chains (e.g. stop → setMachineType → start)
+    pub operation_chains: Vec<OperationChain>,
+
+    // Serial port output viewer
+    pub serial: Option<SerialState>,
+
+    // Effective firewall of an instance
+    pub firewall: Option<FirewallView>,
+
+    // IAP tunnels (child processes, killed on exit)
+    pub tunnels: TunnelManager,
+
+    // Command run on instances over SSH (processes killed when dropped)
+    pub run: Option<RunState>,
+
+    // Folder of the Cloud Storage object browser
+    pub objects: ObjectBrowser,
+
+    // Object downloads and uploads running in the background
+    pub transfers: TransferManager,
+
+    // Content preview of a Cloud Storage object
+    pub preview: Option<ObjectPreview>,
+
+    // Detail view of a bucket
+    pub bucket: Option<BucketView>,
+
+    // Generations of an object in a versioned bucket
+    pub versions: Option<VersionsView>,
+
+    // Labels/metadata/tags edit waiting for confirmation in the diff view
+    pub pending_edit: Option<PendingEdit>,
+
+    // Set after handing the terminal to an external program, so the next
+    // frame is redrawn from scratch
+    pub terminal_dirty: bool,
+
+    // Resource key the loaded items belong to, and the items from the
+    // previous refresh of that view (for :diff against the last snapshot)
+    pub items_resource_key: String,
+    pub previous_items: Vec<Value>,
+}
+
+/// Parameters locating an item for an action: its own zone or region, so
+/// actions work from aggregated ("all" zones) views, and the group of a
+/// managed instance
+pub fn action_params(item: &Value) -> Value {
+    let mut params = serde_json::Map::new();
+    for (param, field) in [
+        ("zone", "zone_short"),
+        ("region", "region_short"),
+        ("instance_group_manager", "instanceGroupManager"),
+    ] {
+        if let Some(value) = item.get(field).and_then(|v| v.as_str()) {
+            if !value.is_empty() {
+                params.insert(param.to_string(), Value::String(value.to_string()));
+            }
+        }
+    }
+    Value::Object(params)
+}
+
+/// Whether an item has deletion protection enabled
+pub fn deletion_protected(item: &Value) -> bool {
+    item.get("deletionProtection").and_then(|v| v.as_bool()) == Some(true)
+}
+
+/// Confirmation setting deletion protection on instances: enabled on all of
+/// them when any is unprotected, otherwise disabled. The target value is
+/// sent rather than flipping what the API returns at execution time.
+pub fn deletion_protection_action(items: &[&Value]) -> Option<PendingAction> {
+    let names: Vec<String> = items
+        .iter()
+        .map(|item| extract_json_value(item, "name"))
+        .filter(|name| name != "-")
+        .collect();
+    if names.is_empty() {
+        return None;
+    }
+    let enable = items.iter().any(|item| !deletion_protected(item));
+    let target = match names.as_slice() {
+        [name] => format!("'{}'", name),
+        names => format!("{} instances ({})", names.len(), names.join(", ")),
+    };
+    let message = if enable {
+        format!("Enable deletion protection on {}?", target)
+    } else {
+        format!(
+            "Disable deletion protection on {}? It can then be deleted.",
+            target
+        )
+    };
+
+    // A zone shared by all the instances (bulk actions get one set of params)
+    let mut params = action_params(items[0]);
+    if items
+        .iter()
+        .any(|item| action_params(item)["zone"] != params["zone"])
+    {
+        params = serde_json::json!({});
+    }
+    params["enable"] = Value::Bool(enable);
+
+    Some(PendingAction {
+        service: "compute".to_string(),
+        sdk_method: "set_deletion_protection".to_string(),
+        resource_id: names.join("\n"),
+        params,
+        message,
+        destructive: !enable,
+        selected_yes: false,
+    })
+}
+
+/// Explanation shown instead of the delete confirmation when some of the
+/// items to delete are protected (the API would reject the request)
+pub fn deletion_protection_refusal(names: &[String]) -> Option<String> {
+    match names {
+        [] => None,
+        [name] => Some(format!(
+            "'{}' has deletion protection enabled and cannot be deleted. \
+             Turn it off first with P (Deletion protection).",
+            name
+        )),
+        names => Some(format!(
+            "{} of the selected resources have deletion protection enabled ({}). \
+             Turn it off first with P (Deletion protection).",
+            names.len(),
+            names.join(", ")
+        )),
+    }
 }
 
 impl App {
@@ -436,6 +918,7 @@ impl App {
             pending_action: None,
             loading: false,
             error_message: None,
+            status_message: None,
             last_refresh: std::time::Instant::now(),
             config,
             last_key_press: None,
@@ -447,6 +930,23 @@ impl App {
             notifications_selected: 0,
             metrics_history: MetricsHistory::default(),
             column_config_state: None,
+            diff_state: None,
+            yank_menu: None,
+            form: None,
+            operation_chains: Vec::new(),
+            serial: None,
+            firewall: None,
+            tunnels: TunnelManager::default(),
+            run: None,
+            objects: ObjectBrowser::default(),
+            transfers: TransferManager::default(),
+            preview: None,
+            bucket: None,
+            versions: None,
+            pending_edit: None,
+            terminal_dirty: false,
+            items_resource_key: "compute-instances".to_string(),
+            previous_items: Vec::new(),
         }
     }
 
@@ -476,8 +976,14 @@ impl App {
     }
 
     pub fn get_available_commands(&self) -> Vec<String> {
+        // Parent-only resources complete only where they open as a sub-resource
+        let is_sub = |key: &str| {
+            self.current_resource()
+                .is_some_and(|r| r.sub_resources.iter().any(|s| s.resource_key == key))
+        };
         let mut commands: Vec<String> = get_all_resource_keys()
-            .iter()
+            .into_iter()
+            .filter(|key| get_resource(key).is_some_and(|r| !r.parent_only) || is_sub(key))
             .map(|s| s.to_string())
             .collect();
 
@@ -486,6 +992,9 @@ impl App {
         commands.push("zones".to_string());
         commands.push("notifications".to_string());
         commands.push("notifications clear".to_string());
+        commands.push("diff".to_string());
+        commands.push("diff prev".to_string());
+        commands.push("tunnels".to_string());
 
         // Add theme commands
         commands.push("theme".to_string());
@@ -521,7 +1030,10 @@ impl App {
         self.loading = true;
         self.error_message = None;
 
-        let filters = self.build_filters_from_context();
+        let mut filters = self.build_filters_from_context();
+        if self.current_resource_key == "storage-objects" {
+            filters.extend(self.objects.filters());
+        }
 
         match fetch_resources_paginated(
             &self.current_resource_key,
@@ -533,7 +1045,15 @@ impl App {
         {
             Ok(result) => {
                 let prev_selected = self.nav.selected;
-                self.items = result.items;
+
+                // Keep the previous refresh of the same view for :diff
+                let previous = std::mem::replace(&mut self.items, result.items);
+                if self.items_resource_key == self.current_resource_key {
+                    self.previous_items = previous;
+                } else {
+                    self.previous_items.clear();
+                    self.items_resource_key = self.current_resource_key.clone();
+                }
 
                 // Enrich VM instances with monitoring metrics
                 if self.current_resource_key == "compute-instances" {
@@ -602,7 +1122,7 @@ impl App {
     }
 
     fn build_filters_from_context(&self) -> Vec<ResourceFilter> {
-        let Some(parent) = &self.nav.parent_context else {
+        let Some(parent) = self.nav.parent_context.as_ref().filter(|p| !p.via_link) else {
             return Vec::new();
         };
 
@@ -681,25 +1201,6 @@ impl App {
         self.filtered_items.get(self.nav.selected)
     }
 
-    pub fn selected_item_json(&self) -> Option<String> {
-        if let Some(ref data) = self.describe.data {
-            return Some(serde_json::to_string_pretty(data).unwrap_or_default());
-        }
-        self.selected_item()
-            .map(|item| serde_json::to_string_pretty(item).unwrap_or_default())
-    }
-
-    pub fn describe_line_count(&self) -> usize {
-        self.selected_item_json()
-            .map(|s| s.lines().count())
-            .unwrap_or(0)
-    }
-
-    pub fn describe_scroll_to_bottom(&mut self, visible_lines: usize) {
-        let total = self.describe_line_count();
-        self.describe.scroll = total.saturating_sub(visible_lines);
-    }
-
     pub fn next(&mut self) {
         match self.mode {
             Mode::Projects => {
@@ -859,13 +1360,29 @@ impl App {
 
         self.mode = Mode::Describe;
         self.describe.scroll = 0;
-        self.describe.data = None;
+        self.describe.clear();
+
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
 
-        // For now, just show the list data
-        // TODO: Fetch detailed data via describe API
-        if let Some(item) = self.selected_item().cloned() {
-            self.describe.data = Some(item);
+        // Fetch full details when the resource defines a detail method,
+        // falling back to the list row if the call fails
+        match fetch_resource_detail(&self.current_resource_key, &self.client, &item).await {
+            Ok(Some(detail)) => {
+                self.describe.detail = Some(detail);
+                self.describe.show_detail = true;
+            },
+            Ok(None) => {},
+            Err(e) => {
+                self.error_message = Some(format!(
+                    "Details unavailable, showing list data: {}",
+                    crate::gcp::client::format_gcp_error(&e)
+                ));
+            },
         }
+
+        self.describe.data = Some(item);
     }
 
     pub fn enter_confirm_mode(&mut self, pending: PendingAction) {
@@ -878,6 +1395,27 @@ impl App {
         self.mode = Mode::Warning;
     }
 
+    /// Show a transient message in the status bar
+    pub fn set_status(&mut self, message: impl Into<String>) {
+        self.status_message = Some((message.into(), std::time::Instant::now()));
+    }
+
+    /// Current status message, if it has not expired yet
+    pub fn current_status(&self) -> Option<&str> {
+        self.status_message
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < self.notification_manager.toast_duration)
+            .map(|(msg, _)| msg.as_str())
+    }
+
+    /// Copy text to the clipboard (OSC 52) and report it in the status bar
+    pub fn copy_to_clipboard(&mut self, text: &str, what: &str) {
+        match crate::clipboard::copy(text) {
+            Ok(()) => self.set_status(format!("Copied {}", what)),
+            Err(e) => self.error_message = Some(format!("Copy failed: {}", e)),
+        }
+    }
+
     pub fn create_pending_action(
         &self,
         action: &crate::resource::ActionDef,
@@ -905,6 +1443,10 @@ impl App {
             service: self.current_resource()?.service.clone(),
             sdk_method: action.sdk_method.clone(),
             resource_id: resource_id.to_string(),
+            params: self
+                .selected_item()
+                .map(action_params)
+                .unwrap_or(Value::Null),
             message: format!("{} '{}'?", message, resource_name),
             destructive: config.destructive,
             selected_yes: config.default_yes,
@@ -1159,74 +1701,1600 @@ impl App {
     pub fn exit_mode(&mut self) {
         self.mode = Mode::Normal;
         self.pending_action = None;
-        self.describe.data = None;
+        self.describe.clear();
+        self.diff_state = None;
+        self.yank_menu = None;
+        self.form = None;
+        self.pending_edit = None;
+        self.serial = None;
+        self.firewall = None;
+        self.run = None;
+        self.preview = None;
+        self.bucket = None;
+        self.versions = None;
+    }
+
+    /// Close the confirmation dialog, going back to the bucket or versions
+    /// view it was opened from (reloaded after the action ran)
+    pub async fn finish_confirm(&mut self, reload: bool) {
+        if self.versions.is_some() {
+            self.pending_action = None;
+            self.mode = Mode::Versions;
+            if reload {
+                self.load_object_versions().await;
+            }
+            return;
+        }
+        if self.bucket.is_none() {
+            self.exit_mode();
+            return;
+        }
+        self.pending_action = None;
+        self.mode = Mode::Bucket;
+        if reload {
+            self.load_bucket_detail().await;
+        }
+    }
+
+    /// Close the warning dialog, going back to the bucket or versions view
+    /// it was shown over
+    pub fn dismiss_warning(&mut self) {
+        self.warning_message = None;
+        if self.versions.is_some() {
+            self.mode = Mode::Versions;
+        } else if self.bucket.is_some() {
+            self.mode = Mode::Bucket;
+        } else {
+            self.exit_mode();
+        }
     }
 
     // =========================================================================
-    // Resource Navigation
+    // Forms
     // =========================================================================
 
-    pub async fn navigate_to_resource(&mut self, resource_key: &str) -> Result<()> {
-        if get_resource(resource_key).is_none() {
-            self.error_message = Some(format!("Unknown resource: {}", resource_key));
-            return Ok(());
+    /// Open the form of a form action (see `ActionDef::form`)
+    pub async fn open_action_form(&mut self, sdk_method: &str) {
+        match sdk_method {
+            "insert_instance" => self.open_create_instance_form().await,
+            "set_machine_type" => self.open_resize_instance_form().await,
+            "create_snapshot" => self.open_snapshot_disk_form(),
+            "insert_disk" => self.open_disk_from_snapshot_form(),
+            "resize_disk" => self.open_resize_disk_form(),
+            "attach_disk" => self.open_attach_disk_form().await,
+            "detach_disk" => self.open_detach_disk(),
+            "resize_instance_group" => self.open_resize_group_form(),
+            "set_deletion_protection" => match deletion_protection_action(&self.action_items()) {
+                Some(pending) => self.enter_confirm_mode(pending),
+                None => self.show_warning("Instance name unknown"),
+            },
+            "insert_address" => self.open_reserve_address_form(false),
+            "insert_global_address" => self.open_reserve_address_form(true),
+            "promote_address" => self.open_promote_address_form().await,
+            "start_iap_tunnel" => self.open_tunnel_form(),
+            "run_command" => self.open_run_command_form(),
+            "upload_object" => self.open_upload_form(),
+            "scp_from_instance" => self.open_copy_files_form(ScpDirection::FromInstance),
+            "scp_to_instance" => self.open_copy_files_form(ScpDirection::ToInstance),
+            "insert_firewall" => self.open_firewall_form(false).await,
+            "patch_firewall" => self.open_firewall_form(true).await,
+            "edit_attributes" => self.edit_attributes(None).await,
+            other => self.error_message = Some(format!("No form defined for {}", other)),
         }
+    }
 
-        self.nav.clear_hierarchy();
-        self.current_resource_key = resource_key.to_string();
-        self.nav.reset();
-        self.filter_sort.reset();
-        self.mode = Mode::Normal;
-        self.selection.clear();
-
-        self.reset_pagination();
-        self.refresh_current().await?;
-        Ok(())
+    /// Run a view action (see `ActionDef::view`)
+    pub async fn open_view_action(&mut self, sdk_method: &str) {
+        match sdk_method {
+            "toggle_recursive" => self.toggle_objects_recursive().await,
+            "toggle_show_deleted" => self.toggle_objects_show_deleted().await,
+            "object_versions" => self.open_object_versions().await,
+            "download_object" => self.open_download_form(),
+            "preview_object" => self.open_object_preview().await,
+            "bucket_details" => self.open_bucket_detail().await,
+            other => self.error_message = Some(format!("No view defined for {}", other)),
+        }
     }
 
-    pub async fn navigate_to_sub_resource(&mut self, sub_resource_key: &str) -> Result<()> {
-        let Some(selected_item) = self.selected_item().cloned() else {
-            return Ok(());
+    /// Open the VM creation wizard, loading image families from the public
+    /// image projects
+    pub async fn open_create_instance_form(&mut self) {
+        let default_zone = if self.zone == "all" {
+            self.available_zones
+                .iter()
+                .find(|z| z.as_str() != "all")
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            self.zone.clone()
         };
+        let mut form = wizard::create_instance_form(&self.available_zones, &default_zone);
+
+        let requests = wizard::IMAGE_PROJECTS.iter().map(|project| {
+            let params = serde_json::json!({ "project": project });
+            let client = &self.client;
+            async move {
+                let response = invoke_sdk("compute", "list_images", client, &params).await;
+                (*project, response)
+            }
+        });
+        let mut images = Vec::new();
+        for (project, response) in futures::future::join_all(requests).await {
+            match response {
+                Ok(response) => images.extend(wizard::image_family_options(project, &response)),
+                Err(e) => tracing::warn!("Failed to list images of {}: {}", project, e),
+            }
+        }
+        if images.is_empty() {
+            form.error = Some("Could not load public images".to_string());
+        }
+        if let Some(field) = form.field_mut("image") {
+            field.set_options(images, wizard::DEFAULT_IMAGE);
+        }
 
-        let Some(current_resource) = self.current_resource() else {
-            return Ok(());
+        self.form = Some(form);
+        self.mode = Mode::Form;
+    }
+
+    /// Open the resize form for the selected instance, listing the machine
+    /// types of its zone
+    pub async fn open_resize_instance_form(&mut self) {
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
+        let mut form = match wizard::resize_instance_form(&item) {
+            Ok(form) => form,
+            Err(e) => {
+                self.show_warning(&e);
+                return;
+            },
         };
 
-        let is_valid = current_resource
-            .sub_resources
-            .iter()
-            .any(|s| s.resource_key == sub_resource_key);
+        let zone = form.context_str("zone").to_string();
+        let current = form.context_str("machine_type").to_string();
+        match invoke_sdk(
+            "compute",
+            "list_machine_types",
+            &self.client,
+            &serde_json::json!({ "zone": zone }),
+        )
+        .await
+        {
+            Ok(response) => {
+                if let Some(field) = form.field_mut("machine_type") {
+                    field.set_options(
+                        wizard::resize_machine_type_options(&response, &current),
+                        &current,
+                    );
+                }
+            },
+            Err(e) => {
+                self.error_message = Some(format!(
+                    "Failed to list machine types in {}: {}",
+                    zone,
+                    crate::gcp::client::format_gcp_error(&e)
+                ));
+                return;
+            },
+        }
 
-        if !is_valid {
-            self.error_message = Some(format!(
-                "{} is not a sub-resource of {}",
-                sub_resource_key, self.current_resource_key
-            ));
-            return Ok(());
+        self.form = Some(form);
+        self.mode = Mode::Form;
+    }
+
+    /// Open the snapshot form for the selected disk
+    pub fn open_snapshot_disk_form(&mut self) {
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+        match wizard::snapshot_disk_form(&item, &timestamp) {
+            Ok(form) => {
+                self.form = Some(form);
+                self.mode = Mode::Form;
+            },
+            Err(e) => self.show_warning(&e),
         }
+    }
 
-        let display_name = extract_json_value(&selected_item, &current_resource.name_field);
-        let id = extract_json_value(&selected_item, &current_resource.id_field);
-        let display = if display_name != "-" {
-            display_name
+    /// Open the form creating a disk from the selected snapshot
+    pub fn open_disk_from_snapshot_form(&mut self) {
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
+        let default_zone = if self.zone == "all" {
+            self.available_zones
+                .iter()
+                .find(|z| z.as_str() != "all")
+                .cloned()
+                .unwrap_or_default()
         } else {
-            id
+            self.zone.clone()
         };
-
-        if let Some(ctx) = self.nav.parent_context.take() {
-            self.nav.navigation_stack.push(ctx);
+        match wizard::disk_from_snapshot_form(&item, &self.available_zones, &default_zone) {
+            Ok(form) => {
+                self.form = Some(form);
+                self.mode = Mode::Form;
+            },
+            Err(e) => self.show_warning(&e),
         }
+    }
 
-        self.nav.parent_context = Some(ParentContext {
-            resource_key: self.current_resource_key.clone(),
-            item: selected_item,
-            display_name: display,
-        });
+    /// Open the form reserving a new external address, in the current
+    /// zone's region by default
+    pub fn open_reserve_address_form(&mut self, global: bool) {
+        let default_region = if self.zone == "all" {
+            String::new()
+        } else {
+            wizard::zone_region(&self.zone).to_string()
+        };
+        self.form = Some(wizard::reserve_address_form(
+            global,
+            &self.available_zones,
+            &default_region,
+        ));
+        self.mode = Mode::Form;
+    }
 
-        self.current_resource_key = sub_resource_key.to_string();
-        self.nav.reset();
-        self.filter_sort.reset();
+    /// Open the form promoting the selected instance's ephemeral IP
+    pub async fn open_promote_address_form(&mut self) {
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
+        let form = match wizard::promote_address_form(&item) {
+            Ok(form) => form,
+            Err(e) => {
+                self.show_warning(&e);
+                return;
+            },
+        };
+
+        // Refuse up front when the IP already is a static address rather
+        // than letting the insert fail
+        let params = serde_json::json!({
+            "region": form.context_str("region"),
+            "filter": format!("address=\"{}\"", form.context_str("address")),
+        });
+        match invoke_sdk("compute", "list_addresses", &self.client, &params).await {
+            Ok(addresses) => {
+                if let Some(message) = wizard::promote_address_refusal(&form, &addresses) {
+                    self.show_warning(&message);
+                    return;
+                }
+            },
+            Err(e) => tracing::warn!("Failed to list addresses before promoting: {}", e),
+        }
+        self.form = Some(form);
+        self.mode = Mode::Form;
+    }
+
+    /// Open the firewall rule create or edit form, loading the instances of
+    /// the current zone for the impact preview
+    pub async fn open_firewall_form(&mut self, edit: bool) {
+        let rule = if edit {
+            let Some(item) = self.selected_item().cloned() else {
+                return;
+            };
+            Some(item)
+        } else {
+            None
+        };
+        let instances =
+            match crate::resource::fetch_resources("compute-instances", &self.client, &[]).await {
+                Ok(instances) => instances,
+                Err(e) => {
+                    tracing::warn!("Failed to list instances for firewall impact: {}", e);
+                    Vec::new()
+                },
+            };
+
+        let form =
+            match rule {
+                Some(rule) => wizard::firewall::edit_firewall_form(&rule, &instances),
+                None => {
+                    let networks =
+                        match invoke_sdk("compute", "list_networks", &self.client, &Value::Null)
+                            .await
+                        {
+                            Ok(response) => wizard::firewall::network_options(&response),
+                            Err(e) => {
+                                self.error_message = Some(format!(
+                                    "Failed to list networks: {}",
+                                    crate::gcp::client::format_gcp_error(&e)
+                                ));
+                                return;
+                            },
+                        };
+                    // The network the view is filtered by, else "default"
+                    let default_network = self
+                        .nav
+                        .parent_context
+                        .as_ref()
+                        .filter(|p| p.resource_key == "compute-networks" && !p.via_link)
+                        .map(|p| extract_json_value(&p.item, "selfLink"))
+                        .or_else(|| {
+                            networks
+                                .iter()
+                                .find(|n| n.label == "default")
+                                .map(|n| n.value.clone())
+                        })
+                        .unwrap_or_default();
+                    Ok(wizard::firewall::create_firewall_form(
+                        networks,
+                        &default_network,
+                        &instances,
+                    ))
+                },
+            };
+        match form {
+            Ok(form) => {
+                self.form = Some(form);
+                self.mode = Mode::Form;
+            },
+            Err(e) => self.show_warning(&e),
+        }
+    }
+
+    /// Open the resize form for the selected disk
+    pub fn open_resize_disk_form(&mut self) {
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
+        match wizard::resize_disk_form(&item) {
+            Ok(form) => {
+                self.form = Some(form);
+                self.mode = Mode::Form;
+            },
+            Err(e) => self.show_warning(&e),
+        }
+    }
+
+    /// Open the IAP tunnel form for the selected instance
+    pub fn open_tunnel_form(&mut self) {
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
+        match wizard::tunnel::tunnel_form(&item, crate::tunnel::free_local_port()) {
+            Ok(form) => {
+                self.form = Some(form);
+                self.mode = Mode::Form;
+            },
+            Err(e) => self.show_warning(&e),
+        }
+    }
+
+    /// Open the run command form for the selected instances (or the
+    /// current one without a selection)
+    pub fn open_run_command_form(&mut self) {
+        let mut items = self.selected_items();
+        if items.is_empty() {
+            items.extend(self.selected_item());
+        }
+        match wizard::run::run_command_form(&items, self.config.ssh.use_iap) {
+            Ok(form) => {
+                self.form = Some(form);
+                self.mode = Mode::Form;
+            },
+            Err(e) => self.show_warning(&e),
+        }
+    }
+
+    /// Open the scp form for the selected instance
+    pub fn open_copy_files_form(&mut self, direction: ScpDirection) {
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
+        match wizard::scp::copy_files_form(&item, direction, self.config.ssh.use_iap) {
+            Ok(form) => {
+                self.form = Some(form);
+                self.mode = Mode::Form;
+            },
+            Err(e) => self.show_warning(&e),
+        }
+    }
+
+    pub fn open_resize_group_form(&mut self) {
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
+        match wizard::resize_group_form(&item) {
+            Ok(form) => {
+                self.form = Some(form);
+                self.mode = Mode::Form;
+            },
+            Err(e) => self.show_warning(&e),
+        }
+    }
+
+    /// Open the attach form for the selected disk, listing the instances of
+    /// its zone
+    pub async fn open_attach_disk_form(&mut self) {
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
+        let zone = extract_json_value(&item, "zone_short");
+        let instances = match invoke_sdk(
+            "compute",
+            "list_instances",
+            &self.client,
+            &serde_json::json!({ "zone": zone }),
+        )
+        .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                self.error_message = Some(format!(
+                    "Failed to list instances in {}: {}",
+                    zone,
+                    crate::gcp::client::format_gcp_error(&e)
+                ));
+                return;
+            },
+        };
+        match wizard::attach_disk_form(&item, &instances) {
+            Ok(form) => {
+                self.form = Some(form);
+                self.mode = Mode::Form;
+            },
+            Err(e) => self.show_warning(&e),
+        }
+    }
+
+    /// Detach the selected disk: from the parent instance when viewing an
+    /// instance's disks, otherwise from its only user or a chosen one
+    pub fn open_detach_disk(&mut self) {
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
+        let parent_instance = self
+            .nav
+            .parent_context
+            .as_ref()
+            .filter(|p| p.resource_key == "compute-instances")
+            .map(|p| extract_json_value(&p.item, "name"));
+        match wizard::detach_disk(&item, parent_instance.as_deref()) {
+            Ok(wizard::DetachDisk::Confirm(pending)) => self.enter_confirm_mode(pending),
+            Ok(wizard::DetachDisk::Choose(form)) => {
+                self.form = Some(form);
+                self.mode = Mode::Form;
+            },
+            Err(e) => self.show_warning(&e),
+        }
+    }
+
+    /// Load the machine types and subnets available in the form's zone
+    async fn load_form_zone_options(&mut self) {
+        let Some(ref form) = self.form else {
+            return;
+        };
+        let zone = form.value("zone").to_string();
+        let region = wizard::zone_region(&zone).to_string();
+
+        let machine_types = invoke_sdk(
+            "compute",
+            "list_machine_types",
+            &self.client,
+            &serde_json::json!({ "zone": zone }),
+        )
+        .await;
+        let subnets = invoke_sdk(
+            "compute",
+            "list_subnetworks",
+            &self.client,
+            &serde_json::json!({ "region": region }),
+        )
+        .await;
+
+        let Some(ref mut form) = self.form else {
+            return;
+        };
+        match machine_types {
+            Ok(response) => {
+                let current = form.value("machine_type").to_string();
+                let preferred = if current.is_empty() {
+                    wizard::DEFAULT_MACHINE_TYPE.to_string()
+                } else {
+                    current
+                };
+                if let Some(field) = form.field_mut("machine_type") {
+                    field.set_options(wizard::machine_type_options(&response), &preferred);
+                }
+            },
+            Err(e) => {
+                form.error = Some(format!(
+                    "Failed to list machine types in {}: {}",
+                    zone,
+                    crate::gcp::client::format_gcp_error(&e)
+                ));
+            },
+        }
+        match subnets {
+            Ok(response) => {
+                if let Some(field) = form.field_mut("subnet") {
+                    field.set_options(wizard::subnet_options(&response), "");
+                }
+            },
+            Err(e) => {
+                tracing::warn!("Failed to list subnetworks in {}: {}", region, e);
+                if let Some(field) = form.field_mut("subnet") {
+                    field.set_options(wizard::subnet_options(&Value::Null), "");
+                }
+            },
+        }
+    }
+
+    /// Advance the form: next field, then next step, then the request preview
+    pub async fn advance_form(&mut self) {
+        let Some(ref mut form) = self.form else {
+            return;
+        };
+        if form.stage != FormStage::Edit {
+            return;
+        }
+        form.error = None;
+        if let Some(field) = form.fields.get(form.focused) {
+            if let Err(e) = field.validate() {
+                form.error = Some(e);
+                return;
+            }
+        }
+        if form.move_focus(true) {
+            return;
+        }
+
+        let was_last = form.is_last_step();
+        let step = form.step;
+        if let Err(e) = form.next_step() {
+            form.error = Some(e);
+            return;
+        }
+
+        if was_last {
+            // Single actions go through the regular confirmation dialog
+            let confirm = match form.purpose {
+                FormPurpose::ResizeDisk => Some(wizard::resize_disk_action(form)),
+                FormPurpose::AttachDisk => Some(wizard::attach_disk_action(form)),
+                FormPurpose::DetachDisk => Some(wizard::detach_disk_action(form)),
+                FormPurpose::ResizeGroup => Some(wizard::resize_group_action(form)),
+                _ => None,
+            };
+            if form.purpose == FormPurpose::Tunnel {
+                self.start_tunnel_from_form();
+                return;
+            }
+            if form.purpose == FormPurpose::RunCommand {
+                self.start_run_from_form();
+                return;
+            }
+            if form.purpose == FormPurpose::CopyFiles {
+                self.copy_files_from_form();
+                return;
+            }
+            if matches!(
+                form.purpose,
+                FormPurpose::DownloadObjects | FormPurpose::UploadObject
+            ) {
+                self.start_transfers_from_form();
+                return;
+            }
+            match confirm {
+                Some(Ok(pending)) => {
+                    self.form = None;
+                    self.enter_confirm_mode(pending);
+                    return;
+                },
+                Some(Err(e)) => {
+                    form.error = Some(e);
+                    return;
+                },
+                None => {},
+            }
+
+            let body = match form.purpose {
+                FormPurpose::CreateInstance => wizard::instance_insert_body(form),
+                FormPurpose::ResizeInstance => wizard::resize_plan(form),
+                FormPurpose::SnapshotDisk => wizard::snapshot_body(form),
+                FormPurpose::CreateDiskFromSnapshot => wizard::disk_from_snapshot_body(form),
+                FormPurpose::ReserveAddress => wizard::reserve_address_body(form),
+                FormPurpose::PromoteAddress => wizard::promote_address_body(form),
+                FormPurpose::CreateFirewall | FormPurpose::EditFirewall => {
+                    wizard::firewall::firewall_body(form)
+                },
+                FormPurpose::ResizeDisk
+                | FormPurpose::AttachDisk
+                | FormPurpose::DetachDisk
+                | FormPurpose::ResizeGroup
+                | FormPurpose::Tunnel
+                | FormPurpose::RunCommand
+                | FormPurpose::CopyFiles
+                | FormPurpose::DownloadObjects
+                | FormPurpose::UploadObject => {
+                    return;
+                },
+            };
+            match body {
+                Ok(body) => {
+                    form.notes = wizard::preview_notes(form, &body);
+                    form.stage = FormStage::Preview(body);
+                },
+                Err(e) => form.error = Some(e),
+            }
+        } else if form.purpose == FormPurpose::CreateInstance && step == 0 {
+            self.load_form_zone_options().await;
+        }
+    }
+
+    /// Go back from the preview or to the previous step, closing the form
+    /// from its first step
+    pub fn form_back(&mut self) {
+        let Some(ref mut form) = self.form else {
+            return;
+        };
+        form.error = None;
+        if matches!(form.stage, FormStage::Preview(_)) {
+            form.stage = FormStage::Edit;
+        } else if !form.prev_step() {
+            self.exit_mode();
+        }
+    }
+
+    /// Submit the previewed request and track the returned operation
+    pub async fn submit_form(&mut self) -> Result<()> {
+        let Some(form) = self.form.take() else {
+            return Ok(());
+        };
+        let FormStage::Preview(ref body) = form.stage else {
+            self.form = Some(form);
+            return Ok(());
+        };
+
+        let (service, method, resource_id, params) = match form.purpose {
+            FormPurpose::CreateInstance => (
+                "compute",
+                "insert_instance",
+                form.value("name").to_string(),
+                serde_json::json!({ "zone": form.value("zone"), "body": body }),
+            ),
+            FormPurpose::SnapshotDisk => (
+                "compute",
+                "create_snapshot",
+                form.context_str("name").to_string(),
+                serde_json::json!({ "zone": form.context_str("zone"), "body": body }),
+            ),
+            FormPurpose::CreateDiskFromSnapshot => (
+                "compute",
+                "insert_disk",
+                form.value("name").to_string(),
+                serde_json::json!({ "zone": form.value("zone"), "body": body }),
+            ),
+            FormPurpose::ReserveAddress if form.context["global"] == true => (
+                "compute",
+                "insert_global_address",
+                form.value("name").to_string(),
+                serde_json::json!({ "body": body }),
+            ),
+            FormPurpose::ReserveAddress => (
+                "compute",
+                "insert_address",
+                form.value("name").to_string(),
+                serde_json::json!({ "region": form.value("region"), "body": body }),
+            ),
+            FormPurpose::PromoteAddress => (
+                "compute",
+                "insert_address",
+                form.value("name").to_string(),
+                serde_json::json!({ "region": form.context_str("region"), "body": body }),
+            ),
+            FormPurpose::CreateFirewall => (
+                "compute",
+                "insert_firewall",
+                form.value("name").to_string(),
+                serde_json::json!({ "body": body }),
+            ),
+            FormPurpose::EditFirewall => (
+                "compute",
+                "patch_firewall",
+                form.context_str("name").to_string(),
+                serde_json::json!({ "body": body }),
+            ),
+            FormPurpose::ResizeDisk
+            | FormPurpose::AttachDisk
+            | FormPurpose::DetachDisk
+            | FormPurpose::ResizeGroup
+            | FormPurpose::Tunnel
+            | FormPurpose::RunCommand
+            | FormPurpose::CopyFiles
+            | FormPurpose::DownloadObjects
+            | FormPurpose::UploadObject => {
+                // Confirmed through the confirm dialog or started directly
+                // instead of a preview
+                return Ok(());
+            },
+            FormPurpose::ResizeInstance => {
+                self.exit_mode();
+                self.start_operation_chain(wizard::resize_chain(&form))
+                    .await;
+                return Ok(());
+            },
+        };
+
+        let notification_id = self.create_operation_notification(method, service, &resource_id);
+        match execute_action(service, method, &self.client, &resource_id, &params).await {
+            Ok(response) => {
+                let operation_url = crate::gcp::client::extract_operation_url(&response);
+                self.mark_notification_in_progress(notification_id, operation_url.clone());
+                if operation_url.is_none() {
+                    self.mark_notification_success(notification_id);
+                }
+                self.exit_mode();
+                self.refresh_current().await?;
+            },
+            Err(e) => {
+                let error_msg = crate::gcp::client::format_gcp_error(&e);
+                self.mark_notification_error(notification_id, error_msg.clone());
+                // Keep the form open so the request can be fixed and resubmitted
+                let mut form = form;
+                form.stage = FormStage::Edit;
+                form.error = Some(error_msg);
+                self.form = Some(form);
+            },
+        }
+        Ok(())
+    }
+
+    // =========================================================================
+    // Attribute editing ($EDITOR)
+    // =========================================================================
+
+    /// Edit the labels (and for instances metadata and tags) of the selected
+    /// item in `$EDITOR`, then preview the changes as a diff. `text` reopens
+    /// a previous edit.
+    pub async fn edit_attributes(&mut self, text: Option<String>) {
+        let Some(kind) = EditKind::from_resource_key(&self.current_resource_key) else {
+            return;
+        };
+        let Some(item) = self
+            .pending_edit
+            .take()
+            .map(|pending| pending.item)
+            .or_else(|| self.selected_item().cloned())
+        else {
+            return;
+        };
+        let name = extract_json_value(&item, "name");
+        let original = edit::editable_document(kind, &item);
+
+        let mut text = match text {
+            Some(text) => text,
+            None => match edit::to_yaml(kind, &name, &original) {
+                Ok(text) => text,
+                Err(e) => {
+                    self.error_message = Some(e.to_string());
+                    return;
+                },
+            },
+        };
+
+        // Reopen the editor on invalid input until it parses or is left
+        // unchanged
+        let edited = loop {
+            let saved = match self.run_editor(&text) {
+                Ok(saved) => saved,
+                Err(e) => {
+                    self.exit_mode();
+                    self.error_message = Some(e.to_string());
+                    return;
+                },
+            };
+            match edit::parse_document(kind, &saved) {
+                Ok(doc) => {
+                    text = saved;
+                    break doc;
+                },
+                Err(e) if saved == text => {
+                    self.exit_mode();
+                    self.error_message = Some(format!("Edit cancelled: {}", e));
+                    return;
+                },
+                Err(e) => text = edit::with_error(&saved, &e),
+            }
+        };
+
+        if edited == original {
+            self.exit_mode();
+            self.set_status(format!("No changes to {}", name));
+            return;
+        }
+        let updates = match edit::plan_updates(kind, &item, &original, &edited) {
+            Ok(updates) => updates,
+            Err(e) => {
+                self.exit_mode();
+                self.error_message = Some(e);
+                return;
+            },
+        };
+
+        self.diff_state = Some(DiffState {
+            left_label: format!("{} (current)", name),
+            right_label: format!("{} (edited)", name),
+            result: diff_values(&original, &edited, &[]),
+            scroll: 0,
+        });
+        self.pending_edit = Some(PendingEdit {
+            name,
+            item,
+            text,
+            updates,
+        });
+        self.mode = Mode::Diff;
+    }
+
+    /// Reopen the editor on the previewed edit
+    pub async fn reedit_attributes(&mut self) {
+        let text = self.pending_edit.as_ref().map(|p| p.text.clone());
+        if text.is_some() {
+            self.edit_attributes(text).await;
+        }
+    }
+
+    /// Run the editor with the TUI suspended
+    fn run_editor(&mut self, text: &str) -> Result<String> {
+        let guard = crate::shell::TerminalGuard::prepare()?;
+        let result = edit::run_editor(text);
+        guard.restore()?;
+        self.terminal_dirty = true;
+        result
+    }
+
+    /// Apply the previewed edit, one fingerprint-guarded call per changed
+    /// section
+    pub async fn apply_edit(&mut self) -> Result<()> {
+        let Some(pending) = self.pending_edit.take() else {
+            return Ok(());
+        };
+        self.exit_mode();
+
+        let mut errors = Vec::new();
+        for update in &pending.updates {
+            let notification_id = self.create_operation_notification(
+                update.sdk_method,
+                update.service,
+                &pending.name,
+            );
+            match execute_action(
+                update.service,
+                update.sdk_method,
+                &self.client,
+                &pending.name,
+                &update.params,
+            )
+            .await
+            {
+                Ok(response) => {
+                    let operation_url = crate::gcp::client::extract_operation_url(&response);
+                    self.mark_notification_in_progress(notification_id, operation_url.clone());
+                    if operation_url.is_none() {
+                        self.mark_notification_success(notification_id);
+                    }
+                },
+                Err(e) => {
+                    let message = edit::update_error(update, &pending.name, &e);
+                    self.mark_notification_error(notification_id, message.clone());
+                    errors.push(message);
+                },
+            }
+        }
+
+        if errors.is_empty() {
+            self.set_status(format!("Updated {}", pending.name));
+        } else {
+            self.error_message = Some(errors.join(" "));
+        }
+        self.refresh_current().await
+    }
+
+    // =========================================================================
+    // Serial port output
+    // =========================================================================
+
+    /// Open the serial output viewer for an instance (console port)
+    pub async fn open_serial_output(&mut self, item: &Value) {
+        let name = extract_json_value(item, "name");
+        let zone = extract_json_value(item, "zone_short");
+        if name == "-" || zone == "-" {
+            return;
+        }
+        self.serial = Some(SerialState::new(&name, &zone, 1));
+        self.mode = Mode::Serial;
+        self.load_serial_output().await;
+    }
+
+    /// Read the output written since the last read
+    pub async fn load_serial_output(&mut self) {
+        let Some(ref mut state) = self.serial else {
+            return;
+        };
+        state.last_poll = std::time::Instant::now();
+        let start = state.start_offset();
+        let params = serde_json::json!({
+            "name": state.instance,
+            "zone": state.zone,
+            "port": state.port.to_string(),
+            "start": start.to_string(),
+        });
+
+        let response = invoke_sdk("compute", "get_serial_port_output", &self.client, &params).await;
+        let Some(ref mut state) = self.serial else {
+            return;
+        };
+        match response {
+            Ok(response) => state.append_response(start, &response),
+            Err(e) => {
+                state.error = Some(format!(
+                    "Failed to read serial port {}: {}",
+                    state.port,
+                    crate::gcp::client::format_gcp_error(&e)
+                ));
+            },
+        }
+    }
+
+    /// Poll for new output in follow mode
+    pub async fn poll_serial_output(&mut self) {
+        if self.mode == Mode::Serial && self.serial.as_ref().is_some_and(|s| s.poll_due()) {
+            self.load_serial_output().await;
+        }
+    }
+
+    /// Show another serial port from the beginning
+    pub async fn switch_serial_port(&mut self, port: u8) {
+        if let Some(ref mut state) = self.serial {
+            if state.port == port {
+                return;
+            }
+            state.switch_port(port);
+        }
+        self.load_serial_output().await;
+    }
+
+    /// Save the serial output to a file in the current directory
+    pub fn save_serial_output(&mut self) {
+        let Some(ref state) = self.serial else {
+            return;
+        };
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+        let path = state.file_name(&timestamp);
+        let count = state.lines.len();
+        match std::fs::write(&path, state.text()) {
+            Ok(()) => self.set_status(format!("Saved {} lines to {}", count, path)),
+            Err(e) => self.error_message = Some(format!("Failed to save {}: {}", path, e)),
+        }
+    }
+
+    // =========================================================================
+    // Effective firewall
+    // =========================================================================
+
+    /// Open the effective firewall of an instance's first network interface
+    pub async fn open_effective_firewall(&mut self, item: &Value) {
+        let name = extract_json_value(item, "name");
+        let zone = extract_json_value(item, "zone_short");
+        if name == "-" || zone == "-" {
+            return;
+        }
+        let nic = item.pointer("/networkInterfaces/0");
+        let field = |key: &str| {
+            nic.and_then(|n| n.get(key))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let nic_name = match field("name") {
+            n if n.is_empty() => "nic0".to_string(),
+            n => n,
+        };
+        self.firewall = Some(FirewallView::new(
+            &name,
+            &zone,
+            &nic_name,
+            &field("network"),
+        ));
+        self.mode = Mode::Firewall;
+        self.load_effective_firewall().await;
+    }
+
+    /// Read the rules applying to the interface
+    pub async fn load_effective_firewall(&mut self) {
+        let Some(ref mut view) = self.firewall else {
+            return;
+        };
+        view.loading = true;
+        let mut params = serde_json::json!({
+            "name": view.instance,
+            "zone": view.zone,
+            "network_interface": view.network_interface,
+        });
+        if !view.network.is_empty() {
+            params["network"] = Value::String(view.network.clone());
+        }
+
+        let response =
+            invoke_sdk("compute", "get_effective_firewalls", &self.client, &params).await;
+        let Some(ref mut view) = self.firewall else {
+            return;
+        };
+        match response {
+            Ok(response) => {
+                let policies_first = response
+                    .get("networkFirewallPolicyEnforcementOrder")
+                    .and_then(|v| v.as_str())
+                    == Some("BEFORE_CLASSIC_FIREWALL");
+                view.set_rules(crate::firewall::effective_rules(&response, policies_first));
+            },
+            Err(e) => {
+                view.loading = false;
+                view.error = Some(format!(
+                    "Failed to read effective firewall: {}",
+                    crate::gcp::client::format_gcp_error(&e)
+                ));
+            },
+        }
+    }
+
+    // =========================================================================
+    // IAP tunnels
+    // =========================================================================
+
+    /// Start the tunnel described by the tunnel form, keeping the form open
+    /// if it cannot be started
+    fn start_tunnel_from_form(&mut self) {
+        let Some(ref mut form) = self.form else {
+            return;
+        };
+        let spec = match wizard::tunnel::tunnel_spec(form, &self.project) {
+            Ok(spec) => spec,
+            Err(e) => {
+                form.error = Some(e);
+                return;
+            },
+        };
+        let label = format!("localhost:{} -> {}", spec.local_port, spec.target());
+        match self.tunnels.start(spec) {
+            Ok(_) => {
+                self.exit_mode();
+                self.set_status(format!("Tunnel {} started (:tunnels to manage)", label));
+            },
+            Err(e) => form.error = Some(e.to_string()),
+        }
+    }
+
+    pub fn enter_tunnels_mode(&mut self) {
+        self.tunnels.select_by(0);
+        self.mode = Mode::Tunnels;
+    }
+
+    /// Report tunnels whose process exited, all of them when several exited
+    /// since the last poll
+    pub fn poll_tunnels(&mut self) {
+        let exited = self.tunnels.poll();
+        if !exited.is_empty() {
+            self.error_message = Some(exited.join("; "));
+        }
+    }
+
+    pub fn stop_selected_tunnel(&mut self) {
+        let Some(id) = self.tunnels.selected_id() else {
+            return;
+        };
+        if let Some(spec) = self.tunnels.stop(id) {
+            self.set_status(format!("Tunnel to {} stopped", spec.target()));
+        }
+    }
+
+    pub fn restart_selected_tunnel(&mut self) {
+        let Some(id) = self.tunnels.selected_id() else {
+            return;
+        };
+        if let Err(e) = self.tunnels.restart(id) {
+            self.error_message = Some(format!("Failed to restart tunnel: {}", e));
+        }
+    }
+
+    // =========================================================================
+    // Copy files (scp)
+    // =========================================================================
+
+    /// Run the copy of the scp form with the TUI suspended, reporting the
+    /// result through a notification
+    fn copy_files_from_form(&mut self) {
+        let Some(ref mut form) = self.form else {
+            return;
+        };
+        let opts = wizard::scp::scp_options(form, &self.project);
+        if let Err(e) = opts.args() {
+            form.error = Some(e.to_string());
+            return;
+        }
+        self.exit_mode();
+
+        let (method, label) = match opts.direction {
+            ScpDirection::FromInstance => (
+                "scp_from_instance",
+                format!(
+                    "{}:{} -> {}",
+                    opts.instance, opts.remote_path, opts.local_path
+                ),
+            ),
+            ScpDirection::ToInstance => (
+                "scp_to_instance",
+                format!(
+                    "{} -> {}:{}",
+                    opts.local_path, opts.instance, opts.remote_path
+                ),
+            ),
+        };
+        let notification_id = self.create_operation_notification(method, "compute", &label);
+        let result = shell::execute_with_terminal_handling(|| shell::scp_instance(&opts));
+        self.terminal_dirty = true;
+
+        let iap_label = if opts.use_iap { " (IAP)" } else { "" };
+        match result {
+            Ok(ShellResult::Success) => self.mark_notification_success(notification_id),
+            Ok(ShellResult::Failed(code)) => self.mark_notification_error(
+                notification_id,
+                format!("scp{} exited with code {}", iap_label, code),
+            ),
+            Ok(ShellResult::Error(msg)) => self.mark_notification_error(notification_id, msg),
+            Err(e) => self
+                .mark_notification_error(notification_id, format!("scp{} error: {}", iap_label, e)),
+        }
+    }
+
+    // =========================================================================
+    // Run a command on instances
+    // =========================================================================
+
+    /// Start the command of the run form on its instances
+    fn start_run_from_form(&mut self) {
+        let Some(ref mut form) = self.form else {
+            return;
+        };
+        let parallelism = validate_parallelism(form.value("parallelism")).unwrap_or(1);
+        let state = RunState::start(
+            "gcloud",
+            wizard::run::run_targets(form),
+            &self.project,
+            form.value("command"),
+            form.is_on("iap"),
+            &self.config.ssh.extra_args,
+            parallelism,
+        );
+        match state {
+            Ok(state) => {
+                self.exit_mode();
+                self.run = Some(state);
+                self.mode = Mode::RunOutput;
+            },
+            Err(e) => form.error = Some(e.to_string()),
+        }
+    }
+
+    /// Collect the output of the running command
+    pub fn poll_run_output(&mut self) {
+        let Some(ref mut run) = self.run else {
+            return;
+        };
+        let was_done = run.is_done();
+        run.poll();
+        if run.follow {
+            run.scroll_to_bottom();
+        }
+        if !was_done && run.is_done() {
+            let message = format!(
+                "Command finished on {} instances, {} failed",
+                run.hosts.len(),
+                run.failed_count()
+            );
+            self.set_status(message);
+        }
+    }
+
+    /// Save the output of all hosts to a file in the current directory
+    pub fn save_run_output(&mut self) {
+        let Some(ref run) = self.run else {
+            return;
+        };
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+        let path = run.file_name(&timestamp);
+        match std::fs::write(&path, run.text()) {
+            Ok(()) => self.set_status(format!(
+                "Saved the output of {} instances to {}",
+                run.hosts.len(),
+                path
+            )),
+            Err(e) => self.error_message = Some(format!("Failed to save {}: {}", path, e)),
+        }
+    }
+
+    // =========================================================================
+    // Operation chains
+    // =========================================================================
+
+    /// Start an operation chain with its first step
+    pub async fn start_operation_chain(&mut self, chain: OperationChain) {
+        self.operation_chains.push(chain);
+        self.run_chain_step(self.operation_chains.len() - 1).await;
+    }
+
+    /// Execute the current step of a chain, continuing while steps complete
+    /// immediately. Chains poll their own operations (rather than the
+    /// notification manager) so they progress even with notifications off.
+    async fn run_chain_step(&mut self, index: usize) {
+        loop {
+            let chain = &self.operation_chains[index];
+            let Some(step) = chain.current_step().cloned() else {
+                self.finish_chain(index).await;
+                return;
+            };
+            let service = chain.service.clone();
+            let resource_id = chain.resource_id.clone();
+            let (position, total) = (chain.current + 1, chain.steps.len());
+
+            let notification_id =
+                self.create_operation_notification(&step.sdk_method, &service, &resource_id);
+            if !notification_id.is_nil() {
+                self.notification_manager
+                    .set_chain_step(notification_id, position, total);
+            }
+
+            let result = execute_action(
+                &service,
+                &step.sdk_method,
+                &self.client,
+                &resource_id,
+                &step.params,
+            )
+            .await;
+
+            match result {
+                Ok(response) => {
+                    self.mark_notification_in_progress(notification_id, None);
+                    let operation_url = crate::gcp::client::extract_operation_url(&response);
+                    let chain = &mut self.operation_chains[index];
+                    chain.notification_id = notification_id;
+                    if operation_url.is_some() {
+                        chain.operation_url = operation_url;
+                        chain.last_poll = std::time::Instant::now();
+                        return;
+                    }
+                    self.mark_notification_success(notification_id);
+                    if !self.operation_chains[index].advance() {
+                        self.finish_chain(index).await;
+                        return;
+                    }
+                },
+                Err(e) => {
+                    self.operation_chains[index].notification_id = notification_id;
+                    self.fail_chain(index, &crate::gcp::client::format_gcp_error(&e));
+                    return;
+                },
+            }
+        }
+    }
+
+    /// Poll the running step of each chain and start the next step when done
+    pub async fn poll_operation_chains(&mut self) {
+        let interval = self.notification_manager.poll_interval;
+
+        // Reverse order: finished chains are removed while iterating
+        for index in (0..self.operation_chains.len()).rev() {
+            let chain = &mut self.operation_chains[index];
+            let Some(url) = chain.operation_url.clone() else {
+                continue;
+            };
+            if chain.last_poll.elapsed() < interval {
+                continue;
+            }
+            chain.last_poll = std::time::Instant::now();
+
+            match self.client.poll_operation(&url).await {
+                Ok(OperationStatus::Done) => {
+                    let notification_id = self.operation_chains[index].notification_id;
+                    self.mark_notification_success(notification_id);
+                    let _ = self.refresh_current().await;
+                    if self.operation_chains[index].advance() {
+                        self.run_chain_step(index).await;
+                    } else {
+                        self.finish_chain(index).await;
+                    }
+                },
+                Ok(OperationStatus::Failed(error)) => self.fail_chain(index, &error),
+                Ok(_) => {},
+                Err(e) => tracing::warn!("Failed to poll chained operation: {}", e),
+            }
+        }
+    }
+
+    async fn finish_chain(&mut self, index: usize) {
+        let chain = self.operation_chains.remove(index);
+        self.set_status(chain.summary);
+        let _ = self.refresh_current().await;
+    }
+
+    /// Stop a chain at its current step, explaining the state left behind
+    fn fail_chain(&mut self, index: usize, error: &str) {
+        let chain = self.operation_chains.remove(index);
+        self.mark_notification_error(chain.notification_id, error.to_string());
+        self.error_message = Some(chain.failure_message(error));
+    }
+
+    // =========================================================================
+    // Yank (copy to clipboard)
+    // =========================================================================
+
+    /// Rows a copy applies to: the multi-selection, or the current row
+    fn yank_targets(&self) -> Vec<&Value> {
+        let mut indices: Vec<usize> = self.selection.indices.iter().copied().collect();
+        if indices.is_empty() {
+            return self.selected_item().into_iter().collect();
+        }
+        indices.sort_unstable();
+        indices
+            .into_iter()
+            .filter_map(|idx| self.filtered_items.get(idx))
+            .collect()
+    }
+
+    /// Console URL of an item, using its own zone/location when known
+    pub fn console_url_for(&self, item: &Value) -> String {
+        let Some(resource) = self.current_resource() else {
+            return String::new();
+        };
+        let name = extract_json_value(item, &resource.name_field);
+        let zone = ["zone_short", "location", "region_short"]
+            .iter()
+            .map(|path| extract_json_value(item, path))
+            .find(|v| v != "-")
+            .unwrap_or_else(|| self.zone.clone());
+        crate::shell::console_url(&self.current_resource_key, &name, &self.project, &zone)
+    }
+
+    /// Value of a yank field for one item (None when the item has no such value)
+    fn yank_value(&self, item: &Value, field: YankField) -> Option<String> {
+        let resource = self.current_resource()?;
+        let value = match field {
+            YankField::Name => extract_json_value(item, &resource.name_field),
+            YankField::Id => extract_json_value(item, &resource.id_field),
+            YankField::SelfLink => extract_json_value(item, "selfLink"),
+            YankField::InternalIp => extract_json_value(item, "networkInterfaces.0.networkIP"),
+            YankField::ConsoleUrl => self.console_url_for(item),
+            // One compact document per line when several rows are copied
+            YankField::Json => serde_json::to_string(item).ok()?,
+        };
+        (value != "-" && !value.is_empty()).then_some(value)
+    }
+
+    /// Open the yank menu with the fields available for the current row(s)
+    pub fn enter_yank_mode(&mut self) {
+        let Some(resource) = self.current_resource() else {
+            return;
+        };
+        let targets = self.yank_targets();
+        let Some(first) = targets.first() else {
+            return;
+        };
+
+        let options: Vec<(YankField, String)> = YankField::ALL
+            .iter()
+            // ID is redundant when it is the same field as the name
+            .filter(|f| **f != YankField::Id || resource.id_field != resource.name_field)
+            .filter_map(|f| self.yank_value(first, *f).map(|v| (*f, v)))
+            .collect();
+
+        let item_count = targets.len();
+        self.yank_menu = Some(YankMenuState {
+            options,
+            selected: 0,
+            item_count,
+        });
+        self.mode = Mode::Yank;
+    }
+
+    /// Copy a field of the targeted row(s), newline-joined, and close the menu
+    pub fn yank(&mut self, field: YankField) {
+        let values: Vec<String> = self
+            .yank_targets()
+            .into_iter()
+            .filter_map(|item| self.yank_value(item, field))
+            .collect();
+        self.exit_mode();
+
+        if values.is_empty() {
+            self.error_message = Some(format!("No {} to copy", field.label()));
+            return;
+        }
+        let what = if values.len() == 1 {
+            field.label().to_string()
+        } else {
+            format!("{} x{}", field.label(), values.len())
+        };
+        self.copy_to_clipboard(&values.join("\n"), &what);
+    }
+
+    // =========================================================================
+    // Diff
+    // =========================================================================
+
+    /// `:diff` - compare the two selected rows, or the selected row against
+    /// its state at the previous refresh when fewer than two are selected
+    pub async fn enter_diff_mode(&mut self, against_previous: bool) {
+        let Some(resource) = self.current_resource() else {
+            return;
+        };
+
+        let mut indices: Vec<usize> = self.selection.indices.iter().copied().collect();
+        indices.sort_unstable();
+
+        if !against_previous && indices.len() == 2 {
+            let (Some(left), Some(right)) = (
+                self.filtered_items.get(indices[0]).cloned(),
+                self.filtered_items.get(indices[1]).cloned(),
+            ) else {
+                return;
+            };
+            let left = self.diff_payload(left).await;
+            let right = self.diff_payload(right).await;
+            let result = diff_values(&left, &right, &resource.diff_ignore);
+            self.diff_state = Some(DiffState {
+                left_label: extract_json_value(&left, &resource.name_field),
+                right_label: extract_json_value(&right, &resource.name_field),
+                result,
+                scroll: 0,
+            });
+            self.mode = Mode::Diff;
+            return;
+        }
+
+        if !against_previous && indices.len() > 2 {
+            self.error_message = Some(format!(
+                "Select exactly 2 rows to diff ({} selected)",
+                indices.len()
+            ));
+            return;
+        }
+
+        let Some(current) = self.selected_item().cloned() else {
+            return;
+        };
+        let Some(previous) = self.find_previous_snapshot(&current, resource) else {
+            self.error_message = Some(
+                "No previous snapshot of this item (refresh with R first, or select 2 rows)"
+                    .to_string(),
+            );
+            return;
+        };
+
+        let result = diff_values(previous, &current, &resource.diff_ignore);
+        let name = extract_json_value(&current, &resource.name_field);
+        self.diff_state = Some(DiffState {
+            left_label: format!("{} (previous refresh)", name),
+            right_label: format!("{} (current)", name),
+            result,
+            scroll: 0,
+        });
+        self.mode = Mode::Diff;
+    }
+
+    /// Full details of an item for diffing, falling back to the list row
+    async fn diff_payload(&self, item: Value) -> Value {
+        match fetch_resource_detail(&self.current_resource_key, &self.client, &item).await {
+            Ok(Some(detail)) => detail,
+            Ok(None) => item,
+            Err(e) => {
+                tracing::debug!("Detail fetch for diff failed: {}", e);
+                item
+            },
+        }
+    }
+
+    /// Find an item in the previous refresh (matched by selfLink, then id_field)
+    fn find_previous_snapshot(&self, item: &Value, resource: &ResourceDef) -> Option<&Value> {
+        let identity = |v: &Value| {
+            let link = extract_json_value(v, "selfLink");
+            if link != "-" {
+                link
+            } else {
+                extract_json_value(v, &resource.id_field)
+            }
+        };
+        let target = identity(item);
+        if target == "-" {
+            return None;
+        }
+        self.previous_items.iter().find(|p| identity(p) == target)
+    }
+
+    pub fn diff_scroll(&mut self, delta: isize) {
+        if let Some(ref mut state) = self.diff_state {
+            let max = state.result.entries.len().saturating_sub(1) as isize;
+            state.scroll = (state.scroll as isize + delta).clamp(0, max.max(0)) as usize;
+        }
+    }
+
+    // =========================================================================
+    // Resource Navigation
+    // =========================================================================
+
+    pub async fn navigate_to_resource(&mut self, resource_key: &str) -> Result<()> {
+        if get_resource(resource_key).is_none() {
+            self.error_message = Some(format!("Unknown resource: {}", resource_key));
+            return Ok(());
+        }
+        if let Some(hint) = parent_only_hint(resource_key) {
+            self.show_warning(&hint);
+            return Ok(());
+        }
+
+        self.nav.clear_hierarchy();
+        self.current_resource_key = resource_key.to_string();
+        self.nav.reset();
+        self.filter_sort.reset();
+        self.mode = Mode::Normal;
+        self.selection.clear();
+
+        self.reset_pagination();
+        self.refresh_current().await?;
+        Ok(())
+    }
+
+    pub async fn navigate_to_sub_resource(&mut self, sub_resource_key: &str) -> Result<()> {
+        let Some(selected_item) = self.selected_item().cloned() else {
+            return Ok(());
+        };
+
+        let Some(current_resource) = self.current_resource() else {
+            return Ok(());
+        };
+
+        let is_valid = current_resource
+            .sub_resources
+            .iter()
+            .any(|s| s.resource_key == sub_resource_key);
+
+        if !is_valid {
+            self.error_message = Some(format!(
+                "{} is not a sub-resource of {}",
+                sub_resource_key, self.current_resource_key
+            ));
+            return Ok(());
+        }
+
+        let display_name = extract_json_value(&selected_item, &current_resource.name_field);
+        let id = extract_json_value(&selected_item, &current_resource.id_field);
+        let display = if display_name != "-" {
+            display_name
+        } else {
+            id
+        };
+
+        if let Some(ctx) = self.nav.parent_context.take() {
+            self.nav.navigation_stack.push(ctx);
+        }
+
+        self.nav.parent_context = Some(ParentContext {
+            resource_key: self.current_resource_key.clone(),
+            item: selected_item,
+            display_name: display,
+            via_link: false,
+            scope: None,
+        });
+
+        self.current_resource_key = sub_resource_key.to_string();
+        if sub_resource_key == "storage-objects" {
+            self.objects = ObjectBrowser::default();
+        }
+        self.nav.reset();
+        self.filter_sort.reset();
         self.selection.clear();
 
         self.reset_pagination();
@@ -1235,8 +3303,30 @@ impl App {
     }
 
     pub async fn navigate_back(&mut self) -> Result<()> {
+        // Up one folder in the object browser before leaving the bucket
+        if self.current_resource_key == "storage-objects" && !self.objects.prefix.is_empty() {
+            let folder = self.objects.prefix.clone();
+            self.objects.up();
+            self.reload_objects().await?;
+            if let Some(index) = self
+                .filtered_items
+                .iter()
+                .position(|i| extract_json_value(i, "name") == folder)
+            {
+                self.nav.selected = index;
+            }
+            return Ok(());
+        }
         if let Some(parent) = self.nav.parent_context.take() {
             self.nav.parent_context = self.nav.navigation_stack.pop();
+            if let Some((project, zone)) = parent.scope {
+                if project != self.project {
+                    self.apply_project(&project).await?;
+                }
+                if zone != self.zone {
+                    self.apply_zone(&zone);
+                }
+            }
             self.current_resource_key = parent.resource_key;
             self.nav.reset();
             self.filter_sort.reset();
@@ -1248,6 +3338,74 @@ impl App {
         Ok(())
     }
 
+    /// Follow a resource URL from the describe view: jump to the matching
+    /// resource type, filtered to the referenced item. The current view is
+    /// pushed onto the navigation stack so `b` returns to it.
+    pub async fn follow_self_link(&mut self, url: &str) -> Result<()> {
+        let Some(link) = resolve_self_link(url) else {
+            self.error_message = Some(format!("No view for {}", url));
+            return Ok(());
+        };
+        let (resource_key, name) = (link.resource_key, link.name.clone());
+
+        let Some(item) = self
+            .describe
+            .data
+            .clone()
+            .or_else(|| self.selected_item().cloned())
+        else {
+            return Ok(());
+        };
+        let display_name = self
+            .current_resource()
+            .map(|r| extract_json_value(&item, &r.name_field))
+            .unwrap_or_else(|| "-".to_string());
+
+        self.exit_mode();
+
+        if let Some(ctx) = self.nav.parent_context.take() {
+            self.nav.navigation_stack.push(ctx);
+        }
+        self.nav.parent_context = Some(ParentContext {
+            resource_key: self.current_resource_key.clone(),
+            item,
+            display_name,
+            via_link: true,
+            scope: Some((self.project.clone(), self.zone.clone())),
+        });
+
+        self.current_resource_key = resource_key.to_string();
+        self.nav.reset();
+        self.filter_sort.reset();
+        self.selection.clear();
+        self.reset_pagination();
+        self.switch_to_link_scope(&link).await?;
+
+        // Filter to the referenced item (fetch_page applies the filter)
+        self.filter_sort.filter_text = name.clone();
+        self.refresh_current().await?;
+
+        let name_field = self
+            .current_resource()
+            .map(|r| r.name_field.as_str())
+            .unwrap_or("name");
+        match self
+            .filtered_items
+            .iter()
+            .position(|i| extract_json_value(i, name_field) == name)
+        {
+            Some(index) => self.nav.selected = index,
+            None if self.error_message.is_none() => {
+                self.error_message = Some(format!(
+                    "{} not found in {} (zone: {})",
+                    name, resource_key, self.zone
+                ));
+            },
+            None => {},
+        }
+        Ok(())
+    }
+
     pub fn get_breadcrumb(&self) -> Vec<String> {
         let mut path = Vec::new();
 
@@ -1260,16 +3418,468 @@ impl App {
         }
 
         path.push(self.current_resource_key.clone());
+        if self.current_resource_key == "storage-objects" {
+            path.extend(self.objects.crumb());
+        }
         path
     }
 
+    // =========================================================================
+    // Cloud Storage object browser
+    // =========================================================================
+
+    /// Whether the selected row is a folder of the object browser
+    pub fn selected_is_folder(&self) -> bool {
+        self.current_resource_key == "storage-objects"
+            && self.selected_item().is_some_and(crate::storage::is_folder)
+    }
+
+    /// Descend into the selected folder
+    pub async fn open_selected_folder(&mut self) -> Result<()> {
+        let Some(folder) = self
+            .selected_item()
+            .map(|item| extract_json_value(item, "name"))
+        else {
+            return Ok(());
+        };
+        self.objects.enter(&folder);
+        self.reload_objects().await
+    }
+
+    /// Bucket whose objects are listed
+    pub fn current_bucket(&self) -> Option<String> {
+        self.nav
+            .parent_context
+            .as_ref()
+            .filter(|p| p.resource_key == "storage-buckets")
+            .map(|p| extract_json_value(&p.item, "name"))
+    }
+
+    /// Open the download form for the selected objects (or the current one)
+    pub fn open_download_form(&mut self) {
+        let mut items = self.selected_items();
+        if items.is_empty() {
+            items.extend(self.selected_item());
+        }
+        match wizard::transfer::download_objects_form(&items, &self.objects.prefix) {
+            Ok(form) => {
+                self.form = Some(form);
+                self.mode = Mode::Form;
+            },
+            Err(e) => self.show_warning(&e),
+        }
+    }
+
+    /// Open the upload form for the current folder
+    pub fn open_upload_form(&mut self) {
+        let Some(bucket) = self.current_bucket() else {
+            self.show_warning("Open the objects of a bucket to upload into it");
+            return;
+        };
+        self.form = Some(wizard::transfer::upload_object_form(
+            &bucket,
+            &self.objects.prefix,
+        ));
+        self.mode = Mode::Form;
+    }
+
+    /// Queue the transfers of the download or upload form, each tracked by a
+    /// notification
+    fn start_transfers_from_form(&mut self) {
+        let Some(ref mut form) = self.form else {
+            return;
+        };
+        let jobs = if form.purpose == FormPurpose::UploadObject {
+            match wizard::transfer::upload_job(form) {
+                Ok(job) => vec![job],
+                Err(e) => {
+                    form.error = Some(e);
+                    return;
+                },
+            }
+        } else {
+            match wizard::transfer::download_jobs(form).and_then(|jobs| {
+                wizard::transfer::confirm_download_overwrite(form, &jobs).map(|_| jobs)
+            }) {
+                Ok(jobs) => jobs,
+                Err(e) => {
+                    form.error = Some(e);
+                    return;
+                },
+            }
+        };
+        self.exit_mode();
+
+        for job in jobs {
+            let id = self.create_operation_notification(job.method(), "storage", &job.label());
+            self.mark_notification_in_progress(id, None);
+            // Without notifications the transfer still needs an id
+            let id = if id.is_nil() { Uuid::new_v4() } else { id };
+            self.transfers.start(id, &self.client, job);
+        }
+        self.set_status(format!(
+            "{} transfer(s) running",
+            self.transfers.active_count()
+        ));
+    }
+
+    /// Report the progress of running transfers, refreshing the object list
+    /// after an upload
+    pub async fn poll_transfers(&mut self) {
+        let mut uploaded = false;
+        for update in self.transfers.poll() {
+            match update {
+                TransferUpdate::Progress(id, text) => {
+                    self.notification_manager.set_progress(id, text)
+                },
+                TransferUpdate::Done(id, kind, _) => {
+                    uploaded |= kind == TransferKind::Upload;
+                    if !self.config.notifications.enabled {
+                        self.set_status("Transfer complete");
+                    }
+                    self.notification_manager.mark_success(id);
+                },
+                TransferUpdate::Failed(id, e) => {
+                    if !self.config.notifications.enabled {
+                        self.error_message = Some(format!("Transfer failed: {}", e));
+                    }
+                    self.notification_manager.mark_error(id, e);
+                },
+            }
+        }
+        if uploaded && self.current_resource_key == "storage-objects" && self.mode == Mode::Normal {
+            let _ = self.refresh_current().await;
+        }
+    }
+
+    /// Open the preview of the selected object with its first bytes
+    pub async fn open_object_preview(&mut self) {
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+        let Some(preview) = ObjectPreview::new(item) else {
+            self.show_warning("Folders cannot be previewed; press Enter to open them");
+            return;
+        };
+        self.preview = Some(preview);
+        self.mode = Mode::Preview;
+        self.load_preview_more().await;
+    }
+
+    /// Read the next range of the previewed object
+    pub async fn load_preview_more(&mut self) {
+        let Some(ref preview) = self.preview else {
+            return;
+        };
+        let Some((start, len)) = preview.next_range() else {
+            if preview.decoded_truncated {
+                self.set_status("The decompressed preview limit is reached");
+            } else {
+                self.set_status("The whole object is loaded");
+            }
+            return;
+        };
+        let url = crate::transfer::media_url(
+            &self.client.storage_objects_url(&preview.bucket),
+            &preview.name,
+            preview.generation.as_deref(),
+        );
+        let result = match self.client.get_token().await {
+            Ok(token) => {
+                crate::transfer::read_range(self.client.http.inner(), &token, &url, start, len)
+                    .await
+            },
+            Err(e) => Err(e),
+        };
+        let Some(ref mut preview) = self.preview else {
+            return;
+        };
+        match result {
+            Ok(bytes) => preview.append(&bytes, len),
+            Err(e) => {
+                preview.error = Some(format!(
+                    "Failed to read the object: {}",
+                    crate::gcp::client::format_gcp_error(&e)
+                ))
+            },
+        }
+    }
+
+    /// Open the detail view of the selected bucket
+    pub async fn open_bucket_detail(&mut self) {
+        let Some(name) = self
+            .selected_item()
+            .map(|item| extract_json_value(item, "name"))
+        else {
+            return;
+        };
+        self.bucket = Some(BucketView::new(&name));
+        self.mode = Mode::Bucket;
+        self.load_bucket_detail().await;
+    }
+
+    /// Read the bucket (`projection=full`) and its IAM policy
+    pub async fn load_bucket_detail(&mut self) {
+        let Some(ref view) = self.bucket else {
+            return;
+        };
+        let params = serde_json::json!({ "bucket": view.name });
+        let response = invoke_sdk("storage", "get_bucket", &self.client, &params).await;
+        let Some(ref mut view) = self.bucket else {
+            return;
+        };
+        match response {
+            Ok(bucket) => view.set_bucket(bucket),
+            Err(e) => {
+                view.error = Some(format!(
+                    "Failed to read bucket {}: {}",
+                    view.name,
+                    crate::gcp::client::format_gcp_error(&e)
+                ))
+            },
+        }
+    }
+
+    /// Ask to turn versioning or public access prevention of the viewed
+    /// bucket on or off
+    pub fn confirm_bucket_toggle(&mut self, versioning: bool) {
+        if self.readonly {
+            self.show_warning("Read-only mode: actions are disabled");
+            return;
+        }
+        let Some(ref view) = self.bucket else {
+            return;
+        };
+        let Some(ref bucket) = view.bucket else {
+            return;
+        };
+        let (method, enable, message, destructive) = if versioning {
+            let enable = !view.versioning_enabled();
+            let message = if enable {
+                format!("Enable object versioning on {}?", view.name)
+            } else {
+                format!(
+                    "Disable object versioning on {}? Overwrites and deletes will no longer keep the previous version.",
+                    view.name
+                )
+            };
+            ("toggle_versioning", enable, message, !enable)
+        } else {
+            let enable = !view.public_access_prevented();
+            let message = if enable {
+                format!(
+                    "Enforce public access prevention on {}? Public IAM bindings and ACLs stop working.",
+                    view.name
+                )
+            } else {
+                format!(
+                    "Stop enforcing public access prevention on {}? The bucket can then be made public.",
+                    view.name
+                )
+            };
+            ("toggle_public_access_prevention", enable, message, !enable)
+        };
+        let metageneration = extract_json_value(bucket, "metageneration");
+        let pending = PendingAction {
+            service: "storage".to_string(),
+            sdk_method: method.to_string(),
+            resource_id: view.name.clone(),
+            params: serde_json::json!({
+                "enable": enable,
+                "if_metageneration_match": metageneration,
+            }),
+            message,
+            destructive,
+            selected_yes: false,
+        };
+        self.enter_confirm_mode(pending);
+    }
+
+    /// Open the generations of the selected object
+    pub async fn open_object_versions(&mut self) {
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+        if crate::storage::is_folder(item) {
+            self.show_warning("Folders have no versions; press Enter to open them");
+            return;
+        }
+        let name = extract_json_value(item, "name");
+        let Some(bucket) = self.current_bucket() else {
+            self.show_warning("Open the objects of a bucket to see their versions");
+            return;
+        };
+        self.versions = Some(VersionsView::new(&bucket, &name));
+        self.mode = Mode::Versions;
+        self.load_object_versions().await;
+    }
+
+    /// List every generation of the viewed object
+    pub async fn load_object_versions(&mut self) {
+        let Some(ref view) = self.versions else {
+            return;
+        };
+        let mut params = view.list_params();
+        let mut items = Vec::new();
+        let result = loop {
+            match invoke_sdk("storage", "list_object_versions", &self.client, &params).await {
+                Ok(response) => {
+                    if let Some(page) = response.get("items").and_then(|v| v.as_array()) {
+                        items.extend(page.iter().cloned());
+                    }
+                    match response.get("nextPageToken").and_then(|v| v.as_str()) {
+                        Some(token) => params["pageToken"] = Value::String(token.to_string()),
+                        None => break Ok(()),
+                    }
+                },
+                Err(e) => break Err(e),
+            }
+        };
+        let Some(ref mut view) = self.versions else {
+            return;
+        };
+        match result {
+            Ok(()) => view.set_versions(items),
+            Err(e) => {
+                view.error = Some(format!(
+                    "Failed to list versions of {}: {}",
+                    view.name,
+                    crate::gcp::client::format_gcp_error(&e)
+                ))
+            },
+        }
+    }
+
+    /// Ask to restore the selected generation onto the live name, or to
+    /// delete it
+    pub fn confirm_version_action(&mut self, restore: bool) {
+        if self.readonly {
+            self.show_warning("Read-only mode: actions are disabled");
+            return;
+        }
+        let Some(ref view) = self.versions else {
+            return;
+        };
+        let Some(version) = view.selected_version() else {
+            return;
+        };
+        let generation = crate::versions::generation(version);
+        let live = crate::versions::is_live(version);
+        let (method, params, message, destructive) = if restore {
+            if live {
+                self.set_status("This generation is already the live one");
+                return;
+            }
+            let message = match view.live_generation() {
+                Some(current) => format!(
+                    "Restore generation {} of {}? It becomes the live object and generation {} turns noncurrent.",
+                    generation, view.name, current
+                ),
+                None => format!(
+                    "Restore generation {} of deleted object {}?",
+                    generation, view.name
+                ),
+            };
+            (
+                "restore_object",
+                view.restore_params(generation),
+                message,
+                false,
+            )
+        } else {
+            let message = if live {
+                format!(
+                    "Permanently delete the live generation {} of {}? Without a noncurrent copy it cannot be recovered.",
+                    generation, view.name
+                )
+            } else {
+                format!(
+                    "Permanently delete generation {} of {}?",
+                    generation, view.name
+                )
+            };
+            let params = serde_json::json!({
+                "bucket": view.bucket,
+                "generation": generation.to_string(),
+            });
+            ("delete_object", params, message, true)
+        };
+        let pending = PendingAction {
+            service: "storage".to_string(),
+            sdk_method: method.to_string(),
+            resource_id: view.name.clone(),
+            params,
+            message,
+            destructive,
+            selected_yes: false,
+        };
+        self.enter_confirm_mode(pending);
+    }
+
+    /// Switch between folder and recursive listing of the current prefix
+    pub async fn toggle_objects_recursive(&mut self) {
+        self.objects.recursive = !self.objects.recursive;
+        if let Err(e) = self.reload_objects().await {
+            self.error_message = Some(e.to_string());
+        }
+    }
+
+    /// Show or hide objects whose live generation was deleted
+    pub async fn toggle_objects_show_deleted(&mut self) {
+        self.objects.show_deleted = !self.objects.show_deleted;
+        if let Err(e) = self.reload_objects().await {
+            self.error_message = Some(e.to_string());
+        }
+    }
+
+    /// List the current folder from its first page
+    async fn reload_objects(&mut self) -> Result<()> {
+        self.nav.reset();
+        self.filter_sort.reset();
+        self.selection.clear();
+        self.reset_pagination();
+        self.refresh_current().await
+    }
+
     // =========================================================================
     // Project/Zone Switching
     // =========================================================================
 
-    pub async fn switch_zone(&mut self, zone: &str) -> Result<()> {
+    /// Switch to the project and zone a followed link points into, so the
+    /// referenced resource is listed: its zone, or a zone of its region
+    /// Switch to the project and zone of a followed link for this session
+    /// only: they are not saved to the config, and `b` switches back
+    async fn switch_to_link_scope(&mut self, link: &SelfLink) -> Result<()> {
+        if let Some(ref project) = link.project {
+            if *project != self.project {
+                self.apply_project(project).await?;
+                self.set_status(format!("Switched to project {}", project));
+            }
+        }
+        let zone = match (&link.zone, &link.region) {
+            (Some(zone), _) if self.zone != "all" && *zone != self.zone => Some(zone.clone()),
+            (None, Some(region)) if self.client.get_region() != *region => self
+                .available_zones
+                .iter()
+                .find(|z| z.starts_with(&format!("{}-", region)))
+                .cloned(),
+            _ => None,
+        };
+        if let Some(zone) = zone {
+            self.apply_zone(&zone);
+            self.set_status(format!("Switched to zone {}", zone));
+        }
+        Ok(())
+    }
+
+    /// Use a zone without saving it to the config
+    fn apply_zone(&mut self, zone: &str) {
         self.client.switch_zone(zone);
         self.zone = zone.to_string();
+    }
+
+    pub async fn switch_zone(&mut self, zone: &str) -> Result<()> {
+        self.apply_zone(zone);
 
         if let Err(e) = self.config.set_zone(zone) {
             tracing::warn!("Failed to save zone to config: {}", e);
@@ -1278,14 +3888,11 @@ impl App {
         Ok(())
     }
 
-    pub async fn switch_project(&mut self, project: &str) -> Result<()> {
+    /// Use a project without saving it to the config
+    async fn apply_project(&mut self, project: &str) -> Result<()> {
         self.client.switch_project(project).await?;
         self.project = project.to_string();
 
-        if let Err(e) = self.config.set_project(project) {
-            tracing::warn!("Failed to save project to config: {}", e);
-        }
-
         // Apply project-specific theme if configured
         let theme_name = self.config.effective_theme(project);
         self.theme_manager.set_theme(&theme_name);
@@ -1293,6 +3900,16 @@ impl App {
         Ok(())
     }
 
+    pub async fn switch_project(&mut self, project: &str) -> Result<()> {
+        self.apply_project(project).await?;
+
+        if let Err(e) = self.config.set_project(project) {
+            tracing::warn!("Failed to save project to config: {}", e);
+        }
+
+        Ok(())
+    }
+
     pub async fn select_project(&mut self) -> Result<()> {
         if let Some(project) = self.projects_selector.current().cloned() {
             self.switch_project(&project).await?;
@@ -1354,6 +3971,13 @@ impl App {
                     self.enter_notifications_mode();
                 }
             },
+            "tunnels" => {
+                self.enter_tunnels_mode();
+            },
+            "diff" => {
+                let against_previous = parts.get(1).is_some_and(|p| *p == "prev");
+                self.enter_diff_mode(against_previous).await;
+            },
             "zone" if parts.len() > 1 => {
                 self.switch_zone(parts[1]).await?;
                 self.refresh_current().await?;
@@ -1531,6 +4155,29 @@ impl App {
             .collect()
     }
 
+    /// Items an action applies to: the selected rows, or the row under the
+    /// cursor when nothing is selected
+    pub fn action_items(&self) -> Vec<&Value> {
+        if self.selection.indices.is_empty() {
+            self.selected_item().into_iter().collect()
+        } else {
+            self.selected_items()
+        }
+    }
+
+    /// Names of the selected items (or the current item without a
+    /// selection) that have deletion protection enabled
+    pub fn protected_selection(&self) -> Vec<String> {
+        let Some(resource) = self.current_resource() else {
+            return Vec::new();
+        };
+        self.action_items()
+            .into_iter()
+            .filter(|item| deletion_protected(item))
+            .map(|item| extract_json_value(item, &resource.name_field))
+            .collect()
+    }
+
     /// Toggle visual/multi-select mode
     pub fn toggle_visual_mode(&mut self) {
         self.selection.visual_mode = !self.selection.visual_mode;
@@ -1573,6 +4220,50 @@ impl App {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_deletion_protection_refusal() {
+        assert!(deletion_protected(
+            &serde_json::json!({"deletionProtection": true})
+        ));
+        assert!(!deletion_protected(&serde_json::json!({"name": "web"})));
+        assert!(deletion_protection_refusal(&[]).is_none());
+        let message = deletion_protection_refusal(&["db-01".to_string()]).unwrap();
+        assert!(message.starts_with("'db-01' has deletion protection enabled"));
+        let message =
+            deletion_protection_refusal(&["db-01".to_string(), "db-02".to_string()]).unwrap();
+        assert!(message.contains("2 of the selected resources"));
+    }
+
+    #[test]
+    fn test_deletion_protection_action() {
+        let web = serde_json::json!({"name": "web", "zone_short": "us-central1-a"});
+        let db = serde_json::json!({
+            "name": "db", "zone_short": "us-central1-a", "deletionProtection": true
+        });
+        let pending = deletion_protection_action(&[&db]).unwrap();
+        assert_eq!(
+            pending.message,
+            "Disable deletion protection on 'db'? It can then be deleted."
+        );
+        assert_eq!(pending.params["enable"], false);
+        assert_eq!(pending.params["zone"], "us-central1-a");
+        assert!(pending.destructive);
+
+        // A mixed selection is protected as a whole
+        let pending = deletion_protection_action(&[&web, &db]).unwrap();
+        assert_eq!(
+            pending.message,
+            "Enable deletion protection on 2 instances (web, db)?"
+        );
+        assert_eq!(pending.resource_id, "web\ndb");
+        assert_eq!(pending.params["enable"], true);
+        assert!(!pending.destructive);
+
+        let other = serde_json::json!({"name": "api", "zone_short": "europe-west1-b"});
+        let pending = deletion_protection_action(&[&web, &other]).unwrap();
+        assert!(pending.params.get("zone").is_none());
+    }
+
     #[test]
     fn test_visible_range_basic() {
         // Simulate: 100 items, viewport 10, scroll_offset 0
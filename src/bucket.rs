@@ -0,0 +1,533 @@
+//! Bucket Detail
+//!
+//! Readable summary of a Cloud Storage bucket read with `projection=full`
+//! and its IAM policy (`b/{bucket}/iam`, merged as `iamPolicy` by
+//! `get_bucket`): access control, versioning, retention, lifecycle rules,
+//! CORS, IAM bindings and ACLs. Settings that expose data publicly are
+//! flagged.
+
+use serde_json::Value;
+
+/// Members granting access to anyone (or any Google account)
+pub const PUBLIC_MEMBERS: [&str; 2] = ["allUsers", "allAuthenticatedUsers"];
+
+/// How a line of the detail view is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Heading,
+    Normal,
+    /// Protective setting in place
+    Good,
+    /// Weaker setting worth a look
+    Warning,
+    /// Exposes data (public bindings, permissive CORS with public access)
+    Risk,
+}
+
+/// A line of the detail view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetailLine {
+    pub text: String,
+    pub kind: LineKind,
+}
+
+impl DetailLine {
+    fn new(kind: LineKind, text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            kind,
+        }
+    }
+}
+
+/// Bucket detail view state
+#[derive(Debug, Clone)]
+pub struct BucketView {
+    pub name: String,
+    /// Bucket resource with its `iamPolicy`, once loaded
+    pub bucket: Option<Value>,
+    pub lines: Vec<DetailLine>,
+    pub error: Option<String>,
+    pub scroll: usize,
+    pub viewport_height: usize,
+}
+
+impl BucketView {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            bucket: None,
+            lines: Vec::new(),
+            error: None,
+            scroll: 0,
+            viewport_height: 1,
+        }
+    }
+
+    pub fn set_bucket(&mut self, bucket: Value) {
+        self.lines = detail_lines(&bucket);
+        self.bucket = Some(bucket);
+        self.error = None;
+        self.scroll = self.scroll.min(self.max_scroll());
+    }
+
+    /// Number of settings flagged as risky
+    pub fn risk_count(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|l| l.kind == LineKind::Risk)
+            .count()
+    }
+
+    /// Whether object versioning is on
+    pub fn versioning_enabled(&self) -> bool {
+        self.bucket.as_ref().is_some_and(versioning_enabled)
+    }
+
+    /// Whether public access prevention is enforced
+    pub fn public_access_prevented(&self) -> bool {
+        self.bucket.as_ref().is_some_and(public_access_prevented)
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.viewport_height)
+    }
+
+    pub fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+}
+
+pub fn versioning_enabled(bucket: &Value) -> bool {
+    bucket
+        .pointer("/versioning/enabled")
+        .and_then(|v| v.as_bool())
+        == Some(true)
+}
+
+pub fn public_access_prevented(bucket: &Value) -> bool {
+    bucket
+        .pointer("/iamConfiguration/publicAccessPrevention")
+        .and_then(|v| v.as_str())
+        == Some("enforced")
+}
+
+/// Body of a `buckets.patch` turning versioning on or off
+pub fn versioning_patch(enable: bool) -> Value {
+    serde_json::json!({ "versioning": { "enabled": enable } })
+}
+
+/// Body of a `buckets.patch` enforcing public access prevention, or letting
+/// the bucket inherit it from the organization policy
+pub fn public_access_prevention_patch(enforce: bool) -> Value {
+    let value = if enforce { "enforced" } else { "inherited" };
+    serde_json::json!({ "iamConfiguration": { "publicAccessPrevention": value } })
+}
+
+/// Duration in seconds as days, hours or seconds ("604800" → "7 days")
+pub fn format_seconds(seconds: &str) -> String {
+    let Ok(secs) = seconds.parse::<u64>() else {
+        return seconds.to_string();
+    };
+    let plural = |n: u64, unit: &str| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" });
+    if secs > 0 && secs % 86_400 == 0 {
+        plural(secs / 86_400, "day")
+    } else if secs > 0 && secs % 3600 == 0 {
+        plural(secs / 3600, "hour")
+    } else {
+        plural(secs, "second")
+    }
+}
+
+fn str_at<'a>(value: &'a Value, pointer: &str) -> Option<&'a str> {
+    value.pointer(pointer).and_then(|v| v.as_str())
+}
+
+fn array_at<'a>(value: &'a Value, pointer: &str) -> &'a [Value] {
+    value
+        .pointer(pointer)
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Join the string items of an array
+fn join_strings(values: &[Value]) -> String {
+    values
+        .iter()
+        .map(|v| match v {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Lay out the sections of the detail view
+pub fn detail_lines(bucket: &Value) -> Vec<DetailLine> {
+    use LineKind::*;
+    let mut lines = Vec::new();
+    let mut push = |kind, text: String| lines.push(DetailLine::new(kind, text));
+    let heading = |lines: &mut Vec<DetailLine>, title: &str| {
+        if !lines.is_empty() {
+            lines.push(DetailLine::new(Normal, ""));
+        }
+        lines.push(DetailLine::new(Heading, title));
+    };
+
+    push(Heading, "Overview".to_string());
+    let location = match str_at(bucket, "/locationType") {
+        Some(kind) => format!("{} ({})", str_at(bucket, "/location").unwrap_or("-"), kind),
+        None => str_at(bucket, "/location").unwrap_or("-").to_string(),
+    };
+    push(Normal, format!("  Location:         {}", location));
+    push(
+        Normal,
+        format!(
+            "  Storage class:    {}",
+            str_at(bucket, "/storageClass").unwrap_or("-")
+        ),
+    );
+    push(
+        Normal,
+        format!(
+            "  Created:          {}",
+            str_at(bucket, "/timeCreated").unwrap_or("-")
+        ),
+    );
+
+    // Access control
+    heading(&mut lines, "Access control");
+    let uniform = bucket
+        .pointer("/iamConfiguration/uniformBucketLevelAccess/enabled")
+        .and_then(|v| v.as_bool())
+        == Some(true);
+    if uniform {
+        let locked = str_at(
+            bucket,
+            "/iamConfiguration/uniformBucketLevelAccess/lockedTime",
+        )
+        .map(|t| format!(" (permanent after {})", t))
+        .unwrap_or_default();
+        lines.push(DetailLine::new(
+            Good,
+            format!("  Uniform bucket-level access: enabled{}", locked),
+        ));
+    } else {
+        lines.push(DetailLine::new(
+            Warning,
+            "  Uniform bucket-level access: disabled (object ACLs apply)",
+        ));
+    }
+    match str_at(bucket, "/iamConfiguration/publicAccessPrevention") {
+        Some("enforced") => lines.push(DetailLine::new(
+            Good,
+            "  Public access prevention:    enforced",
+        )),
+        other => lines.push(DetailLine::new(
+            Warning,
+            format!(
+                "  Public access prevention:    {} (public grants allowed)",
+                other.unwrap_or("inherited")
+            ),
+        )),
+    }
+
+    // Versioning and data protection
+    heading(&mut lines, "Versioning and retention");
+    if versioning_enabled(bucket) {
+        lines.push(DetailLine::new(Good, "  Object versioning: enabled"));
+    } else {
+        lines.push(DetailLine::new(
+            Normal,
+            "  Object versioning: disabled (overwrites and deletes are final)",
+        ));
+    }
+    match bucket.get("retentionPolicy") {
+        Some(policy) => {
+            let period = str_at(policy, "/retentionPeriod")
+                .map(format_seconds)
+                .unwrap_or_else(|| "-".to_string());
+            let locked = policy.get("isLocked").and_then(|v| v.as_bool()) == Some(true);
+            let (kind, lock) = if locked {
+                (Good, "locked, cannot be shortened or removed")
+            } else {
+                (Normal, "not locked")
+            };
+            lines.push(DetailLine::new(
+                kind,
+                format!("  Retention policy:  {} ({})", period, lock),
+            ));
+            if let Some(effective) = str_at(policy, "/effectiveTime") {
+                lines.push(DetailLine::new(
+                    Normal,
+                    format!("                     in effect since {}", effective),
+                ));
+            }
+        },
+        None => lines.push(DetailLine::new(Normal, "  Retention policy:  none")),
+    }
+    if let Some(duration) = str_at(bucket, "/softDeletePolicy/retentionDurationSeconds") {
+        let text = if duration == "0" {
+            "disabled".to_string()
+        } else {
+            format_seconds(duration)
+        };
+        lines.push(DetailLine::new(
+            Normal,
+            format!("  Soft delete:       {}", text),
+        ));
+    }
+    if bucket
+        .pointer("/defaultEventBasedHold")
+        .and_then(|v| v.as_bool())
+        == Some(true)
+    {
+        lines.push(DetailLine::new(
+            Normal,
+            "  Event-based hold:  on by default for new objects",
+        ));
+    }
+
+    // Lifecycle
+    heading(&mut lines, "Lifecycle rules");
+    let rules = array_at(bucket, "/lifecycle/rule");
+    if rules.is_empty() {
+        lines.push(DetailLine::new(Normal, "  none"));
+    }
+    for rule in rules {
+        lines.push(DetailLine::new(
+            Normal,
+            format!("  {}", lifecycle_rule(rule)),
+        ));
+    }
+
+    // CORS
+    heading(&mut lines, "CORS");
+    let cors = array_at(bucket, "/cors");
+    if cors.is_empty() {
+        lines.push(DetailLine::new(Normal, "  none"));
+    }
+    for entry in cors {
+        let origins = array_at(entry, "/origin");
+        let any_origin = origins.iter().any(|o| o.as_str() == Some("*"));
+        let mut text = format!(
+            "  {} from {}",
+            match join_strings(array_at(entry, "/method")) {
+                m if m.is_empty() => "any method".to_string(),
+                m => m,
+            },
+            join_strings(origins)
+        );
+        let headers = join_strings(array_at(entry, "/responseHeader"));
+        if !headers.is_empty() {
+            text.push_str(&format!(", headers {}", headers));
+        }
+        if let Some(max_age) = entry.get("maxAgeSeconds").and_then(|v| v.as_u64()) {
+            text.push_str(&format!(", max age {}s", max_age));
+        }
+        lines.push(DetailLine::new(
+            if any_origin { Warning } else { Normal },
+            text,
+        ));
+    }
+
+    // IAM
+    heading(&mut lines, "IAM bindings");
+    match bucket.get("iamPolicy") {
+        None => lines.push(DetailLine::new(Warning, "  IAM policy could not be read")),
+        Some(policy) => {
+            let bindings = array_at(policy, "/bindings");
+            if bindings.is_empty() {
+                lines.push(DetailLine::new(Normal, "  none"));
+            }
+            for binding in bindings {
+                let role = binding.get("role").and_then(|v| v.as_str()).unwrap_or("-");
+                let role = role.strip_prefix("roles/").unwrap_or(role);
+                let condition = str_at(binding, "/condition/title")
+                    .map(|t| format!(" (if {})", t))
+                    .unwrap_or_default();
+                lines.push(DetailLine::new(Normal, format!("  {}{}", role, condition)));
+                for member in array_at(binding, "/members")
+                    .iter()
+                    .filter_map(|m| m.as_str())
+                {
+                    if PUBLIC_MEMBERS.contains(&member) {
+                        lines.push(DetailLine::new(Risk, format!("    {}  PUBLIC", member)));
+                    } else {
+                        lines.push(DetailLine::new(Normal, format!("    {}", member)));
+                    }
+                }
+            }
+        },
+    }
+
+    // ACLs only apply without uniform bucket-level access
+    for (title, field) in [
+        ("Bucket ACL", "acl"),
+        ("Default object ACL", "defaultObjectAcl"),
+    ] {
+        heading(&mut lines, title);
+        if uniform {
+            lines.push(DetailLine::new(
+                Normal,
+                "  not used (uniform bucket-level access)",
+            ));
+            continue;
+        }
+        let Some(entries) = bucket.get(field).and_then(|v| v.as_array()) else {
+            lines.push(DetailLine::new(
+                Warning,
+                "  could not be read (needs owner access to the bucket)",
+            ));
+            continue;
+        };
+        if entries.is_empty() {
+            lines.push(DetailLine::new(Normal, "  none"));
+        }
+        for entry in entries {
+            let entity = str_at(entry, "/entity").unwrap_or("-");
+            let role = str_at(entry, "/role").unwrap_or("-");
+            if PUBLIC_MEMBERS.contains(&entity) {
+                lines.push(DetailLine::new(
+                    Risk,
+                    format!("  {}: {}  PUBLIC", entity, role),
+                ));
+            } else {
+                lines.push(DetailLine::new(Normal, format!("  {}: {}", entity, role)));
+            }
+        }
+    }
+    lines
+}
+
+/// "SetStorageClass to NEARLINE if age >= 30, matchesStorageClass STANDARD"
+pub fn lifecycle_rule(rule: &Value) -> String {
+    let mut action = str_at(rule, "/action/type").unwrap_or("?").to_string();
+    if let Some(class) = str_at(rule, "/action/storageClass") {
+        action.push_str(&format!(" to {}", class));
+    }
+    let conditions: Vec<String> = rule
+        .get("condition")
+        .and_then(|c| c.as_object())
+        .map(|condition| {
+            condition
+                .iter()
+                .map(|(key, value)| match (key.as_str(), value) {
+                    ("age", v) => format!("age >= {} days", v),
+                    ("numNewerVersions", v) => format!("{} newer versions", v),
+                    ("daysSinceNoncurrentTime", v) => format!("noncurrent for {} days", v),
+                    ("daysSinceCustomTime", v) => format!("custom time {} days ago", v),
+                    ("isLive", Value::Bool(true)) => "live".to_string(),
+                    ("isLive", Value::Bool(false)) => "noncurrent".to_string(),
+                    (key, Value::Array(values)) => format!("{} {}", key, join_strings(values)),
+                    (key, Value::String(s)) => format!("{} {}", key, s),
+                    (key, other) => format!("{} {}", key, other),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if conditions.is_empty() {
+        action
+    } else {
+        format!("{} if {}", action, conditions.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_bucket_detail_lines() {
+        let bucket = json!({
+            "name": "assets",
+            "location": "US",
+            "locationType": "multi-region",
+            "versioning": { "enabled": true },
+            "iamConfiguration": {
+                "uniformBucketLevelAccess": { "enabled": true },
+                "publicAccessPrevention": "inherited"
+            },
+            "retentionPolicy": { "retentionPeriod": "2592000", "isLocked": true },
+            "lifecycle": { "rule": [
+                { "action": { "type": "Delete" }, "condition": { "age": 30, "isLive": false } },
+                { "action": { "type": "SetStorageClass", "storageClass": "NEARLINE" },
+                  "condition": { "matchesStorageClass": ["STANDARD"] } }
+            ]},
+            "cors": [{ "origin": ["*"], "method": ["GET"], "maxAgeSeconds": 3600 }],
+            "iamPolicy": { "bindings": [
+                { "role": "roles/storage.objectViewer", "members": ["allUsers", "user:a@example.com"] }
+            ]}
+        });
+        let mut view = BucketView::new("assets");
+        view.set_bucket(bucket);
+        assert!(view.versioning_enabled());
+        assert!(!view.public_access_prevented());
+        assert_eq!(view.risk_count(), 1);
+
+        let text: Vec<&str> = view.lines.iter().map(|l| l.text.as_str()).collect();
+        assert!(text.contains(&"  Location:         US (multi-region)"));
+        assert!(
+            text.contains(&"  Retention policy:  30 days (locked, cannot be shortened or removed)")
+        );
+        assert!(text.contains(&"  Delete if age >= 30 days, noncurrent"));
+        assert!(text.contains(&"  SetStorageClass to NEARLINE if matchesStorageClass STANDARD"));
+        assert!(text.contains(&"  GET from *, max age 3600s"));
+        assert!(text.contains(&"  storage.objectViewer"));
+        let risk = view
+            .lines
+            .iter()
+            .find(|l| l.kind == LineKind::Risk)
+            .unwrap();
+        assert_eq!(risk.text, "    allUsers  PUBLIC");
+
+        assert!(text.contains(&"  not used (uniform bucket-level access)"));
+
+        // Without uniform access, public ACL entries make the bucket public
+        let bucket = json!({
+            "iamConfiguration": { "uniformBucketLevelAccess": { "enabled": false } },
+            "acl": [
+                { "entity": "project-owners-123", "role": "OWNER" },
+                { "entity": "allUsers", "role": "READER" }
+            ],
+            "defaultObjectAcl": [{ "entity": "allAuthenticatedUsers", "role": "READER" }],
+            "iamPolicy": { "bindings": [] }
+        });
+        view.set_bucket(bucket);
+        assert_eq!(view.risk_count(), 2);
+        let risks: Vec<&str> = view
+            .lines
+            .iter()
+            .filter(|l| l.kind == LineKind::Risk)
+            .map(|l| l.text.as_str())
+            .collect();
+        assert_eq!(
+            risks,
+            vec![
+                "  allUsers: READER  PUBLIC",
+                "  allAuthenticatedUsers: READER  PUBLIC"
+            ]
+        );
+        let text: Vec<&str> = view.lines.iter().map(|l| l.text.as_str()).collect();
+        assert!(text.contains(&"  project-owners-123: OWNER"));
+
+        assert_eq!(format_seconds("3600"), "1 hour");
+        assert_eq!(
+            public_access_prevention_patch(true)["iamConfiguration"]["publicAccessPrevention"],
+            "enforced"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Operation Chains
+//!
+//! Actions that must run one after another, each step starting once the
+//! previous operation is done (e.g. stop → setMachineType → start). Every
+//! step gets its own notification, numbered within the chain; if a step
+//! fails the chain stops and reports what state the resource was left in.
+
+use serde_json::Value;
+use std::time::Instant;
+use uuid::Uuid;
+
+/// A single action of a chain
+#[derive(Debug, Clone)]
+pub struct ChainStep {
+    pub sdk_method: String,
+    pub params: Value,
+    /// Explanation shown when this step fails (what was left changed and how
+    /// to roll back)
+    pub on_failure: String,
+}
+
+impl ChainStep {
+    pub fn new(sdk_method: &str, params: Value, on_failure: impl Into<String>) -> Self {
+        Self {
+            sdk_method: sdk_method.to_string(),
+            params,
+            on_failure: on_failure.into(),
+        }
+    }
+}
+
+/// A running sequence of actions on one resource
+#[derive(Debug, Clone)]
+pub struct OperationChain {
+    pub service: String,
+    pub resource_id: String,
+    /// What the chain does, shown when it completes (e.g. "Resized web-01")
+    pub summary: String,
+    pub steps: Vec<ChainStep>,
+    /// Index of the running step
+    pub current: usize,
+    /// Notification of the running step
+    pub notification_id: Uuid,
+    /// Operation of the running step, polled until done
+    pub operation_url: Option<String>,
+    pub last_poll: Instant,
+}
+
+impl OperationChain {
+    pub fn new(service: &str, resource_id: &str, summary: &str, steps: Vec<ChainStep>) -> Self {
+        Self {
+            service: service.to_string(),
+            resource_id: resource_id.to_string(),
+            summary: summary.to_string(),
+            steps,
+            current: 0,
+            notification_id: Uuid::nil(),
+            operation_url: None,
+            last_poll: Instant::now(),
+        }
+    }
+
+    pub fn current_step(&self) -> Option<&ChainStep> {
+        self.steps.get(self.current)
+    }
+
+    /// Move to the next step, returns false when the chain is complete
+    pub fn advance(&mut self) -> bool {
+        self.current += 1;
+        self.operation_url = None;
+        self.notification_id = Uuid::nil();
+        self.current < self.steps.len()
+    }
+
+    /// Message for a failure of the running step
+    pub fn failure_message(&self, error: &str) -> String {
+        match self.current_step() {
+            Some(step) => format!("{} ({})", step.on_failure, error),
+            None => error.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_chain_advances_through_steps() {
+        let mut chain = OperationChain::new(
+            "compute",
+            "web-01",
+            "Resized web-01",
+            vec![
+                ChainStep::new("stop_instance", json!({}), "Nothing changed"),
+                ChainStep::new("start_instance", json!({}), "Still stopped"),
+            ],
+        );
+        assert_eq!(chain.current_step().unwrap().sdk_method, "stop_instance");
+        assert!(chain.advance());
+        assert_eq!(chain.current_step().unwrap().sdk_method, "start_instance");
+        assert_eq!(chain.failure_message("quota"), "Still stopped (quota)");
+        assert!(!chain.advance());
+        assert!(chain.current_step().is_none());
+    }
+}
@@ -0,0 +1,34 @@
+//! Clipboard Support
+//!
+//! Copies text to the system clipboard using the OSC 52 terminal escape
+//! sequence. This works over SSH and inside tmux (with `set-clipboard on`)
+//! without any clipboard daemon on the host.
+
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::io::Write;
+
+/// Build the OSC 52 sequence that sets the clipboard to `text`
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+/// Copy text to the clipboard by writing OSC 52 to the terminal
+pub fn copy(text: &str) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("hello"), "\x1b]52;c;aGVsbG8=\x07");
+        assert_eq!(osc52_sequence(""), "\x1b]52;c;\x07");
+    }
+}
@@ -0,0 +1,464 @@
+//! Describe View Document Model
+//!
+//! Flattens a JSON value into display lines for the describe view, with
+//! support for folding containers, rendering as JSON or YAML, and searching.
+//!
+//! Every line carries the JSON pointer (RFC 6901) of the value it belongs to,
+//! so folding, copying and search work the same in both formats.
+
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Output format of the describe view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DocFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl DocFormat {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Json => Self::Yaml,
+            Self::Yaml => Self::Json,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+        }
+    }
+}
+
+/// Kind of a rendered line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// Scalar value or empty container
+    Leaf,
+    /// Opening line of an object/array (folded or not)
+    Open { folded: bool },
+    /// Closing bracket of an object/array (JSON only)
+    Close,
+}
+
+/// A single rendered line of the document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocLine {
+    pub text: String,
+    /// JSON pointer of the value this line belongs to ("" for the root)
+    pub pointer: String,
+    pub kind: LineKind,
+}
+
+impl DocLine {
+    pub fn is_foldable(&self) -> bool {
+        !matches!(self.kind, LineKind::Leaf) && !self.pointer.is_empty()
+    }
+}
+
+/// Render a value into lines, skipping the contents of collapsed containers
+pub fn render_lines(value: &Value, format: DocFormat, collapsed: &HashSet<String>) -> Vec<DocLine> {
+    let mut lines = Vec::new();
+    match format {
+        DocFormat::Json => render_json(value, "", None, 0, true, collapsed, &mut lines),
+        DocFormat::Yaml => {
+            if is_empty_or_scalar(value) {
+                lines.push(leaf("", yaml_scalar(value)));
+            } else {
+                render_yaml_children(value, "", 0, collapsed, &mut lines);
+            }
+        },
+    }
+    lines
+}
+
+fn leaf(pointer: &str, text: String) -> DocLine {
+    DocLine {
+        text,
+        pointer: pointer.to_string(),
+        kind: LineKind::Leaf,
+    }
+}
+
+fn is_empty_or_scalar(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => map.is_empty(),
+        Value::Array(arr) => arr.is_empty(),
+        _ => true,
+    }
+}
+
+/// Short summary shown in place of a folded container
+fn folded_summary(value: &Value) -> String {
+    match value {
+        Value::Object(map) => format!("{{ {} keys }}", map.len()),
+        Value::Array(arr) => format!("[ {} items ]", arr.len()),
+        _ => String::new(),
+    }
+}
+
+/// Iterate over (child pointer, key label, child value) of a container
+fn children<'a>(value: &'a Value, pointer: &str) -> Vec<(String, Option<&'a str>, &'a Value)> {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| (child_pointer(pointer, k), Some(k.as_str()), v))
+            .collect(),
+        Value::Array(arr) => arr
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (child_pointer(pointer, &i.to_string()), None, v))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn render_json(
+    value: &Value,
+    pointer: &str,
+    key: Option<&str>,
+    depth: usize,
+    last: bool,
+    collapsed: &HashSet<String>,
+    lines: &mut Vec<DocLine>,
+) {
+    let indent = "  ".repeat(depth);
+    let prefix = match key {
+        Some(k) => format!(
+            "{}{}: ",
+            indent,
+            serde_json::to_string(k).unwrap_or_default()
+        ),
+        None => indent.clone(),
+    };
+    let comma = if last { "" } else { "," };
+
+    if is_empty_or_scalar(value) {
+        let rendered = serde_json::to_string(value).unwrap_or_default();
+        lines.push(leaf(pointer, format!("{}{}{}", prefix, rendered, comma)));
+        return;
+    }
+
+    if !pointer.is_empty() && collapsed.contains(pointer) {
+        lines.push(DocLine {
+            text: format!("{}{}{}", prefix, folded_summary(value), comma),
+            pointer: pointer.to_string(),
+            kind: LineKind::Open { folded: true },
+        });
+        return;
+    }
+
+    let (open, close) = if value.is_object() {
+        ('{', '}')
+    } else {
+        ('[', ']')
+    };
+    lines.push(DocLine {
+        text: format!("{}{}", prefix, open),
+        pointer: pointer.to_string(),
+        kind: LineKind::Open { folded: false },
+    });
+
+    let kids = children(value, pointer);
+    let count = kids.len();
+    for (i, (child_ptr, child_key, child)) in kids.into_iter().enumerate() {
+        render_json(
+            child,
+            &child_ptr,
+            child_key,
+            depth + 1,
+            i + 1 == count,
+            collapsed,
+            lines,
+        );
+    }
+
+    lines.push(DocLine {
+        text: format!("{}{}{}", indent, close, comma),
+        pointer: pointer.to_string(),
+        kind: LineKind::Close,
+    });
+}
+
+fn render_yaml_children(
+    value: &Value,
+    pointer: &str,
+    depth: usize,
+    collapsed: &HashSet<String>,
+    lines: &mut Vec<DocLine>,
+) {
+    let indent = "  ".repeat(depth);
+    for (child_ptr, child_key, child) in children(value, pointer) {
+        let prefix = match child_key {
+            Some(k) => format!("{}{}:", indent, yaml_scalar(&Value::String(k.to_string()))),
+            None => format!("{}-", indent),
+        };
+
+        if is_empty_or_scalar(child) {
+            lines.push(leaf(
+                &child_ptr,
+                format!("{} {}", prefix, yaml_scalar(child)),
+            ));
+        } else if collapsed.contains(&child_ptr) {
+            lines.push(DocLine {
+                text: format!("{} {}", prefix, folded_summary(child)),
+                pointer: child_ptr,
+                kind: LineKind::Open { folded: true },
+            });
+        } else {
+            lines.push(DocLine {
+                text: prefix,
+                pointer: child_ptr.clone(),
+                kind: LineKind::Open { folded: false },
+            });
+            render_yaml_children(child, &child_ptr, depth + 1, collapsed, lines);
+        }
+    }
+}
+
+/// Render a scalar (or empty container) as a single-line YAML value
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        // Multi-line strings would break the one-line-per-value model,
+        // so fall back to a double-quoted (JSON-compatible) string
+        Value::String(s) if s.contains('\n') => serde_json::to_string(s).unwrap_or_default(),
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
+        _ => serde_yaml::to_string(value)
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// Build the pointer of a child element
+pub fn child_pointer(parent: &str, key: &str) -> String {
+    format!("{}/{}", parent, key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Split a JSON pointer into unescaped segments
+fn pointer_segments(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+/// Pointers of all strict ancestors of a pointer (excluding the root)
+pub fn ancestor_pointers(pointer: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = pointer;
+    while let Some(pos) = current.rfind('/') {
+        current = &current[..pos];
+        if !current.is_empty() {
+            result.push(current.to_string());
+        }
+    }
+    result
+}
+
+/// Pointer of the parent container ("" for top-level members)
+pub fn parent_pointer(pointer: &str) -> String {
+    pointer
+        .rfind('/')
+        .map(|pos| pointer[..pos].to_string())
+        .unwrap_or_default()
+}
+
+/// Pointers of all non-empty containers below the root (for collapse-all)
+pub fn container_pointers(value: &Value) -> Vec<String> {
+    fn walk(value: &Value, pointer: &str, out: &mut Vec<String>) {
+        for (child_ptr, _, child) in children(value, pointer) {
+            if !is_empty_or_scalar(child) {
+                out.push(child_ptr.clone());
+                walk(child, &child_ptr, out);
+            }
+        }
+    }
+    let mut out = Vec::new();
+    walk(value, "", &mut out);
+    out
+}
+
+/// Convert a JSON pointer to the dot path notation used by resource
+/// definitions (e.g. `networkInterfaces.0.networkIP`). Keys containing dots
+/// are written in bracket form.
+pub fn display_path(pointer: &str) -> String {
+    let mut path = String::new();
+    for segment in pointer_segments(pointer) {
+        if segment.contains('.') || segment.contains(' ') {
+            path.push_str(&format!(
+                "[{}]",
+                serde_json::to_string(&segment).unwrap_or_default()
+            ));
+        } else {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(&segment);
+        }
+    }
+    path
+}
+
+/// Text to copy for a value: raw strings, compact scalars, pretty containers
+pub fn value_to_text(value: &Value, format: DocFormat) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Object(_) | Value::Array(_) => match format {
+            DocFormat::Json => serde_json::to_string_pretty(value).unwrap_or_default(),
+            DocFormat::Yaml => serde_yaml::to_string(value).unwrap_or_default(),
+        },
+        _ => value.to_string(),
+    }
+}
+
+/// Pointers of lines matching a case-insensitive query, searching the fully
+/// expanded document so matches inside folded containers are found
+pub fn find_matches(value: &Value, format: DocFormat, query: &str) -> Vec<String> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let needle = query.to_lowercase();
+    render_lines(value, format, &HashSet::new())
+        .into_iter()
+        .filter(|line| line.kind != LineKind::Close)
+        .filter(|line| line.text.to_lowercase().contains(&needle))
+        .map(|line| line.pointer)
+        .collect()
+}
+
+/// First googleapis.com URL in a rendered line, if any
+pub fn find_link(text: &str) -> Option<&str> {
+    let start = text.find("https://")?;
+    let url = &text[start..];
+    let end = url
+        .find(|c: char| c == '"' || c == '\'' || c.is_whitespace())
+        .unwrap_or(url.len());
+    let url = &url[..end];
+    let host = url["https://".len()..]
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    host.ends_with(".googleapis.com").then_some(url)
+}
+
+/// Index of the (non-closing) line for a pointer
+pub fn line_index(lines: &[DocLine], pointer: &str) -> Option<usize> {
+    lines
+        .iter()
+        .position(|l| l.pointer == pointer && l.kind != LineKind::Close)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Value {
+        json!({
+            "name": "web-01",
+            "labels": {"app.kubernetes.io/name": "web", "env": "prod"},
+            "networkInterfaces": [{"networkIP": "10.0.0.2"}],
+            "tags": []
+        })
+    }
+
+    #[test]
+    fn test_json_lines_match_pretty_print() {
+        let value = sample();
+        let lines = render_lines(&value, DocFormat::Json, &HashSet::new());
+        let text: Vec<&str> = lines.iter().map(|l| l.text.as_str()).collect();
+        let expected = serde_json::to_string_pretty(&value).unwrap();
+        assert_eq!(text.join("\n"), expected);
+    }
+
+    #[test]
+    fn test_fold_hides_children() {
+        let value = sample();
+        let mut collapsed = HashSet::new();
+        collapsed.insert("/labels".to_string());
+        let lines = render_lines(&value, DocFormat::Json, &collapsed);
+        let labels = lines.iter().find(|l| l.pointer == "/labels").unwrap();
+        assert_eq!(labels.kind, LineKind::Open { folded: true });
+        assert!(labels.text.contains("{ 2 keys }"));
+        assert!(!lines.iter().any(|l| l.pointer.starts_with("/labels/")));
+    }
+
+    #[test]
+    fn test_yaml_lines() {
+        let value = sample();
+        let lines = render_lines(&value, DocFormat::Yaml, &HashSet::new());
+        let text: Vec<&str> = lines.iter().map(|l| l.text.as_str()).collect();
+        assert!(text.contains(&"name: web-01"));
+        assert!(text.contains(&"networkInterfaces:"));
+        assert!(text.contains(&"  -"));
+        assert!(text.contains(&"    networkIP: 10.0.0.2"));
+        assert!(text.contains(&"tags: []"));
+        // Rendered YAML must parse back to the same value
+        let parsed: Value = serde_yaml::from_str(&text.join("\n")).unwrap();
+        assert_eq!(parsed, value);
+    }
+
+    #[test]
+    fn test_pointer_helpers() {
+        assert_eq!(child_pointer("/labels", "a/b"), "/labels/a~1b");
+        assert_eq!(
+            ancestor_pointers("/networkInterfaces/0/networkIP"),
+            vec!["/networkInterfaces/0", "/networkInterfaces"]
+        );
+        assert_eq!(parent_pointer("/name"), "");
+        assert_eq!(
+            display_path("/networkInterfaces/0/networkIP"),
+            "networkInterfaces.0.networkIP"
+        );
+        assert_eq!(
+            display_path("/labels/app.kubernetes.io~1name"),
+            "labels[\"app.kubernetes.io/name\"]"
+        );
+    }
+
+    #[test]
+    fn test_container_pointers_skip_empty() {
+        let pointers = container_pointers(&sample());
+        assert!(pointers.contains(&"/labels".to_string()));
+        assert!(pointers.contains(&"/networkInterfaces/0".to_string()));
+        assert!(!pointers.contains(&"/tags".to_string()));
+    }
+
+    #[test]
+    fn test_find_matches_searches_folded_content() {
+        let value = sample();
+        let matches = find_matches(&value, DocFormat::Json, "NETWORKip");
+        assert_eq!(matches, vec!["/networkInterfaces/0/networkIP"]);
+    }
+
+    #[test]
+    fn test_find_link() {
+        assert_eq!(
+            find_link(
+                r#"  "source": "https://www.googleapis.com/compute/v1/projects/p/zones/z/disks/d","#
+            ),
+            Some("https://www.googleapis.com/compute/v1/projects/p/zones/z/disks/d")
+        );
+        assert_eq!(
+            find_link("  - https://www.googleapis.com/storage/v1/b/bucket"),
+            Some("https://www.googleapis.com/storage/v1/b/bucket")
+        );
+        assert_eq!(find_link(r#"  "docs": "https://example.com/x""#), None);
+        assert_eq!(find_link(r#"  "name": "web-01""#), None);
+    }
+
+    #[test]
+    fn test_value_to_text() {
+        assert_eq!(value_to_text(&json!("abc"), DocFormat::Json), "abc");
+        assert_eq!(value_to_text(&json!(42), DocFormat::Json), "42");
+        assert_eq!(
+            value_to_text(&json!({"a": 1}), DocFormat::Yaml).trim(),
+            "a: 1"
+        );
+    }
+}
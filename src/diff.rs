@@ -0,0 +1,190 @@
+//! Structural JSON Diff
+//!
+//! Compares two resources (or two snapshots of the same resource) path by
+//! path. Objects are compared by key and arrays by index; paths use the same
+//! dot notation as resource definitions (e.g. `networkInterfaces.0.networkIP`).
+
+use serde_json::Value;
+
+/// Kind of difference at a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Only present on the right side
+    Added,
+    /// Only present on the left side
+    Removed,
+    /// Present on both sides with different values
+    Changed,
+}
+
+impl DiffKind {
+    pub fn symbol(self) -> char {
+        match self {
+            Self::Added => '+',
+            Self::Removed => '-',
+            Self::Changed => '~',
+        }
+    }
+}
+
+/// A single difference between two values
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffEntry {
+    pub path: String,
+    pub kind: DiffKind,
+    pub left: Option<Value>,
+    pub right: Option<Value>,
+}
+
+/// Result of a diff, including how many paths were skipped
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiffResult {
+    pub entries: Vec<DiffEntry>,
+    pub ignored: usize,
+}
+
+/// Compute the differences between two values, skipping ignored paths.
+///
+/// An ignore entry matches a path when it equals the full dot path or the
+/// last key of the path, so `fingerprint` also skips
+/// `networkInterfaces.0.fingerprint`.
+pub fn diff_values(left: &Value, right: &Value, ignore: &[String]) -> DiffResult {
+    let mut result = DiffResult::default();
+    walk(left, right, "", ignore, &mut result);
+    result
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn is_ignored(path: &str, key: &str, ignore: &[String]) -> bool {
+    ignore.iter().any(|i| i == path || i == key)
+}
+
+fn walk(left: &Value, right: &Value, path: &str, ignore: &[String], result: &mut DiffResult) {
+    match (left, right) {
+        (Value::Object(l), Value::Object(r)) => {
+            let mut keys: Vec<&String> = l.keys().chain(r.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child_path = join(path, key);
+                if is_ignored(&child_path, key, ignore) {
+                    if l.get(key) != r.get(key) {
+                        result.ignored += 1;
+                    }
+                    continue;
+                }
+                compare_child(l.get(key), r.get(key), &child_path, ignore, result);
+            }
+        },
+        (Value::Array(l), Value::Array(r)) => {
+            for i in 0..l.len().max(r.len()) {
+                let child_path = join(path, &i.to_string());
+                compare_child(l.get(i), r.get(i), &child_path, ignore, result);
+            }
+        },
+        _ if left != right => result.entries.push(DiffEntry {
+            path: path.to_string(),
+            kind: DiffKind::Changed,
+            left: Some(left.clone()),
+            right: Some(right.clone()),
+        }),
+        _ => {},
+    }
+}
+
+fn compare_child(
+    left: Option<&Value>,
+    right: Option<&Value>,
+    path: &str,
+    ignore: &[String],
+    result: &mut DiffResult,
+) {
+    match (left, right) {
+        (Some(l), Some(r)) => walk(l, r, path, ignore, result),
+        (None, Some(r)) => result.entries.push(DiffEntry {
+            path: path.to_string(),
+            kind: DiffKind::Added,
+            left: None,
+            right: Some(r.clone()),
+        }),
+        (Some(l), None) => result.entries.push(DiffEntry {
+            path: path.to_string(),
+            kind: DiffKind::Removed,
+            left: Some(l.clone()),
+            right: None,
+        }),
+        (None, None) => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_identical_values_have_no_diff() {
+        let value = json!({"name": "web-01", "tags": ["a", "b"]});
+        let result = diff_values(&value, &value, &[]);
+        assert!(result.entries.is_empty());
+        assert_eq!(result.ignored, 0);
+    }
+
+    #[test]
+    fn test_added_removed_changed() {
+        let left = json!({"name": "web-01", "machineType": "e2-medium", "tags": ["http"]});
+        let right =
+            json!({"name": "web-02", "machineType": "e2-medium", "labels": {"env": "prod"}});
+        let result = diff_values(&left, &right, &[]);
+
+        let summary: Vec<(String, DiffKind)> = result
+            .entries
+            .iter()
+            .map(|e| (e.path.clone(), e.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("labels".to_string(), DiffKind::Added),
+                ("name".to_string(), DiffKind::Changed),
+                ("tags".to_string(), DiffKind::Removed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_paths_use_dot_notation() {
+        let left = json!({"networkInterfaces": [{"networkIP": "10.0.0.2"}]});
+        let right =
+            json!({"networkInterfaces": [{"networkIP": "10.0.0.3"}, {"networkIP": "10.0.0.4"}]});
+        let result = diff_values(&left, &right, &[]);
+        assert_eq!(result.entries.len(), 2);
+        assert_eq!(result.entries[0].path, "networkInterfaces.0.networkIP");
+        assert_eq!(result.entries[0].kind, DiffKind::Changed);
+        assert_eq!(result.entries[1].path, "networkInterfaces.1");
+        assert_eq!(result.entries[1].kind, DiffKind::Added);
+    }
+
+    #[test]
+    fn test_ignore_by_key_and_path() {
+        let left = json!({"id": "1", "labelFingerprint": "a", "metadata": {"fingerprint": "x", "items": []}});
+        let right = json!({"id": "2", "labelFingerprint": "b", "metadata": {"fingerprint": "y", "items": []}});
+        let ignore = vec!["id".to_string(), "fingerprint".to_string()];
+        let result = diff_values(&left, &right, &ignore);
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].path, "labelFingerprint");
+        assert_eq!(result.ignored, 2);
+
+        let ignore = vec!["labelFingerprint".to_string(), "metadata".to_string()];
+        let result = diff_values(&left, &right, &ignore);
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].path, "id");
+    }
+}
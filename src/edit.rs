@@ -0,0 +1,452 @@
+//! Attribute Editing
+//!
+//! Labels, metadata and network tags are edited as a YAML document in
+//! `$EDITOR`. The edited document is compared with the current values and
+//! turned into the update calls of the resource, each guarded by the
+//! fingerprint (or bucket metageneration) the values were loaded with, so a
+//! concurrent change makes the update fail instead of being overwritten.
+
+use crate::shell::validate_gcp_resource_name;
+use crate::wizard::validate_label;
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Map, Value};
+use std::process::Command;
+
+/// Kind of resource whose attributes can be edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditKind {
+    /// Labels, metadata and network tags
+    Instance,
+    /// Labels
+    Disk,
+    /// Labels
+    Bucket,
+}
+
+impl EditKind {
+    pub fn from_resource_key(key: &str) -> Option<Self> {
+        match key {
+            "compute-instances" => Some(Self::Instance),
+            "compute-disks" => Some(Self::Disk),
+            "storage-buckets" => Some(Self::Bucket),
+            _ => None,
+        }
+    }
+
+    fn sections(self) -> &'static [&'static str] {
+        match self {
+            Self::Instance => &["labels", "metadata", "tags"],
+            Self::Disk | Self::Bucket => &["labels"],
+        }
+    }
+}
+
+/// A single update call of an edit
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditUpdate {
+    /// Edited section (`labels`, `metadata` or `tags`)
+    pub section: &'static str,
+    pub service: &'static str,
+    pub sdk_method: &'static str,
+    pub params: Value,
+}
+
+/// An edit waiting for confirmation in the diff preview
+#[derive(Debug, Clone)]
+pub struct PendingEdit {
+    pub name: String,
+    /// The list item the edit started from
+    pub item: Value,
+    /// Edited YAML, reopened by "edit again"
+    pub text: String,
+    pub updates: Vec<EditUpdate>,
+}
+
+/// Current labels, metadata and tags of an item as an editable document
+pub fn editable_document(kind: EditKind, item: &Value) -> Value {
+    let mut doc = Map::new();
+    for section in kind.sections() {
+        let value = match *section {
+            "labels" => item.get("labels").cloned().unwrap_or_else(|| json!({})),
+            "metadata" => {
+                let entries: Map<String, Value> = item
+                    .pointer("/metadata/items")
+                    .and_then(|v| v.as_array())
+                    .map(|items| {
+                        items
+                            .iter()
+                            .filter_map(|entry| {
+                                let key = entry.get("key")?.as_str()?;
+                                let value = entry.get("value").cloned().unwrap_or(json!(""));
+                                Some((key.to_string(), value))
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Value::Object(entries)
+            },
+            _ => item
+                .pointer("/tags/items")
+                .cloned()
+                .unwrap_or_else(|| json!([])),
+        };
+        doc.insert(section.to_string(), value);
+    }
+    Value::Object(doc)
+}
+
+/// YAML shown in the editor, with a short explanation on top
+pub fn to_yaml(kind: EditKind, name: &str, doc: &Value) -> Result<String> {
+    let body = serde_yaml::to_string(doc).context("Failed to serialize attributes")?;
+    let hint = match kind {
+        EditKind::Instance => "# labels and metadata are key: value maps, tags a list.\n",
+        EditKind::Disk | EditKind::Bucket => "# labels is a key: value map.\n",
+    };
+    Ok(format!(
+        "# Editing {}. Save and quit to preview the changes,\n# quit without saving to cancel.\n{}{}",
+        name, hint, body
+    ))
+}
+
+/// Prefix the YAML with the error of the previous attempt (replacing an
+/// earlier error)
+pub fn with_error(text: &str, error: &str) -> String {
+    let rest: String = text
+        .lines()
+        .skip_while(|line| line.starts_with("# Error:"))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    format!("# Error: {}\n{}", error, rest)
+}
+
+/// Parse and validate an edited document
+pub fn parse_document(kind: EditKind, text: &str) -> Result<Value, String> {
+    let parsed: Value = serde_yaml::from_str(text).map_err(|e| format!("Invalid YAML: {}", e))?;
+    let mut parsed = match parsed {
+        Value::Object(map) => map,
+        Value::Null => Map::new(),
+        _ => return Err("Expected a mapping at the top level".to_string()),
+    };
+
+    if let Some(unknown) = parsed
+        .keys()
+        .find(|k| !kind.sections().contains(&k.as_str()))
+    {
+        return Err(format!(
+            "Unknown section '{}' (expected {})",
+            unknown,
+            kind.sections().join(", ")
+        ));
+    }
+
+    let mut doc = Map::new();
+    for section in kind.sections() {
+        let value = parsed.remove(*section).unwrap_or(Value::Null);
+        let value = match *section {
+            "tags" => Value::Array(parse_tags(value)?),
+            "labels" => {
+                let labels = string_map(section, value)?;
+                for (key, value) in &labels {
+                    validate_label(key, value.as_str().unwrap_or_default())?;
+                }
+                Value::Object(labels)
+            },
+            _ => Value::Object(string_map(section, value)?),
+        };
+        doc.insert(section.to_string(), value);
+    }
+    Ok(Value::Object(doc))
+}
+
+/// A map of scalars, converted to strings (`version: 2` is label "2")
+fn string_map(section: &str, value: Value) -> Result<Map<String, Value>, String> {
+    let map = match value {
+        Value::Null => return Ok(Map::new()),
+        Value::Object(map) => map,
+        _ => return Err(format!("'{}' must be a key: value map", section)),
+    };
+    map.into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(s) => s,
+                Value::Null => String::new(),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                _ => return Err(format!("Value of {}.{} must be a string", section, key)),
+            };
+            Ok((key, Value::String(value)))
+        })
+        .collect()
+}
+
+fn parse_tags(value: Value) -> Result<Vec<Value>, String> {
+    let tags = match value {
+        Value::Null => return Ok(Vec::new()),
+        Value::Array(tags) => tags,
+        _ => return Err("'tags' must be a list".to_string()),
+    };
+    tags.into_iter()
+        .map(|tag| {
+            let tag = tag
+                .as_str()
+                .ok_or_else(|| "Network tags must be strings".to_string())?
+                .to_string();
+            validate_gcp_resource_name(&tag, "Network tag").map_err(|e| e.to_string())?;
+            Ok(Value::String(tag))
+        })
+        .collect()
+}
+
+/// Update calls turning `original` into `edited`, one per changed section
+pub fn plan_updates(
+    kind: EditKind,
+    item: &Value,
+    original: &Value,
+    edited: &Value,
+) -> Result<Vec<EditUpdate>, String> {
+    let name = item
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let zone = item
+        .get("zone_short")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let fingerprint = |pointer: &str| {
+        item.pointer(pointer)
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .ok_or_else(|| {
+                format!(
+                    "{} has no fingerprint ({}); refresh and edit again",
+                    name, pointer
+                )
+            })
+    };
+
+    let mut updates = Vec::new();
+    for section in kind.sections() {
+        let (before, after) = (&original[*section], &edited[*section]);
+        if before == after {
+            continue;
+        }
+        let update = match (kind, *section) {
+            (EditKind::Bucket, _) => {
+                // PATCH merges labels, so removed ones are set to null
+                let mut labels = after.as_object().cloned().unwrap_or_default();
+                for key in before.as_object().map(|m| m.keys()).into_iter().flatten() {
+                    labels.entry(key.clone()).or_insert(Value::Null);
+                }
+                let metageneration = item
+                    .get("metageneration")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        format!("{} has no metageneration; refresh and edit again", name)
+                    })?;
+                EditUpdate {
+                    section,
+                    service: "storage",
+                    sdk_method: "patch_bucket",
+                    params: json!({
+                        "body": { "labels": labels },
+                        "if_metageneration_match": metageneration,
+                    }),
+                }
+            },
+            (_, "labels") => EditUpdate {
+                section,
+                service: "compute",
+                sdk_method: if kind == EditKind::Disk {
+                    "set_disk_labels"
+                } else {
+                    "set_instance_labels"
+                },
+                params: json!({
+                    "zone": zone,
+                    "body": { "labels": after, "labelFingerprint": fingerprint("/labelFingerprint")? },
+                }),
+            },
+            (_, "metadata") => {
+                let items: Vec<Value> = after
+                    .as_object()
+                    .map(|m| {
+                        m.iter()
+                            .map(|(key, value)| json!({ "key": key, "value": value }))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                EditUpdate {
+                    section,
+                    service: "compute",
+                    sdk_method: "set_instance_metadata",
+                    params: json!({
+                        "zone": zone,
+                        "body": { "items": items, "fingerprint": fingerprint("/metadata/fingerprint")? },
+                    }),
+                }
+            },
+            _ => EditUpdate {
+                section,
+                service: "compute",
+                sdk_method: "set_instance_tags",
+                params: json!({
+                    "zone": zone,
+                    "body": { "items": after, "fingerprint": fingerprint("/tags/fingerprint")? },
+                }),
+            },
+        };
+        updates.push(update);
+    }
+    Ok(updates)
+}
+
+/// Message for a failed update, spelling out fingerprint/etag conflicts
+pub fn update_error(update: &EditUpdate, name: &str, error: &anyhow::Error) -> String {
+    let text = error.to_string();
+    if text.contains("412") {
+        format!(
+            "{} of {} not updated: they were changed by someone else since they were loaded. Refresh (R) and edit again.",
+            update.section, name
+        )
+    } else {
+        format!(
+            "{} of {} not updated: {}",
+            update.section,
+            name,
+            crate::gcp::client::format_gcp_error(error)
+        )
+    }
+}
+
+/// Open `text` in `$VISUAL`/`$EDITOR` (default `vi`) and return the saved
+/// content. The terminal must be suspended by the caller.
+///
+/// The editor variable may carry arguments (e.g. `code --wait`); it is split
+/// on whitespace and run directly, never through a shell.
+pub fn run_editor(text: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+
+    let path = std::env::temp_dir().join(format!("tgcp-edit-{}.yaml", uuid::Uuid::new_v4()));
+    std::fs::write(&path, text).context("Failed to write temporary file")?;
+
+    let status = Command::new(program).args(parts).arg(&path).status();
+    let content = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+
+    let status = status.with_context(|| format!("Failed to run editor '{}'", program))?;
+    if !status.success() {
+        return Err(anyhow!("Editor exited with {}", status));
+    }
+    content.context("Failed to read edited file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance() -> Value {
+        json!({
+            "name": "web-01",
+            "zone_short": "us-central1-a",
+            "labels": { "env": "prod" },
+            "labelFingerprint": "lf",
+            "metadata": {
+                "fingerprint": "mf",
+                "items": [{ "key": "enable-oslogin", "value": "TRUE" }],
+            },
+            "tags": { "fingerprint": "tf", "items": ["http-server"] },
+        })
+    }
+
+    #[test]
+    fn test_document_round_trip() {
+        let doc = editable_document(EditKind::Instance, &instance());
+        assert_eq!(
+            doc,
+            json!({
+                "labels": { "env": "prod" },
+                "metadata": { "enable-oslogin": "TRUE" },
+                "tags": ["http-server"],
+            })
+        );
+        let text = to_yaml(EditKind::Instance, "web-01", &doc).unwrap();
+        assert_eq!(parse_document(EditKind::Instance, &text).unwrap(), doc);
+    }
+
+    #[test]
+    fn test_parse_document_validation() {
+        let doc = parse_document(EditKind::Disk, "labels:\n  version: 2\n").unwrap();
+        assert_eq!(doc, json!({ "labels": { "version": "2" } }));
+        assert_eq!(
+            parse_document(EditKind::Disk, "").unwrap(),
+            json!({ "labels": {} })
+        );
+
+        assert!(parse_document(EditKind::Disk, "labels:\n  Env: prod\n").is_err());
+        assert!(parse_document(EditKind::Disk, "tags: [a]\n").is_err());
+        assert!(parse_document(EditKind::Instance, "tags: [Not_Valid]\n").is_err());
+        assert!(parse_document(EditKind::Instance, "labels: [\n").is_err());
+    }
+
+    #[test]
+    fn test_plan_updates_only_changed_sections() {
+        let item = instance();
+        let original = editable_document(EditKind::Instance, &item);
+        let mut edited = original.clone();
+        edited["tags"] = json!(["http-server", "https-server"]);
+
+        let updates = plan_updates(EditKind::Instance, &item, &original, &edited).unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].sdk_method, "set_instance_tags");
+        assert_eq!(
+            updates[0].params,
+            json!({
+                "zone": "us-central1-a",
+                "body": { "items": ["http-server", "https-server"], "fingerprint": "tf" },
+            })
+        );
+    }
+
+    #[test]
+    fn test_bucket_patch_removes_labels_with_null() {
+        let item = json!({ "name": "b", "labels": { "a": "1", "b": "2" }, "metageneration": "7" });
+        let original = editable_document(EditKind::Bucket, &item);
+        let edited = json!({ "labels": { "a": "1", "c": "3" } });
+
+        let updates = plan_updates(EditKind::Bucket, &item, &original, &edited).unwrap();
+        assert_eq!(
+            updates[0].params,
+            json!({
+                "body": { "labels": { "a": "1", "b": null, "c": "3" } },
+                "if_metageneration_match": "7",
+            })
+        );
+    }
+
+    #[test]
+    fn test_conflict_error_is_explicit() {
+        let update = EditUpdate {
+            section: "labels",
+            service: "compute",
+            sdk_method: "set_instance_labels",
+            params: Value::Null,
+        };
+        let message = update_error(
+            &update,
+            "web-01",
+            &anyhow!("API request failed: 412 Precondition Failed"),
+        );
+        assert!(message.contains("changed by someone else"));
+    }
+
+    #[test]
+    fn test_with_error_replaces_previous_error() {
+        let text = with_error("labels: {}\n", "first");
+        let text = with_error(&text, "second");
+        assert_eq!(text, "# Error: second\nlabels: {}\n");
+    }
+}
@@ -3,6 +3,8 @@
 //! Keyboard and event handling for tgcp.
 
 use crate::app::{App, Mode};
+use crate::describe::find_link;
+use crate::form::{FieldKind, FormStage};
 use crate::gcp::client::extract_operation_url;
 use crate::resource::{execute_action, extract_json_value};
 use crate::shell::{self, ShellResult, SshOptions};
@@ -17,6 +19,9 @@ use std::time::Duration;
 /// Timeout for double-key sequences like 'gg' (go to top)
 const DOUBLE_KEY_TIMEOUT_MS: u64 = 1000;
 
+/// Delay before a lone prefix key (e.g. 'y' in describe) runs its own command
+const PENDING_KEY_TIMEOUT_MS: u64 = 400;
+
 /// Number of items to scroll for page up/down
 const PAGE_SCROLL_SIZE: usize = 10;
 
@@ -29,6 +34,8 @@ pub async fn handle_events(app: &mut App) -> Result<bool> {
         if let Event::Key(key) = read()? {
             return handle_key_event(app, key.code, key.modifiers).await;
         }
+    } else {
+        resolve_pending_key(app);
     }
     Ok(false)
 }
@@ -47,9 +54,19 @@ async fn handle_key_event(app: &mut App, code: KeyCode, modifiers: KeyModifiers)
         Mode::Warning => handle_warning_mode(app, code),
         Mode::Projects => handle_projects_mode(app, code, modifiers).await,
         Mode::Zones => handle_zones_mode(app, code, modifiers).await,
-        Mode::Describe => handle_describe_mode(app, code, modifiers),
+        Mode::Describe => handle_describe_mode(app, code, modifiers).await,
         Mode::Notifications => handle_notifications_mode(app, code),
         Mode::ColumnConfig => handle_column_config_mode(app, code),
+        Mode::Diff => handle_diff_mode(app, code, modifiers).await,
+        Mode::Serial => handle_serial_mode(app, code, modifiers).await,
+        Mode::Firewall => handle_firewall_mode(app, code, modifiers).await,
+        Mode::Tunnels => handle_tunnels_mode(app, code, modifiers),
+        Mode::RunOutput => handle_run_output_mode(app, code, modifiers),
+        Mode::Preview => handle_preview_mode(app, code, modifiers).await,
+        Mode::Bucket => handle_bucket_mode(app, code, modifiers).await,
+        Mode::Versions => handle_versions_mode(app, code).await,
+        Mode::Yank => handle_yank_mode(app, code),
+        Mode::Form => handle_form_mode(app, code, modifiers).await,
     }
 }
 
@@ -172,6 +189,9 @@ async fn handle_normal_mode(app: &mut App, code: KeyCode, modifiers: KeyModifier
         },
 
         // Describe/Enter
+        KeyCode::Enter if app.selected_is_folder() => {
+            app.open_selected_folder().await?;
+        },
         KeyCode::Enter => {
             app.enter_describe_mode().await;
         },
@@ -195,15 +215,10 @@ async fn handle_normal_mode(app: &mut App, code: KeyCode, modifiers: KeyModifier
         },
 
         // Back navigation
-        KeyCode::Backspace | KeyCode::Left => {
-            if app.nav.parent_context.is_some() {
-                app.navigate_back().await?;
-            }
-        },
-        KeyCode::Char('b') => {
-            if app.nav.parent_context.is_some() {
-                app.navigate_back().await?;
-            }
+        KeyCode::Backspace | KeyCode::Left | KeyCode::Char('b')
+            if app.nav.parent_context.is_some() =>
+        {
+            app.navigate_back().await?;
         },
 
         // Projects
@@ -226,6 +241,11 @@ async fn handle_normal_mode(app: &mut App, code: KeyCode, modifiers: KeyModifier
             app.enter_column_config_mode();
         },
 
+        // Copy to clipboard
+        KeyCode::Char('y') => {
+            app.enter_yank_mode();
+        },
+
         // Delete action with Delete key (resolves Ctrl+D conflict)
         KeyCode::Delete => {
             if let Some(resource) = app.current_resource() {
@@ -278,12 +298,43 @@ async fn handle_normal_mode(app: &mut App, code: KeyCode, modifiers: KeyModifier
 }
 
 async fn handle_action(app: &mut App, action_def: &crate::resource::ActionDef) -> Result<()> {
-    // Shell actions don't respect readonly mode (they don't modify resources)
-    if app.readonly && !action_def.shell_action {
+    // Shell and view actions don't respect readonly mode (they don't modify
+    // resources)
+    if app.readonly && action_def.mutates() {
         app.show_warning("Read-only mode: actions are disabled");
         return Ok(());
     }
 
+    // Views (preview, details, listing toggles) pick up the selection
+    // themselves
+    if action_def.view {
+        app.open_view_action(&action_def.sdk_method).await;
+        return Ok(());
+    }
+
+    // Form actions (wizards) pick up the selected item themselves if needed
+    if action_def.form {
+        app.open_action_form(&action_def.sdk_method).await;
+        return Ok(());
+    }
+
+    // Refuse upfront to delete protected resources instead of letting the
+    // API reject the confirmed request
+    if action_def.sdk_method.starts_with("delete_") {
+        let protected = app.protected_selection();
+        if let Some(message) = crate::app::deletion_protection_refusal(&protected) {
+            app.show_warning(&message);
+            return Ok(());
+        }
+    }
+
+    if action_def.sdk_method == "delete_object" {
+        if let Some(message) = crate::storage::folder_delete_refusal(&app.action_items()) {
+            app.show_warning(&message);
+            return Ok(());
+        }
+    }
+
     let Some(resource) = app.current_resource() else {
         return Ok(());
     };
@@ -331,7 +382,7 @@ async fn handle_action(app: &mut App, action_def: &crate::resource::ActionDef) -
             &action_def.sdk_method,
             &app.client,
             &resource_id,
-            &serde_json::Value::Null,
+            &crate::app::action_params(&item),
         )
         .await;
 
@@ -359,6 +410,56 @@ async fn handle_action(app: &mut App, action_def: &crate::resource::ActionDef) -
     Ok(())
 }
 
+async fn handle_form_mode(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Result<bool> {
+    let Some(ref mut form) = app.form else {
+        app.exit_mode();
+        return Ok(false);
+    };
+
+    if matches!(form.stage, FormStage::Preview(_)) {
+        match code {
+            KeyCode::Enter => app.submit_form().await?,
+            KeyCode::Esc | KeyCode::Backspace => app.form_back(),
+            KeyCode::Char('j') | KeyCode::Down => form.preview_scroll += 1,
+            KeyCode::Char('k') | KeyCode::Up => {
+                form.preview_scroll = form.preview_scroll.saturating_sub(1);
+            },
+            _ => {},
+        }
+        return Ok(false);
+    }
+
+    match code {
+        KeyCode::Esc => app.form_back(),
+        KeyCode::Enter => app.advance_form().await,
+        KeyCode::Tab | KeyCode::Down => {
+            form.move_focus(true);
+        },
+        KeyCode::BackTab | KeyCode::Up => {
+            form.move_focus(false);
+        },
+        KeyCode::Left | KeyCode::Right => {
+            if let Some(field) = form.focused_field_mut() {
+                if !matches!(field.kind, FieldKind::Text | FieldKind::Number) {
+                    field.cycle(code == KeyCode::Right);
+                }
+            }
+        },
+        KeyCode::Backspace => {
+            if let Some(field) = form.focused_field_mut() {
+                field.backspace();
+            }
+        },
+        KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+            if let Some(field) = form.focused_field_mut() {
+                field.input_char(c);
+            }
+        },
+        _ => {},
+    }
+    Ok(false)
+}
+
 /// Handle bulk action on multiple selected resources
 async fn handle_bulk_action(
     app: &mut App,
@@ -394,6 +495,7 @@ async fn handle_bulk_action(
         service: resource.service.clone(),
         sdk_method: action_def.sdk_method.clone(),
         resource_id: bulk_resource_id,
+        params: serde_json::Value::Null,
         message,
         destructive: is_destructive,
         selected_yes: false,
@@ -460,12 +562,14 @@ async fn handle_shell_action(
         "ssh_instance_iap" => {
             execute_ssh_to_instance(app, resource_id, item, true);
         },
+        "serial_port_output" => {
+            app.open_serial_output(item).await;
+        },
+        "effective_firewall" => {
+            app.open_effective_firewall(item).await;
+        },
         "open_console" => {
-            let zone = extract_json_value(item, "zone_short");
-            let zone = if zone != "-" { zone } else { app.zone.clone() };
-
-            let url =
-                shell::console_url(&app.current_resource_key, resource_id, &app.project, &zone);
+            let url = app.console_url_for(item);
 
             let result = shell::open_browser(&url);
 
@@ -540,7 +644,7 @@ async fn handle_confirm_mode(
 ) -> Result<bool> {
     match code {
         KeyCode::Esc | KeyCode::Char('N') => {
-            app.exit_mode();
+            app.finish_confirm(false).await;
         },
         KeyCode::Left | KeyCode::Char('h') => {
             if let Some(ref mut pending) = app.pending_action {
@@ -580,7 +684,7 @@ async fn handle_confirm_mode(
                                 &pending.sdk_method,
                                 &app.client,
                                 resource_id,
-                                &serde_json::Value::Null,
+                                &pending.params,
                             )
                             .await;
 
@@ -630,7 +734,7 @@ async fn handle_confirm_mode(
                             &pending.sdk_method,
                             &app.client,
                             &pending.resource_id,
-                            &serde_json::Value::Null,
+                            &pending.params,
                         )
                         .await;
 
@@ -657,7 +761,7 @@ async fn handle_confirm_mode(
                     }
                 }
             }
-            app.exit_mode();
+            app.finish_confirm(true).await;
         },
         _ => {},
     }
@@ -666,10 +770,7 @@ async fn handle_confirm_mode(
 
 fn handle_warning_mode(app: &mut App, code: KeyCode) -> Result<bool> {
     match code {
-        KeyCode::Esc | KeyCode::Enter => {
-            app.warning_message = None;
-            app.exit_mode();
-        },
+        KeyCode::Esc | KeyCode::Enter => app.dismiss_warning(),
         _ => {},
     }
     Ok(false)
@@ -751,38 +852,451 @@ async fn handle_zones_mode(app: &mut App, code: KeyCode, modifiers: KeyModifiers
     handle_selector_mode(app, code, modifiers, SelectorType::Zones).await
 }
 
-fn handle_describe_mode(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Result<bool> {
+async fn handle_describe_mode(
+    app: &mut App,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+) -> Result<bool> {
+    // Search query input
+    if app.describe.search.input_active {
+        match code {
+            KeyCode::Esc => {
+                app.describe.clear_search();
+            },
+            KeyCode::Enter => {
+                app.describe.search.input_active = false;
+                app.describe.jump_to_first_match();
+            },
+            KeyCode::Backspace => {
+                app.describe.search.query.pop();
+                app.describe.update_search();
+            },
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                app.describe.search.query.push(c);
+                app.describe.update_search();
+            },
+            _ => {},
+        }
+        return Ok(false);
+    }
+
+    // Key sequences: za/zR/zM (folding) and yy (copy path)
+    if let Some((prev, time)) = app.last_key_press.take() {
+        let in_time = time.elapsed() < Duration::from_millis(DOUBLE_KEY_TIMEOUT_MS);
+        match (prev, code) {
+            (KeyCode::Char('z'), KeyCode::Char('a')) if in_time => {
+                app.describe.toggle_fold();
+                return Ok(false);
+            },
+            (KeyCode::Char('z'), KeyCode::Char('R')) if in_time => {
+                app.describe.expand_all();
+                return Ok(false);
+            },
+            (KeyCode::Char('z'), KeyCode::Char('M')) if in_time => {
+                app.describe.collapse_all();
+                return Ok(false);
+            },
+            (KeyCode::Char('y'), KeyCode::Char('y')) if in_time => {
+                if let Some(path) = app.describe.cursor_path() {
+                    let what = format!("path {}", path);
+                    app.copy_to_clipboard(&path, &what);
+                }
+                return Ok(false);
+            },
+            // A lone 'y' followed by another key toggles YAML first
+            (KeyCode::Char('y'), _) => app.describe.toggle_format(),
+            _ => {},
+        }
+    }
+
     match code {
+        KeyCode::Esc if !app.describe.search.query.is_empty() => {
+            app.describe.clear_search();
+        },
         KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => {
             app.exit_mode();
         },
         KeyCode::Char('j') | KeyCode::Down => {
-            app.describe.scroll = app.describe.scroll.saturating_add(1);
+            app.describe.move_cursor(1);
         },
         KeyCode::Char('k') | KeyCode::Up => {
-            app.describe.scroll = app.describe.scroll.saturating_sub(1);
+            app.describe.move_cursor(-1);
         },
         KeyCode::PageDown => {
-            app.describe.scroll = app.describe.scroll.saturating_add(10);
+            app.describe.move_cursor(PAGE_SCROLL_SIZE as isize);
         },
         KeyCode::PageUp => {
-            app.describe.scroll = app.describe.scroll.saturating_sub(10);
+            app.describe.move_cursor(-(PAGE_SCROLL_SIZE as isize));
         },
         KeyCode::Char('d') => {
             if modifiers.contains(KeyModifiers::CONTROL) {
-                app.describe.scroll = app.describe.scroll.saturating_add(10);
+                app.describe.move_cursor(PAGE_SCROLL_SIZE as isize);
             } else {
                 app.exit_mode();
             }
         },
         KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
-            app.describe.scroll = app.describe.scroll.saturating_sub(10);
+            app.describe.move_cursor(-(PAGE_SCROLL_SIZE as isize));
+        },
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.describe.cursor_to_top();
+        },
+        KeyCode::Char('G') | KeyCode::End => {
+            app.describe.cursor_to_bottom();
+        },
+        KeyCode::Tab => {
+            // Switch between the list row and the fetched details
+            app.describe.toggle_detail();
+        },
+        KeyCode::Enter => {
+            // Follow a resource reference (selfLink URL) under the cursor
+            let link = app
+                .describe
+                .cursor_line()
+                .and_then(|line| find_link(&line.text).map(str::to_string));
+            if let Some(url) = link {
+                app.follow_self_link(&url).await?;
+            }
+        },
+        KeyCode::Char('z') | KeyCode::Char('y') => {
+            // Start of a key sequence, resolved by the next key or on timeout
+            app.last_key_press = Some((code, std::time::Instant::now()));
+        },
+        KeyCode::Char('Y') => {
+            if let Some(text) = app.describe.cursor_value_text() {
+                app.copy_to_clipboard(&text, "value");
+            }
+        },
+        KeyCode::Char('/') => {
+            app.describe.clear_search();
+            app.describe.search.input_active = true;
+        },
+        KeyCode::Char('n') => {
+            app.describe.jump_to_match(true);
+        },
+        KeyCode::Char('N') => {
+            app.describe.jump_to_match(false);
+        },
+        _ => {},
+    }
+    Ok(false)
+}
+
+/// Resolve a pending single-key command once its sequence has timed out
+/// (a lone 'y' in the describe view toggles YAML)
+fn resolve_pending_key(app: &mut App) {
+    if app.mode != Mode::Describe {
+        return;
+    }
+    if let Some((KeyCode::Char('y'), time)) = app.last_key_press {
+        if time.elapsed() >= Duration::from_millis(PENDING_KEY_TIMEOUT_MS) {
+            app.last_key_press = None;
+            app.describe.toggle_format();
+        }
+    }
+}
+
+async fn handle_diff_mode(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Result<bool> {
+    // Previewing a labels/metadata/tags edit
+    if app.pending_edit.is_some() {
+        match code {
+            KeyCode::Enter => {
+                app.apply_edit().await?;
+                return Ok(false);
+            },
+            KeyCode::Char('e') => {
+                app.reedit_attributes().await;
+                return Ok(false);
+            },
+            _ => {},
+        }
+    }
+
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => {
+            app.exit_mode();
+        },
+        KeyCode::Char('j') | KeyCode::Down => app.diff_scroll(1),
+        KeyCode::Char('k') | KeyCode::Up => app.diff_scroll(-1),
+        KeyCode::PageDown => app.diff_scroll(PAGE_SCROLL_SIZE as isize),
+        KeyCode::PageUp => app.diff_scroll(-(PAGE_SCROLL_SIZE as isize)),
+        KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => {
+            app.diff_scroll(PAGE_SCROLL_SIZE as isize);
+        },
+        KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
+            app.diff_scroll(-(PAGE_SCROLL_SIZE as isize));
         },
         KeyCode::Char('g') | KeyCode::Home => {
-            app.describe.scroll = 0;
+            if let Some(ref mut state) = app.diff_state {
+                state.scroll = 0;
+            }
         },
         KeyCode::Char('G') | KeyCode::End => {
-            app.describe_scroll_to_bottom(30); // Approximate visible lines
+            let len = app
+                .diff_state
+                .as_ref()
+                .map_or(0, |s| s.result.entries.len());
+            app.diff_scroll(len as isize);
+        },
+        _ => {},
+    }
+    Ok(false)
+}
+
+async fn handle_serial_mode(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Result<bool> {
+    let Some(ref mut state) = app.serial else {
+        app.exit_mode();
+        return Ok(false);
+    };
+
+    // Search query input
+    if state.search.input_active {
+        match code {
+            KeyCode::Esc => state.clear_search(),
+            KeyCode::Enter => {
+                state.search.input_active = false;
+                state.jump_to_first_match();
+            },
+            KeyCode::Backspace => {
+                state.search.query.pop();
+                state.update_search();
+            },
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                state.search.query.push(c);
+                state.update_search();
+            },
+            _ => {},
+        }
+        return Ok(false);
+    }
+
+    let page = PAGE_SCROLL_SIZE as isize;
+    match code {
+        KeyCode::Esc if !state.search.query.is_empty() => state.clear_search(),
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => app.exit_mode(),
+        KeyCode::Char('j') | KeyCode::Down => state.scroll_by(1),
+        KeyCode::Char('k') | KeyCode::Up => state.scroll_by(-1),
+        KeyCode::PageDown => state.scroll_by(page),
+        KeyCode::PageUp => state.scroll_by(-page),
+        KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => state.scroll_by(page),
+        KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => state.scroll_by(-page),
+        KeyCode::Char('g') | KeyCode::Home => state.scroll_to_top(),
+        KeyCode::Char('G') | KeyCode::End => state.scroll_to_bottom(),
+        KeyCode::Char('f') => state.toggle_follow(),
+        KeyCode::Char('/') => {
+            state.clear_search();
+            state.search.input_active = true;
+        },
+        KeyCode::Char('n') => state.jump_to_match(true),
+        KeyCode::Char('N') => state.jump_to_match(false),
+        KeyCode::Char('s') => app.save_serial_output(),
+        KeyCode::Char('r') => app.load_serial_output().await,
+        KeyCode::Char(c) if c.is_ascii_digit() => {
+            let port = c as u8 - b'0';
+            if crate::serial::SERIAL_PORTS.contains(&port) {
+                app.switch_serial_port(port).await;
+            }
+        },
+        _ => {},
+    }
+    Ok(false)
+}
+
+async fn handle_firewall_mode(
+    app: &mut App,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+) -> Result<bool> {
+    let Some(ref mut view) = app.firewall else {
+        app.exit_mode();
+        return Ok(false);
+    };
+
+    // Query prompt
+    if let Some(ref mut input) = view.input {
+        match code {
+            KeyCode::Esc => view.input = None,
+            KeyCode::Enter => {
+                let text = input.clone();
+                view.input = None;
+                view.run_query(&text);
+            },
+            KeyCode::Backspace => {
+                input.pop();
+            },
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => input.push(c),
+            _ => {},
+        }
+        return Ok(false);
+    }
+
+    let page = PAGE_SCROLL_SIZE as isize;
+    match code {
+        KeyCode::Esc if view.verdict.is_some() => view.clear_query(),
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => app.exit_mode(),
+        KeyCode::Char('j') | KeyCode::Down => view.select_by(1),
+        KeyCode::Char('k') | KeyCode::Up => view.select_by(-1),
+        KeyCode::PageDown => view.select_by(page),
+        KeyCode::PageUp => view.select_by(-page),
+        KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => view.select_by(page),
+        KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => view.select_by(-page),
+        KeyCode::Char('g') | KeyCode::Home => view.select_first(),
+        KeyCode::Char('G') | KeyCode::End => view.select_last(),
+        KeyCode::Char('/') => view.open_prompt(),
+        KeyCode::Char('c') => view.clear_query(),
+        KeyCode::Char('r') => app.load_effective_firewall().await,
+        _ => {},
+    }
+    Ok(false)
+}
+
+fn handle_tunnels_mode(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Result<bool> {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => app.exit_mode(),
+        KeyCode::Char('j') | KeyCode::Down => app.tunnels.select_by(1),
+        KeyCode::Char('k') | KeyCode::Up => app.tunnels.select_by(-1),
+        KeyCode::Char('g') | KeyCode::Home => app.tunnels.selected = 0,
+        KeyCode::Char('G') | KeyCode::End => app.tunnels.select_by(isize::MAX),
+        KeyCode::Char('s') => app.stop_selected_tunnel(),
+        KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => {
+            app.stop_selected_tunnel()
+        },
+        KeyCode::Char('r') => app.restart_selected_tunnel(),
+        _ => {},
+    }
+    Ok(false)
+}
+
+fn handle_run_output_mode(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Result<bool> {
+    let Some(ref mut run) = app.run else {
+        app.exit_mode();
+        return Ok(false);
+    };
+
+    let page = PAGE_SCROLL_SIZE as isize;
+    match code {
+        // Leaving the view kills the processes still running
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => app.exit_mode(),
+        KeyCode::Char('j') | KeyCode::Down | KeyCode::Tab => run.select_by(1),
+        KeyCode::Char('k') | KeyCode::Up | KeyCode::BackTab => run.select_by(-1),
+        KeyCode::Char('J') => run.scroll_by(1),
+        KeyCode::Char('K') => run.scroll_by(-1),
+        KeyCode::PageDown => run.scroll_by(page),
+        KeyCode::PageUp => run.scroll_by(-page),
+        KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => run.scroll_by(page),
+        KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => run.scroll_by(-page),
+        KeyCode::Char('g') | KeyCode::Home => run.scroll_to_top(),
+        KeyCode::Char('G') | KeyCode::End => run.scroll_to_bottom(),
+        KeyCode::Char('c') => run.cancel(),
+        KeyCode::Char('s') => app.save_run_output(),
+        _ => {},
+    }
+    Ok(false)
+}
+
+async fn handle_preview_mode(
+    app: &mut App,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+) -> Result<bool> {
+    let Some(ref mut preview) = app.preview else {
+        app.exit_mode();
+        return Ok(false);
+    };
+
+    let page = preview.viewport_height.max(1) as isize;
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => app.exit_mode(),
+        KeyCode::Char('j') | KeyCode::Down => preview.scroll_by(1),
+        KeyCode::Char('k') | KeyCode::Up => preview.scroll_by(-1),
+        KeyCode::PageDown => preview.scroll_by(page),
+        KeyCode::PageUp => preview.scroll_by(-page),
+        KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => {
+            preview.scroll_by(page / 2)
+        },
+        KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
+            preview.scroll_by(-page / 2)
+        },
+        KeyCode::Char('g') | KeyCode::Home => preview.scroll_to_top(),
+        KeyCode::Char('G') | KeyCode::End => preview.scroll_to_bottom(),
+        KeyCode::Char('m') => app.load_preview_more().await,
+        _ => {},
+    }
+    Ok(false)
+}
+
+async fn handle_bucket_mode(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Result<bool> {
+    let Some(ref mut view) = app.bucket else {
+        app.exit_mode();
+        return Ok(false);
+    };
+
+    let page = view.viewport_height.max(1) as isize;
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => app.exit_mode(),
+        KeyCode::Char('j') | KeyCode::Down => view.scroll_by(1),
+        KeyCode::Char('k') | KeyCode::Up => view.scroll_by(-1),
+        KeyCode::PageDown => view.scroll_by(page),
+        KeyCode::PageUp => view.scroll_by(-page),
+        KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => view.scroll_by(page / 2),
+        KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
+            view.scroll_by(-page / 2)
+        },
+        KeyCode::Char('g') | KeyCode::Home => view.scroll_to_top(),
+        KeyCode::Char('G') | KeyCode::End => view.scroll_to_bottom(),
+        KeyCode::Char('v') => app.confirm_bucket_toggle(true),
+        KeyCode::Char('p') => app.confirm_bucket_toggle(false),
+        KeyCode::Char('r') => app.load_bucket_detail().await,
+        _ => {},
+    }
+    Ok(false)
+}
+
+async fn handle_versions_mode(app: &mut App, code: KeyCode) -> Result<bool> {
+    let Some(ref mut view) = app.versions else {
+        app.exit_mode();
+        return Ok(false);
+    };
+
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => app.exit_mode(),
+        KeyCode::Char('j') | KeyCode::Down => view.select_next(),
+        KeyCode::Char('k') | KeyCode::Up => view.select_prev(),
+        KeyCode::Char('g') | KeyCode::Home => view.select_first(),
+        KeyCode::Char('G') | KeyCode::End => view.select_last(),
+        KeyCode::Char('s') => app.confirm_version_action(true),
+        KeyCode::Char('d') => app.confirm_version_action(false),
+        KeyCode::Char('r') => app.load_object_versions().await,
+        _ => {},
+    }
+    Ok(false)
+}
+
+fn handle_yank_mode(app: &mut App, code: KeyCode) -> Result<bool> {
+    let Some(ref mut menu) = app.yank_menu else {
+        app.exit_mode();
+        return Ok(false);
+    };
+
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('y') => {
+            app.exit_mode();
+        },
+        KeyCode::Char('j') | KeyCode::Down if menu.selected + 1 < menu.options.len() => {
+            menu.selected += 1;
+        },
+        KeyCode::Char('k') | KeyCode::Up => {
+            menu.selected = menu.selected.saturating_sub(1);
+        },
+        KeyCode::Enter => {
+            if let Some(&(field, _)) = menu.options.get(menu.selected) {
+                app.yank(field);
+            }
+        },
+        KeyCode::Char(c) => {
+            if let Some(&(field, _)) = menu.options.iter().find(|(f, _)| f.key() == c) {
+                app.yank(field);
+            }
         },
         _ => {},
     }
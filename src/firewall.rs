@@ -0,0 +1,1108 @@
+//! Firewall Analysis
+//!
+//! Effective firewall of an instance from `instances.getEffectiveFirewalls`:
+//! the VPC firewall rules and firewall policy rules that apply to one of its
+//! network interfaces, in evaluation order, and which rule decides a given
+//! connection (e.g. `tcp:22 from 0.0.0.0/0`).
+//!
+//! Rules are evaluated level by level: hierarchical policies (organization,
+//! then folders), then the VPC rules and network firewall policies in the
+//! network's enforcement order, then the implied rules. Within a level the
+//! matching rule with the lowest priority number wins; `goto_next` hands the
+//! decision to the next level.
+//!
+//! Also the parsing and body building of VPC rules created or edited from
+//! the firewall rule forms, and which loaded instances a change newly
+//! applies to.
+
+use serde_json::{json, Map, Value};
+use std::net::IpAddr;
+
+/// Query shown when the prompt is opened for the first time
+pub const EXAMPLE_QUERY: &str = "tcp:22 from 0.0.0.0/0";
+
+/// An IPv4 or IPv6 range (`10.0.0.0/8`, `35.235.240.0/20`, `::/0`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Parse a range; a bare address is a single-host range
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (addr, prefix) = match text.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (text, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("Invalid IP address: {}", text))?;
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(|| format!("Invalid prefix length in {}", text))?,
+            None => max,
+        };
+        Ok(Self { addr, prefix })
+    }
+
+    /// Whether every address of `other` is in this range
+    pub fn contains(&self, other: &Cidr) -> bool {
+        if other.prefix < self.prefix {
+            return false;
+        }
+        match (self.addr, other.addr) {
+            (IpAddr::V4(a), IpAddr::V4(b)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(a) & mask == u32::from(b) & mask
+            },
+            (IpAddr::V6(a), IpAddr::V6(b)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(a) & mask == u128::from(b) & mask
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Parse a port or port range (`22`, `8000-8080`)
+pub fn parse_port_range(text: &str) -> Result<(u16, u16), String> {
+    let port = |p: &str| {
+        p.trim()
+            .parse::<u16>()
+            .ok()
+            .filter(|p| *p > 0)
+            .ok_or_else(|| format!("Invalid port: {}", text))
+    };
+    match text.split_once('-') {
+        Some((low, high)) => {
+            let (low, high) = (port(low)?, port(high)?);
+            if low > high {
+                return Err(format!("Invalid port range: {}", text));
+            }
+            Ok((low, high))
+        },
+        None => port(text).map(|p| (p, p)),
+    }
+}
+
+/// Protocol name for a protocol given by name or IANA number
+pub fn normalize_protocol(protocol: &str) -> String {
+    match protocol.trim().to_lowercase().as_str() {
+        "6" => "tcp".to_string(),
+        "17" => "udp".to_string(),
+        "1" => "icmp".to_string(),
+        "132" => "sctp".to_string(),
+        "50" => "esp".to_string(),
+        "51" => "ah".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// What a rule does with matching traffic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleAction {
+    Allow,
+    Deny,
+    /// Policy rules only: let the next level decide
+    GotoNext,
+}
+
+impl RuleAction {
+    fn parse(action: &str) -> Self {
+        match action.to_lowercase().as_str() {
+            "allow" => Self::Allow,
+            "goto_next" => Self::GotoNext,
+            _ => Self::Deny,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Allow => "ALLOW",
+            Self::Deny => "DENY",
+            Self::GotoNext => "GOTO_NEXT",
+        }
+    }
+}
+
+/// A protocol with its port ranges (none: all ports)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolPorts {
+    pub protocol: String,
+    pub ports: Vec<(u16, u16)>,
+}
+
+impl ProtocolPorts {
+    fn from_json(value: &Value, protocol_key: &str) -> Self {
+        let protocol = value
+            .get(protocol_key)
+            .and_then(|v| v.as_str())
+            .map(normalize_protocol)
+            .unwrap_or_else(|| "all".to_string());
+        let ports = strings(value.get("ports"))
+            .iter()
+            .filter_map(|p| parse_port_range(p).ok())
+            .collect();
+        Self { protocol, ports }
+    }
+
+    fn matches(&self, protocol: &str, port: Option<u16>) -> bool {
+        if self.protocol != "all" && self.protocol != protocol {
+            return false;
+        }
+        match port {
+            Some(port) => {
+                self.ports.is_empty() || self.ports.iter().any(|(l, h)| (*l..=*h).contains(&port))
+            },
+            // Without a port, only rules covering every port decide
+            None => self.ports.is_empty(),
+        }
+    }
+
+    fn label(&self) -> String {
+        if self.ports.is_empty() {
+            return self.protocol.clone();
+        }
+        let ports: Vec<String> = self
+            .ports
+            .iter()
+            .map(|(l, h)| {
+                if l == h {
+                    l.to_string()
+                } else {
+                    format!("{}-{}", l, h)
+                }
+            })
+            .collect();
+        format!("{}:{}", self.protocol, ports.join(","))
+    }
+}
+
+/// A rule that applies to the instance
+#[derive(Debug, Clone)]
+pub struct EffectiveRule {
+    /// Evaluation level (hierarchical policies first, implied rules last)
+    pub level: usize,
+    /// `VPC`, the policy name, or `implied`
+    pub origin: String,
+    pub name: String,
+    pub priority: i64,
+    pub ingress: bool,
+    pub action: RuleAction,
+    /// Empty: all protocols
+    pub protocols: Vec<ProtocolPorts>,
+    /// Source ranges of ingress rules, destination ranges of egress rules
+    pub ranges: Vec<String>,
+    /// Source tags and service accounts (shown, not evaluated)
+    pub sources: Vec<String>,
+    pub targets: String,
+    pub disabled: bool,
+}
+
+impl EffectiveRule {
+    pub fn protocols_label(&self) -> String {
+        if self.protocols.is_empty() {
+            return "all".to_string();
+        }
+        self.protocols
+            .iter()
+            .map(ProtocolPorts::label)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn peers_label(&self) -> String {
+        let mut peers = self.ranges.clone();
+        peers.extend(self.sources.iter().cloned());
+        if peers.is_empty() {
+            "any".to_string()
+        } else {
+            peers.join(", ")
+        }
+    }
+
+    /// Whether the rule matches the queried connection. Ranges must cover the
+    /// whole queried range; rules matching only tags or service accounts
+    /// never match an address query.
+    fn matches(&self, query: &Query) -> bool {
+        if self.disabled || self.ingress != query.ingress {
+            return false;
+        }
+        if !self.protocols.is_empty()
+            && !self
+                .protocols
+                .iter()
+                .any(|p| p.matches(&query.protocol, query.port))
+        {
+            return false;
+        }
+        if self.ranges.is_empty() {
+            return self.sources.is_empty();
+        }
+        self.ranges
+            .iter()
+            .filter_map(|r| Cidr::parse(r).ok())
+            .any(|r| r.contains(&query.range))
+    }
+}
+
+fn strings(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn short(url: &str) -> &str {
+    url.rsplit('/').next().unwrap_or(url)
+}
+
+fn targets_label(tags: Vec<String>, service_accounts: Vec<String>) -> String {
+    let mut targets = tags;
+    targets.extend(service_accounts);
+    if targets.is_empty() {
+        "all instances".to_string()
+    } else {
+        targets.join(", ")
+    }
+}
+
+/// A VPC firewall rule (`firewalls` of the response)
+fn vpc_rule(level: usize, rule: &Value) -> EffectiveRule {
+    let (action, list) = match rule.get("denied") {
+        Some(denied) => (RuleAction::Deny, denied),
+        None => (
+            RuleAction::Allow,
+            rule.get("allowed").unwrap_or(&Value::Null),
+        ),
+    };
+    let ingress = rule.get("direction").and_then(|v| v.as_str()) != Some("EGRESS");
+    let ranges = if ingress {
+        strings(rule.get("sourceRanges"))
+    } else {
+        strings(rule.get("destinationRanges"))
+    };
+    let mut sources = strings(rule.get("sourceTags"));
+    sources.extend(strings(rule.get("sourceServiceAccounts")));
+
+    EffectiveRule {
+        level,
+        origin: "VPC".to_string(),
+        name: rule
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("-")
+            .to_string(),
+        priority: rule
+            .get("priority")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(1000),
+        ingress,
+        action,
+        protocols: list
+            .as_array()
+            .map(|l| {
+                l.iter()
+                    .map(|p| ProtocolPorts::from_json(p, "IPProtocol"))
+                    .filter(|p| p.protocol != "all")
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ranges,
+        sources,
+        targets: targets_label(
+            strings(rule.get("targetTags")),
+            strings(rule.get("targetServiceAccounts")),
+        ),
+        disabled: rule.get("disabled").and_then(|v| v.as_bool()) == Some(true),
+    }
+}
+
+/// A firewall policy rule (`firewallPolicys[].rules` of the response)
+fn policy_rule(level: usize, origin: &str, rule: &Value) -> EffectiveRule {
+    let ingress = rule.get("direction").and_then(|v| v.as_str()) != Some("EGRESS");
+    let matcher = rule.get("match").unwrap_or(&Value::Null);
+    let ranges = if ingress {
+        strings(matcher.get("srcIpRanges"))
+    } else {
+        strings(matcher.get("destIpRanges"))
+    };
+    let mut sources: Vec<String> = matcher
+        .get("srcSecureTags")
+        .and_then(|v| v.as_array())
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|t| t.get("name").and_then(|v| v.as_str()))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+    sources.extend(strings(matcher.get("srcFqdns")));
+    sources.extend(strings(matcher.get("srcRegionCodes")));
+    let priority = rule.get("priority").and_then(|v| v.as_i64()).unwrap_or(0);
+    let name = ["ruleName", "description"]
+        .iter()
+        .find_map(|key| rule.get(*key).and_then(|v| v.as_str()))
+        .filter(|n| !n.is_empty())
+        .map(String::from)
+        .unwrap_or_else(|| format!("priority {}", priority));
+
+    EffectiveRule {
+        level,
+        origin: origin.to_string(),
+        name,
+        priority,
+        ingress,
+        action: RuleAction::parse(
+            rule.get("action")
+                .and_then(|v| v.as_str())
+                .unwrap_or("deny"),
+        ),
+        protocols: matcher
+            .get("layer4Configs")
+            .and_then(|v| v.as_array())
+            .map(|l| {
+                l.iter()
+                    .map(|p| ProtocolPorts::from_json(p, "ipProtocol"))
+                    .filter(|p| p.protocol != "all")
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ranges,
+        sources,
+        targets: targets_label(
+            strings(rule.get("targetResources"))
+                .iter()
+                .map(|r| short(r).to_string())
+                .collect(),
+            strings(rule.get("targetServiceAccounts")),
+        ),
+        disabled: rule.get("disabled").and_then(|v| v.as_bool()) == Some(true),
+    }
+}
+
+/// The rules GCP adds to every network, evaluated last
+fn implied_rules(level: usize) -> Vec<EffectiveRule> {
+    [(true, RuleAction::Deny), (false, RuleAction::Allow)]
+        .into_iter()
+        .map(|(ingress, action)| EffectiveRule {
+            level,
+            origin: "implied".to_string(),
+            name: if ingress {
+                "deny all ingress"
+            } else {
+                "allow all egress"
+            }
+            .to_string(),
+            priority: 65535,
+            ingress,
+            action,
+            protocols: Vec::new(),
+            ranges: vec!["0.0.0.0/0".to_string(), "::/0".to_string()],
+            sources: Vec::new(),
+            targets: "all instances".to_string(),
+            disabled: false,
+        })
+        .collect()
+}
+
+/// Rules of a `getEffectiveFirewalls` response in evaluation order.
+/// `policies_first` is the network's `BEFORE_CLASSIC_FIREWALL` enforcement
+/// order (network policies before VPC rules).
+pub fn effective_rules(response: &Value, policies_first: bool) -> Vec<EffectiveRule> {
+    let policies = response
+        .get("firewallPolicys")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let policy_type = |p: &Value| {
+        p.get("type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("HIERARCHY")
+            .to_string()
+    };
+
+    // Levels: each hierarchical policy, then VPC and network policies
+    let mut levels: Vec<Vec<EffectiveRule>> = Vec::new();
+    let push_policy = |levels: &mut Vec<Vec<EffectiveRule>>, policy: &Value| {
+        let level = levels.len();
+        let name = ["shortName", "displayName", "name"]
+            .iter()
+            .find_map(|key| policy.get(*key).and_then(|v| v.as_str()))
+            .unwrap_or("policy");
+        let origin = match policy_type(policy).as_str() {
+            "HIERARCHY" => format!("{} (hierarchy)", name),
+            "NETWORK_REGIONAL" => format!("{} (regional)", name),
+            _ => format!("{} (network)", name),
+        };
+        let rules = policy
+            .get("rules")
+            .and_then(|v| v.as_array())
+            .map(|rules| {
+                rules
+                    .iter()
+                    .map(|r| policy_rule(level, &origin, r))
+                    .collect()
+            })
+            .unwrap_or_default();
+        levels.push(rules);
+    };
+
+    for policy in policies.iter().filter(|p| policy_type(p) == "HIERARCHY") {
+        push_policy(&mut levels, policy);
+    }
+    let network_policies: Vec<&Value> = policies
+        .iter()
+        .filter(|p| policy_type(p) != "HIERARCHY")
+        .collect();
+    let push_vpc = |levels: &mut Vec<Vec<EffectiveRule>>| {
+        let level = levels.len();
+        let rules = response
+            .get("firewalls")
+            .and_then(|v| v.as_array())
+            .map(|rules| rules.iter().map(|r| vpc_rule(level, r)).collect())
+            .unwrap_or_default();
+        levels.push(rules);
+    };
+    if !policies_first {
+        push_vpc(&mut levels);
+    }
+    for policy in network_policies {
+        push_policy(&mut levels, policy);
+    }
+    if policies_first {
+        push_vpc(&mut levels);
+    }
+    let implied = levels.len();
+    levels.push(implied_rules(implied));
+
+    // Within a level: lowest priority number first, deny before allow
+    levels
+        .into_iter()
+        .flat_map(|mut rules| {
+            rules.sort_by_key(|r| (r.priority, r.action != RuleAction::Deny));
+            rules
+        })
+        .collect()
+}
+
+/// A connection to check against the rules
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub protocol: String,
+    pub port: Option<u16>,
+    pub ingress: bool,
+    pub range: Cidr,
+}
+
+impl Query {
+    /// Parse `<protocol>[:<port>] [from|to <address or range>]`. `from` asks
+    /// about ingress, `to` about egress; without either, ingress from
+    /// anywhere.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let Some(first) = words.first() else {
+            return Err(format!("Expected e.g. {}", EXAMPLE_QUERY));
+        };
+        let (protocol, port) = match first.split_once(':') {
+            Some((protocol, port)) => {
+                let port = port
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|p| *p > 0)
+                    .ok_or_else(|| format!("Invalid port: {}", port))?;
+                (protocol, Some(port))
+            },
+            None => (*first, None),
+        };
+        let protocol = normalize_protocol(protocol);
+        if protocol.is_empty() || protocol == "all" {
+            return Err("Give a protocol, e.g. tcp, udp or icmp".to_string());
+        }
+
+        let (ingress, range) = match &words[1..] {
+            [] => (true, "0.0.0.0/0"),
+            ["from", range] => (true, *range),
+            ["to", range] => (false, *range),
+            _ => return Err(format!("Expected e.g. {}", EXAMPLE_QUERY)),
+        };
+        Ok(Self {
+            protocol,
+            port,
+            ingress,
+            range: Cidr::parse(range)?,
+        })
+    }
+}
+
+/// The rule deciding a queried connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verdict {
+    /// Index of the deciding rule
+    pub rule: usize,
+    pub allowed: bool,
+    pub explanation: String,
+}
+
+/// Find the rule deciding a connection. The implied rules always match, so
+/// there is always a verdict when they are included.
+pub fn evaluate(rules: &[EffectiveRule], query: &Query) -> Option<Verdict> {
+    let mut skip_level = None;
+    for (index, rule) in rules.iter().enumerate() {
+        if skip_level == Some(rule.level) || !rule.matches(query) {
+            continue;
+        }
+        match rule.action {
+            RuleAction::GotoNext => skip_level = Some(rule.level),
+            action => {
+                let allowed = action == RuleAction::Allow;
+                return Some(Verdict {
+                    rule: index,
+                    allowed,
+                    explanation: format!(
+                        "{} by '{}' ({}, priority {})",
+                        if allowed { "ALLOWED" } else { "DENIED" },
+                        rule.name,
+                        rule.origin,
+                        rule.priority
+                    ),
+                });
+            },
+        }
+    }
+    None
+}
+
+/// Effective firewall view state
+#[derive(Debug, Clone)]
+pub struct FirewallView {
+    pub instance: String,
+    pub zone: String,
+    pub network_interface: String,
+    /// Link of the interface's network (for its policy enforcement order)
+    pub network: String,
+    pub rules: Vec<EffectiveRule>,
+    pub loading: bool,
+    pub error: Option<String>,
+    pub selected: usize,
+    pub scroll: usize,
+    pub viewport_height: usize,
+    /// Query being typed, when the prompt is open
+    pub input: Option<String>,
+    /// Last evaluated query and its result
+    pub query: String,
+    pub verdict: Option<Result<Verdict, String>>,
+}
+
+impl FirewallView {
+    pub fn new(instance: &str, zone: &str, network_interface: &str, network: &str) -> Self {
+        Self {
+            instance: instance.to_string(),
+            zone: zone.to_string(),
+            network_interface: network_interface.to_string(),
+            network: network.to_string(),
+            rules: Vec::new(),
+            loading: true,
+            error: None,
+            selected: 0,
+            scroll: 0,
+            viewport_height: 1,
+            input: None,
+            query: String::new(),
+            verdict: None,
+        }
+    }
+
+    /// Replace the rules, re-running the last query
+    pub fn set_rules(&mut self, rules: Vec<EffectiveRule>) {
+        self.rules = rules;
+        self.loading = false;
+        self.error = None;
+        self.selected = self.selected.min(self.rules.len().saturating_sub(1));
+        if !self.query.is_empty() {
+            let query = self.query.clone();
+            self.run_query(&query);
+        }
+    }
+
+    pub fn open_prompt(&mut self) {
+        let text = if self.query.is_empty() {
+            EXAMPLE_QUERY.to_string()
+        } else {
+            self.query.clone()
+        };
+        self.input = Some(text);
+    }
+
+    /// Evaluate a query and select the deciding rule
+    pub fn run_query(&mut self, text: &str) {
+        self.query = text.trim().to_string();
+        self.verdict = Some(Query::parse(&self.query).and_then(|query| {
+            evaluate(&self.rules, &query).ok_or_else(|| "No rule matches".to_string())
+        }));
+        if let Some(Ok(ref verdict)) = self.verdict {
+            self.selected = verdict.rule;
+            self.scroll_to_selected();
+        }
+    }
+
+    pub fn clear_query(&mut self) {
+        self.query.clear();
+        self.verdict = None;
+    }
+
+    /// Index of the deciding rule of the last query
+    pub fn deciding_rule(&self) -> Option<usize> {
+        match self.verdict {
+            Some(Ok(ref verdict)) => Some(verdict.rule),
+            _ => None,
+        }
+    }
+
+    pub fn select_by(&mut self, delta: isize) {
+        if self.rules.is_empty() {
+            return;
+        }
+        let max = self.rules.len() as isize - 1;
+        self.selected = (self.selected as isize + delta).clamp(0, max) as usize;
+        self.scroll_to_selected();
+    }
+
+    pub fn select_first(&mut self) {
+        self.selected = 0;
+        self.scroll_to_selected();
+    }
+
+    pub fn select_last(&mut self) {
+        self.selected = self.rules.len().saturating_sub(1);
+        self.scroll_to_selected();
+    }
+
+    fn scroll_to_selected(&mut self) {
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + self.viewport_height {
+            self.scroll = self.selected + 1 - self.viewport_height;
+        }
+    }
+}
+
+// =============================================================================
+// Rule editing
+// =============================================================================
+
+/// Protocols that take ports
+const PORT_PROTOCOLS: &[&str] = &["tcp", "udp", "sctp"];
+
+/// Protocols accepted by name (others by IANA number)
+const FIREWALL_PROTOCOLS: &[&str] = &["all", "tcp", "udp", "icmp", "esp", "ah", "sctp", "ipip"];
+
+fn split_list(input: &str) -> impl Iterator<Item = &str> {
+    input.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// Parse protocols and ports in gcloud syntax (`tcp:22,tcp:8000-8080,icmp`
+/// or `all`) into the `allowed`/`denied` list of a rule
+pub fn parse_firewall_protocols(input: &str) -> Result<Vec<Value>, String> {
+    let mut protocols: Vec<(String, Vec<String>)> = Vec::new();
+    for entry in split_list(input) {
+        let (protocol, port) = match entry.split_once(':') {
+            Some((protocol, port)) => (protocol, Some(port.trim())),
+            None => (entry, None),
+        };
+        let protocol = normalize_protocol(protocol);
+        let known =
+            FIREWALL_PROTOCOLS.contains(&protocol.as_str()) || protocol.parse::<u8>().is_ok();
+        if !known {
+            return Err(format!("Unknown protocol '{}'", protocol));
+        }
+        if let Some(port) = port {
+            if !PORT_PROTOCOLS.contains(&protocol.as_str()) {
+                return Err(format!(
+                    "Ports can only be given for tcp, udp or sctp: {}",
+                    entry
+                ));
+            }
+            parse_port_range(port)?;
+        }
+        match protocols.iter_mut().find(|(p, _)| *p == protocol) {
+            Some((_, ports)) => ports.extend(port.map(String::from)),
+            None => protocols.push((protocol, port.map(String::from).into_iter().collect())),
+        }
+    }
+    if protocols.is_empty() {
+        return Err("Give protocols, e.g. tcp:22,tcp:443 or all".to_string());
+    }
+    if protocols.len() > 1 && protocols.iter().any(|(p, _)| p == "all") {
+        return Err("'all' cannot be combined with other protocols".to_string());
+    }
+
+    Ok(protocols
+        .into_iter()
+        .map(|(protocol, ports)| {
+            // A protocol given once without a port covers all its ports
+            let all_ports = input.split(',').any(|e| normalize_protocol(e) == protocol);
+            if ports.is_empty() || all_ports {
+                json!({ "IPProtocol": protocol })
+            } else {
+                json!({ "IPProtocol": protocol, "ports": ports })
+            }
+        })
+        .collect())
+}
+
+/// Parse comma-separated IP ranges
+pub fn parse_cidrs(input: &str) -> Result<Vec<String>, String> {
+    split_list(input)
+        .map(|range| Cidr::parse(range).map(|_| range.to_string()))
+        .collect()
+}
+
+/// Parse comma-separated service account emails
+pub fn parse_service_accounts(input: &str) -> Result<Vec<String>, String> {
+    split_list(input)
+        .map(|email| match email.split_once('@') {
+            Some((user, domain)) if !user.is_empty() && domain.contains('.') => {
+                Ok(email.to_string())
+            },
+            _ => Err(format!("Invalid service account: {}", email)),
+        })
+        .collect()
+}
+
+/// Parse a rule priority (0-65535)
+pub fn parse_priority(input: &str) -> Result<u64, String> {
+    input
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|p| *p <= 65535)
+        .ok_or_else(|| "Priority must be between 0 and 65535".to_string())
+}
+
+/// The changeable fields of a VPC firewall rule, parsed
+#[derive(Debug, Clone)]
+pub struct RuleEdit {
+    pub ingress: bool,
+    /// `allowed` or `denied`
+    pub action: String,
+    pub priority: u64,
+    pub protocols: Vec<Value>,
+    /// Source ranges of ingress rules, destination ranges of egress rules
+    pub ranges: Vec<String>,
+    pub source_tags: Vec<String>,
+    pub target_tags: Vec<String>,
+    pub target_service_accounts: Vec<String>,
+    pub disabled: bool,
+    pub logging: bool,
+}
+
+impl RuleEdit {
+    /// Body of a `firewalls.patch`: the changeable fields, cleared ones as
+    /// empty lists
+    pub fn patch_body(&self) -> Result<Value, String> {
+        if !self.target_tags.is_empty() && !self.target_service_accounts.is_empty() {
+            return Err("Use target tags or target service accounts, not both".to_string());
+        }
+        if self.ingress && self.ranges.is_empty() && self.source_tags.is_empty() {
+            // The API would default to 0.0.0.0/0
+            return Err(
+                "Give source ranges or source tags (an ingress rule without sources allows 0.0.0.0/0)"
+                    .to_string(),
+            );
+        }
+        if !self.ingress && !self.source_tags.is_empty() {
+            return Err("Source tags only apply to ingress rules".to_string());
+        }
+
+        let mut body = Map::new();
+        body.insert("priority".to_string(), json!(self.priority));
+        body.insert(self.action.clone(), json!(self.protocols));
+        let ranges_key = if self.ingress {
+            "sourceRanges"
+        } else {
+            "destinationRanges"
+        };
+        body.insert(ranges_key.to_string(), json!(self.ranges));
+        if self.ingress {
+            body.insert("sourceTags".to_string(), json!(self.source_tags));
+        }
+        body.insert("targetTags".to_string(), json!(self.target_tags));
+        body.insert(
+            "targetServiceAccounts".to_string(),
+            json!(self.target_service_accounts),
+        );
+        body.insert("disabled".to_string(), json!(self.disabled));
+        body.insert("logConfig".to_string(), json!({ "enable": self.logging }));
+        Ok(Value::Object(body))
+    }
+
+    /// Body of a `firewalls.insert`: the patch fields plus the ones fixed at
+    /// creation
+    pub fn insert_body(&self, name: &str, network: &str) -> Result<Value, String> {
+        let mut body = self.patch_body()?;
+        body["name"] = json!(name);
+        body["network"] = json!(network);
+        body["direction"] = json!(if self.ingress { "INGRESS" } else { "EGRESS" });
+        Ok(body)
+    }
+}
+
+/// Instance fields used for the impact preview, from a list item
+pub fn impact_instance(instance: &Value) -> Value {
+    let nested = |list: &str, key: &str| -> Vec<String> {
+        instance
+            .get(list)
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|item| item.get(key).and_then(|v| v.as_str()))
+            .map(String::from)
+            .collect()
+    };
+    json!({
+        "name": instance.get("name").and_then(|v| v.as_str()).unwrap_or(""),
+        "networks": nested("networkInterfaces", "network"),
+        "tags": strings(instance.pointer("/tags/items")),
+        "serviceAccounts": nested("serviceAccounts", "email"),
+    })
+}
+
+/// Whether an enabled rule applies to an impact instance (same network, and
+/// target tags or service accounts matching, or no targets)
+pub fn rule_targets_instance(rule: &Value, instance: &Value) -> bool {
+    if rule.get("disabled").and_then(|v| v.as_bool()) == Some(true) {
+        return false;
+    }
+    let network = short(rule.get("network").and_then(|v| v.as_str()).unwrap_or(""));
+    let in_network = strings(instance.get("networks"))
+        .iter()
+        .any(|n| short(n) == network);
+    if !in_network {
+        return false;
+    }
+
+    let target_tags = strings(rule.get("targetTags"));
+    let target_accounts = strings(rule.get("targetServiceAccounts"));
+    if !target_tags.is_empty() {
+        let tags = strings(instance.get("tags"));
+        return target_tags.iter().any(|t| tags.contains(t));
+    }
+    if !target_accounts.is_empty() {
+        let accounts = strings(instance.get("serviceAccounts"));
+        return target_accounts.iter().any(|a| accounts.contains(a));
+    }
+    true
+}
+
+/// Impact of a new or changed rule (`current`: the rule before the change,
+/// Null when creating): the instances it newly applies to, and a warning
+/// when it opens ingress to the whole internet
+pub fn impact_notes(rule: &Value, current: &Value, instances: &[Value]) -> Vec<String> {
+    let mut rule = rule.clone();
+    if rule.get("network").is_none() {
+        rule["network"] = current.get("network").cloned().unwrap_or(Value::Null);
+    }
+    let newly_matched: Vec<&str> = instances
+        .iter()
+        .filter(|i| rule_targets_instance(&rule, i) && !rule_targets_instance(current, i))
+        .map(|i| i.get("name").and_then(|v| v.as_str()).unwrap_or("-"))
+        .collect();
+
+    let mut notes = Vec::new();
+    let open = strings(rule.get("sourceRanges"))
+        .iter()
+        .any(|r| r == "0.0.0.0/0" || r == "::/0");
+    if rule.get("allowed").is_some() && open {
+        notes.push("WARNING: allows ingress from the whole internet (0.0.0.0/0)".to_string());
+    }
+    if newly_matched.is_empty() {
+        notes.push(format!(
+            "No loaded instance is newly matched ({} loaded)",
+            instances.len()
+        ));
+    } else {
+        notes.push(format!(
+            "Newly matched instances ({} of {} loaded): {}",
+            newly_matched.len(),
+            instances.len(),
+            newly_matched.join(", ")
+        ));
+    }
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response() -> Value {
+        json!({
+            "firewalls": [
+                {"name": "allow-ssh-iap", "direction": "INGRESS", "priority": 1000,
+                 "allowed": [{"IPProtocol": "tcp", "ports": ["22"]}],
+                 "sourceRanges": ["35.235.240.0/20"]},
+                {"name": "allow-web", "direction": "INGRESS", "priority": 1000,
+                 "allowed": [{"IPProtocol": "tcp", "ports": ["80", "443"]}],
+                 "sourceRanges": ["0.0.0.0/0"], "targetTags": ["web"]},
+                {"name": "deny-egress-smtp", "direction": "EGRESS", "priority": 900,
+                 "denied": [{"IPProtocol": "tcp", "ports": ["25"]}],
+                 "destinationRanges": ["0.0.0.0/0"]},
+            ],
+            "firewallPolicys": [{
+                "name": "123", "shortName": "org-baseline", "type": "HIERARCHY",
+                "rules": [
+                    {"priority": 100, "direction": "INGRESS", "action": "deny",
+                     "match": {"srcIpRanges": ["0.0.0.0/0"],
+                               "layer4Configs": [{"ipProtocol": "tcp", "ports": ["3389"]}]}},
+                    {"priority": 2147483647, "direction": "INGRESS", "action": "goto_next",
+                     "match": {"srcIpRanges": ["0.0.0.0/0"]}},
+                ],
+            }],
+        })
+    }
+
+    #[test]
+    fn test_cidr_contains() {
+        let range = Cidr::parse("10.0.0.0/8").unwrap();
+        assert!(range.contains(&Cidr::parse("10.1.2.3").unwrap()));
+        assert!(range.contains(&Cidr::parse("10.128.0.0/9").unwrap()));
+        assert!(!range.contains(&Cidr::parse("0.0.0.0/0").unwrap()));
+        assert!(Cidr::parse("0.0.0.0/0")
+            .unwrap()
+            .contains(&Cidr::parse("1.2.3.4").unwrap()));
+        assert!(!range.contains(&Cidr::parse("::1").unwrap()));
+        assert!(Cidr::parse("10.0.0.0/33").is_err());
+        assert!(Cidr::parse("10.0.0/8").is_err());
+        assert_eq!(parse_port_range("8000-8080"), Ok((8000, 8080)));
+        assert!(parse_port_range("80-22").is_err());
+        assert!(parse_port_range("http").is_err());
+    }
+
+    #[test]
+    fn test_rules_are_ordered_by_level_and_priority() {
+        let rules = effective_rules(&response(), false);
+        let names: Vec<&str> = rules.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "priority 100",
+                "priority 2147483647",
+                "deny-egress-smtp",
+                "allow-ssh-iap",
+                "allow-web",
+                "deny all ingress",
+                "allow all egress",
+            ]
+        );
+        assert_eq!(rules[0].origin, "org-baseline (hierarchy)");
+        assert_eq!(rules[4].protocols_label(), "tcp:80,443");
+    }
+
+    #[test]
+    fn test_evaluate_queries() {
+        let rules = effective_rules(&response(), false);
+        let verdict = |text: &str| evaluate(&rules, &Query::parse(text).unwrap()).unwrap();
+
+        // IAP range only, not the whole internet
+        let ssh = verdict("tcp:22 from 35.235.241.7");
+        assert!(ssh.allowed);
+        assert_eq!(rules[ssh.rule].name, "allow-ssh-iap");
+        let ssh = verdict("tcp:22 from 0.0.0.0/0");
+        assert!(!ssh.allowed);
+        assert_eq!(rules[ssh.rule].name, "deny all ingress");
+
+        // Denied by the organization before VPC rules are considered
+        assert_eq!(rules[verdict("tcp:3389").rule].name, "priority 100");
+        assert!(verdict("tcp:443 from 8.8.8.8").allowed);
+        assert!(!verdict("tcp:25 to 1.2.3.4").allowed);
+        assert!(verdict("udp:53 to 8.8.8.8").allowed);
+        assert!(Query::parse("tcp:22 via 1.2.3.4").is_err());
+        assert!(Query::parse("tcp:http").is_err());
+    }
+    #[test]
+    fn test_parse_firewall_protocols() {
+        assert_eq!(
+            parse_firewall_protocols("tcp:22,tcp:8000-8080,udp,icmp").unwrap(),
+            vec![
+                json!({"IPProtocol": "tcp", "ports": ["22", "8000-8080"]}),
+                json!({"IPProtocol": "udp"}),
+                json!({"IPProtocol": "icmp"}),
+            ]
+        );
+        assert_eq!(
+            parse_firewall_protocols("all").unwrap(),
+            vec![json!({"IPProtocol": "all"})]
+        );
+        assert!(parse_firewall_protocols("tcp:0").is_err());
+        assert!(parse_firewall_protocols("icmp:8").is_err());
+        assert!(parse_firewall_protocols("tcp:22,all").is_err());
+        assert!(parse_firewall_protocols("http").is_err());
+        assert!(parse_cidrs("10.0.0.0/8, 35.235.240.0/20").is_ok());
+        assert!(parse_cidrs("10.0.0.0/40").is_err());
+    }
+
+    #[test]
+    fn test_rule_impact() {
+        let network = "https://www.googleapis.com/compute/v1/projects/p/global/networks/default";
+        let instances: Vec<Value> = [
+            json!({"name": "web-01", "networkInterfaces": [{"network": network}],
+                   "tags": {"items": ["web"]}}),
+            json!({"name": "batch", "networkInterfaces": [{"network": network}],
+                   "serviceAccounts": [{"email": "batch@p.iam.gserviceaccount.com"}]}),
+            json!({"name": "other", "networkInterfaces": [{"network": "projects/p/global/networks/vpc2"}],
+                   "tags": {"items": ["web"]}}),
+        ]
+        .iter()
+        .map(impact_instance)
+        .collect();
+        assert_eq!(
+            instances[1]["serviceAccounts"],
+            json!(["batch@p.iam.gserviceaccount.com"])
+        );
+
+        let mut edit = RuleEdit {
+            ingress: true,
+            action: "allowed".to_string(),
+            priority: parse_priority("1000").unwrap(),
+            protocols: parse_firewall_protocols("tcp:8080").unwrap(),
+            ranges: Vec::new(),
+            source_tags: Vec::new(),
+            target_tags: Vec::new(),
+            target_service_accounts: parse_service_accounts("batch@p.iam.gserviceaccount.com")
+                .unwrap(),
+            disabled: false,
+            logging: false,
+        };
+        assert!(edit.insert_body("allow-batch", network).is_err());
+        edit.ranges = vec!["10.0.0.0/8".to_string()];
+        let rule = edit.insert_body("allow-batch", network).unwrap();
+        assert_eq!(rule["direction"], "INGRESS");
+        assert!(rule_targets_instance(&rule, &instances[1]));
+        assert!(!rule_targets_instance(&rule, &instances[0]));
+        assert_eq!(
+            impact_notes(&rule, &Value::Null, &instances),
+            vec!["Newly matched instances (1 of 3 loaded): batch"]
+        );
+
+        // Disabled rules match nothing
+        edit.disabled = true;
+        let rule = edit.insert_body("allow-batch", network).unwrap();
+        assert!(!rule_targets_instance(&rule, &instances[1]));
+        assert!(parse_priority("70000").is_err());
+        assert!(parse_service_accounts("batch").is_err());
+    }
+}
@@ -0,0 +1,555 @@
+//! Form State
+//!
+//! Generic multi-step input forms (wizards and prompts). A form is a list of
+//! fields grouped into steps, optionally followed by a preview of the request
+//! that will be sent. What happens on submit is decided by the form's
+//! [`FormPurpose`].
+
+use serde_json::Value;
+
+/// What a form does when submitted
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormPurpose {
+    /// Create a VM instance (`instances.insert`)
+    CreateInstance,
+    /// Change the machine type of an instance (stop → setMachineType → start)
+    ResizeInstance,
+    /// Snapshot a persistent disk (`disks.createSnapshot`)
+    SnapshotDisk,
+    /// Create a persistent disk from a snapshot (`disks.insert`)
+    CreateDiskFromSnapshot,
+    /// Grow a persistent disk (`disks.resize`)
+    ResizeDisk,
+    /// Attach a disk to an instance (`instances.attachDisk`)
+    AttachDisk,
+    /// Detach a disk from one of its instances (`instances.detachDisk`)
+    DetachDisk,
+    /// Change the target size of a managed instance group
+    /// (`instanceGroupManagers.resize`)
+    ResizeGroup,
+    /// Reserve a new external address (`addresses.insert`)
+    ReserveAddress,
+    /// Promote an instance's ephemeral IP to a static address
+    /// (`addresses.insert` with the current IP)
+    PromoteAddress,
+    /// Create a VPC firewall rule (`firewalls.insert`)
+    CreateFirewall,
+    /// Change a VPC firewall rule (`firewalls.patch`)
+    EditFirewall,
+    /// Forward a local port to an instance (`gcloud compute start-iap-tunnel`)
+    Tunnel,
+    /// Run a command on the selected instances (`gcloud compute ssh --command`)
+    RunCommand,
+    /// Copy files from or to an instance (`gcloud compute scp`)
+    CopyFiles,
+    /// Download the selected objects (`alt=media`)
+    DownloadObjects,
+    /// Upload a local file into the current folder of a bucket
+    UploadObject,
+}
+
+/// An option of a select field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectOption {
+    /// Value used when submitting
+    pub value: String,
+    /// Text shown in the list
+    pub label: String,
+}
+
+impl SelectOption {
+    pub fn new(value: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            label: label.into(),
+        }
+    }
+
+    /// Option whose label is its value
+    pub fn plain(value: impl Into<String>) -> Self {
+        let value = value.into();
+        Self {
+            label: value.clone(),
+            value,
+        }
+    }
+}
+
+/// Kind of input of a field
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldKind {
+    /// Free text
+    Text,
+    /// Non-negative integer
+    Number,
+    /// Boolean switch
+    Toggle,
+    /// One of a list of options, filtered by typing
+    Select {
+        options: Vec<SelectOption>,
+        filter: String,
+        /// Index into the filtered options
+        selected: usize,
+    },
+}
+
+/// Extra validation of a field value, returning a user-facing message
+pub type Validator = fn(&str) -> Result<(), String>;
+
+/// A single form field
+#[derive(Debug, Clone)]
+pub struct FormField {
+    pub key: String,
+    pub label: String,
+    pub kind: FieldKind,
+    /// Current value (text/number input, "true"/"false" for toggles)
+    pub value: String,
+    pub required: bool,
+    pub help: Option<String>,
+    /// Step this field belongs to
+    pub step: usize,
+    pub validator: Option<Validator>,
+}
+
+impl FormField {
+    fn new(key: &str, label: &str, kind: FieldKind, value: String) -> Self {
+        Self {
+            key: key.to_string(),
+            label: label.to_string(),
+            kind,
+            value,
+            required: false,
+            help: None,
+            step: 0,
+            validator: None,
+        }
+    }
+
+    pub fn text(key: &str, label: &str, default: &str) -> Self {
+        Self::new(key, label, FieldKind::Text, default.to_string())
+    }
+
+    pub fn number(key: &str, label: &str, default: u64) -> Self {
+        Self::new(key, label, FieldKind::Number, default.to_string())
+    }
+
+    pub fn toggle(key: &str, label: &str, default: bool) -> Self {
+        Self::new(key, label, FieldKind::Toggle, default.to_string())
+    }
+
+    /// Select field, pre-selecting `default` when it is one of the options
+    pub fn select(key: &str, label: &str, options: Vec<SelectOption>, default: &str) -> Self {
+        let mut field = Self::new(
+            key,
+            label,
+            FieldKind::Select {
+                options: Vec::new(),
+                filter: String::new(),
+                selected: 0,
+            },
+            String::new(),
+        );
+        field.set_options(options, default);
+        field
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    pub fn in_step(mut self, step: usize) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn with_validator(mut self, validator: Validator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Replace the options of a select field, keeping `preferred` selected
+    /// when available
+    pub fn set_options(&mut self, new_options: Vec<SelectOption>, preferred: &str) {
+        if let FieldKind::Select {
+            options,
+            filter,
+            selected,
+        } = &mut self.kind
+        {
+            filter.clear();
+            *selected = new_options
+                .iter()
+                .position(|o| o.value == preferred)
+                .unwrap_or(0);
+            *options = new_options;
+        }
+        self.sync_select_value();
+    }
+
+    /// Options of a select field matching its filter
+    pub fn filtered_options(&self) -> Vec<&SelectOption> {
+        match &self.kind {
+            FieldKind::Select {
+                options, filter, ..
+            } => {
+                let filter = filter.to_lowercase();
+                options
+                    .iter()
+                    .filter(|o| {
+                        filter.is_empty()
+                            || o.label.to_lowercase().contains(&filter)
+                            || o.value.to_lowercase().contains(&filter)
+                    })
+                    .collect()
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Label of the current value (the option label for selects)
+    pub fn display_value(&self) -> String {
+        match &self.kind {
+            FieldKind::Select { options, .. } => options
+                .iter()
+                .find(|o| o.value == self.value)
+                .map(|o| o.label.clone())
+                .unwrap_or_default(),
+            FieldKind::Toggle => {
+                if self.is_on() {
+                    "[x]".to_string()
+                } else {
+                    "[ ]".to_string()
+                }
+            },
+            _ => self.value.clone(),
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.value == "true"
+    }
+
+    fn sync_select_value(&mut self) {
+        let value = match &self.kind {
+            FieldKind::Select { selected, .. } => self
+                .filtered_options()
+                .get(*selected)
+                .map(|o| o.value.clone()),
+            _ => return,
+        };
+        self.value = value.unwrap_or_default();
+    }
+
+    /// Type a character into the field
+    pub fn input_char(&mut self, c: char) {
+        match &mut self.kind {
+            FieldKind::Text => self.value.push(c),
+            FieldKind::Number if c.is_ascii_digit() => self.value.push(c),
+            FieldKind::Toggle if c == ' ' => self.toggle_value(),
+            FieldKind::Select {
+                filter, selected, ..
+            } => {
+                filter.push(c);
+                *selected = 0;
+                self.sync_select_value();
+            },
+            _ => {},
+        }
+    }
+
+    /// Delete the last character (or filter character for selects)
+    pub fn backspace(&mut self) {
+        match &mut self.kind {
+            FieldKind::Text | FieldKind::Number => {
+                self.value.pop();
+            },
+            FieldKind::Select {
+                filter, selected, ..
+            } => {
+                filter.pop();
+                *selected = 0;
+                self.sync_select_value();
+            },
+            FieldKind::Toggle => {},
+        }
+    }
+
+    /// Move the selection of a select field, or flip a toggle
+    pub fn cycle(&mut self, forward: bool) {
+        if let FieldKind::Toggle = self.kind {
+            self.toggle_value();
+            return;
+        }
+        let count = self.filtered_options().len();
+        if let FieldKind::Select { selected, .. } = &mut self.kind {
+            if count == 0 {
+                return;
+            }
+            *selected = if forward {
+                (*selected + 1) % count
+            } else {
+                (*selected + count - 1) % count
+            };
+        }
+        self.sync_select_value();
+    }
+
+    fn toggle_value(&mut self) {
+        self.value = (!self.is_on()).to_string();
+    }
+
+    /// Check the value against the field's kind and required flag
+    pub fn validate(&self) -> Result<(), String> {
+        if self.required && self.value.trim().is_empty() {
+            return Err(format!("{} is required", self.label));
+        }
+        if self.kind == FieldKind::Number
+            && !self.value.is_empty()
+            && self.value.parse::<u64>().is_err()
+        {
+            return Err(format!("{} must be a number", self.label));
+        }
+        match self.validator {
+            Some(validator) if !self.value.is_empty() => validator(&self.value),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Stage of a form
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormStage {
+    /// Editing the fields of the current step
+    Edit,
+    /// Reviewing the request before submitting
+    Preview(Value),
+}
+
+/// State of an open form
+#[derive(Debug, Clone)]
+pub struct FormState {
+    pub title: String,
+    pub purpose: FormPurpose,
+    /// Step titles (a single-step form has one)
+    pub steps: Vec<String>,
+    pub step: usize,
+    pub fields: Vec<FormField>,
+    /// Index into `fields` of the focused field
+    pub focused: usize,
+    pub stage: FormStage,
+    /// Scroll offset of the preview
+    pub preview_scroll: usize,
+    /// Notes shown above the preview (e.g. the impact of the request)
+    pub notes: Vec<String>,
+    pub error: Option<String>,
+    /// Data the submit handler needs (e.g. the selected item)
+    pub context: Value,
+}
+
+impl FormState {
+    pub fn new(title: &str, purpose: FormPurpose, steps: &[&str], fields: Vec<FormField>) -> Self {
+        let mut form = Self {
+            title: title.to_string(),
+            purpose,
+            steps: steps.iter().map(|s| s.to_string()).collect(),
+            step: 0,
+            fields,
+            focused: 0,
+            stage: FormStage::Edit,
+            preview_scroll: 0,
+            notes: Vec::new(),
+            error: None,
+            context: Value::Null,
+        };
+        form.focus_first_in_step();
+        form
+    }
+
+    pub fn with_context(mut self, context: Value) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// String value from the context ("" when missing)
+    pub fn context_str(&self, key: &str) -> &str {
+        self.context
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+    }
+
+    /// Indices of the fields of the current step
+    pub fn step_fields(&self) -> Vec<usize> {
+        self.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.step == self.step)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn focus_first_in_step(&mut self) {
+        if let Some(&first) = self.step_fields().first() {
+            self.focused = first;
+        }
+    }
+
+    pub fn field(&self, key: &str) -> Option<&FormField> {
+        self.fields.iter().find(|f| f.key == key)
+    }
+
+    pub fn field_mut(&mut self, key: &str) -> Option<&mut FormField> {
+        self.fields.iter_mut().find(|f| f.key == key)
+    }
+
+    /// Value of a field ("" when missing)
+    pub fn value(&self, key: &str) -> &str {
+        self.field(key).map(|f| f.value.as_str()).unwrap_or("")
+    }
+
+    pub fn is_on(&self, key: &str) -> bool {
+        self.field(key).is_some_and(|f| f.is_on())
+    }
+
+    pub fn focused_field_mut(&mut self) -> Option<&mut FormField> {
+        self.fields.get_mut(self.focused)
+    }
+
+    /// Move focus within the current step, returns false at the boundary
+    pub fn move_focus(&mut self, forward: bool) -> bool {
+        let fields = self.step_fields();
+        let Some(pos) = fields.iter().position(|&i| i == self.focused) else {
+            self.focus_first_in_step();
+            return false;
+        };
+        let target = if forward {
+            fields.get(pos + 1)
+        } else {
+            pos.checked_sub(1).and_then(|p| fields.get(p))
+        };
+        match target {
+            Some(&index) => {
+                self.focused = index;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Validate the fields of the current step
+    pub fn validate_step(&self) -> Result<(), String> {
+        for index in self.step_fields() {
+            self.fields[index].validate()?;
+        }
+        Ok(())
+    }
+
+    pub fn is_last_step(&self) -> bool {
+        self.step + 1 >= self.steps.len()
+    }
+
+    /// Go to the next step (after validating the current one)
+    pub fn next_step(&mut self) -> Result<(), String> {
+        self.validate_step()?;
+        if !self.is_last_step() {
+            self.step += 1;
+            self.focus_first_in_step();
+        }
+        Ok(())
+    }
+
+    /// Go back one step, returns false on the first step
+    pub fn prev_step(&mut self) -> bool {
+        if self.step == 0 {
+            return false;
+        }
+        self.step -= 1;
+        self.focus_first_in_step();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_form() -> FormState {
+        FormState::new(
+            "Test",
+            FormPurpose::CreateInstance,
+            &["One", "Two"],
+            vec![
+                FormField::text("name", "Name", "").required(),
+                FormField::number("size", "Size", 10),
+                FormField::select(
+                    "type",
+                    "Type",
+                    vec![
+                        SelectOption::plain("pd-balanced"),
+                        SelectOption::plain("pd-ssd"),
+                        SelectOption::plain("pd-standard"),
+                    ],
+                    "pd-ssd",
+                )
+                .in_step(1),
+                FormField::toggle("spot", "Spot", false).in_step(1),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_select_default_and_filter() {
+        let mut form = sample_form();
+        assert_eq!(form.value("type"), "pd-ssd");
+
+        let field = form.field_mut("type").unwrap();
+        field.input_char('s');
+        field.input_char('t');
+        assert_eq!(field.filtered_options().len(), 1);
+        assert_eq!(field.value, "pd-standard");
+
+        field.backspace();
+        field.backspace();
+        field.cycle(false);
+        assert_eq!(field.value, "pd-standard");
+        field.cycle(true);
+        assert_eq!(field.value, "pd-balanced");
+    }
+
+    #[test]
+    fn test_number_and_toggle_input() {
+        let mut form = sample_form();
+        let size = form.field_mut("size").unwrap();
+        size.input_char('0');
+        size.input_char('x');
+        assert_eq!(size.value, "100");
+
+        let spot = form.field_mut("spot").unwrap();
+        spot.input_char(' ');
+        assert!(form.is_on("spot"));
+    }
+
+    #[test]
+    fn test_steps_validate_required_fields() {
+        let mut form = sample_form();
+        assert_eq!(form.step_fields(), vec![0, 1]);
+        assert!(form.next_step().is_err());
+
+        form.field_mut("name").unwrap().input_char('a');
+        assert!(form.next_step().is_ok());
+        assert_eq!(form.step, 1);
+        assert_eq!(form.focused, 2);
+        assert!(form.is_last_step());
+
+        assert!(form.move_focus(true));
+        assert!(!form.move_focus(true));
+        assert!(form.prev_step());
+        assert_eq!(form.focused, 0);
+    }
+}
@@ -51,6 +51,12 @@ impl GcpClient {
         self.http.post(url, &token, body).await
     }
 
+    /// Make a PATCH request to a GCP API
+    pub async fn patch(&self, url: &str, body: &Value) -> Result<Value> {
+        let token = self.get_token().await?;
+        self.http.patch(url, &token, body).await
+    }
+
     /// Make a DELETE request to a GCP API
     pub async fn delete(&self, url: &str) -> Result<Value> {
         let token = self.get_token().await?;
@@ -92,6 +98,15 @@ impl GcpClient {
         )
     }
 
+    /// Build Compute Engine API URL for another project (e.g. public image projects)
+    pub fn compute_project_url(&self, project: &str, path: &str) -> String {
+        format!(
+            "https://compute.googleapis.com/compute/v1/projects/{}/{}",
+            urlencoding::encode(project),
+            path
+        )
+    }
+
     /// Build zonal Compute Engine API URL
     pub fn compute_zonal_url(&self, resource: &str) -> String {
         self.compute_url(&format!("zones/{}/{}", self.zone, resource))
@@ -131,6 +146,14 @@ impl GcpClient {
         self.storage_url(&format!("b/{}/o", bucket))
     }
 
+    /// Build Cloud Storage upload URL of a bucket's objects
+    pub fn storage_upload_url(&self, bucket: &str) -> String {
+        format!(
+            "https://storage.googleapis.com/upload/storage/v1/b/{}/o",
+            bucket
+        )
+    }
+
     // =========================================================================
     // GKE API helpers
     // =========================================================================
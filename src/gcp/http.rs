@@ -84,6 +84,12 @@ impl GcpHttpClient {
         Ok(Self { client })
     }
 
+    /// Underlying reqwest client, for requests that are not JSON (object
+    /// media transfers)
+    pub fn inner(&self) -> &Client {
+        &self.client
+    }
+
     /// Make a GET request to a GCP API with retry logic for transient errors
     pub async fn get(&self, url: &str, token: &str) -> Result<Value> {
         tracing::debug!("GET {}", url);
@@ -193,6 +199,64 @@ impl GcpHttpClient {
         Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Request failed after retries")))
     }
 
+    /// Make a PATCH request to a GCP API with retry logic for transient errors
+    pub async fn patch(&self, url: &str, token: &str, body: &Value) -> Result<Value> {
+        tracing::debug!("PATCH {}", url);
+
+        let mut last_error = None;
+
+        for attempt in 0..=MAX_RETRIES {
+            let response = self
+                .client
+                .patch(url)
+                .bearer_auth(token)
+                .json(body)
+                .send()
+                .await
+                .context("Failed to send request")?;
+
+            let status = response.status();
+            let response_body = response
+                .text()
+                .await
+                .context("Failed to read response body")?;
+
+            if status.is_success() {
+                if response_body.is_empty() {
+                    return Ok(Value::Null);
+                }
+                return serde_json::from_str(&response_body)
+                    .context("Failed to parse response JSON");
+            }
+
+            // Check if error is retryable
+            if is_retryable_status(status) && attempt < MAX_RETRIES {
+                let delay = calculate_backoff_delay(attempt);
+                tracing::warn!(
+                    "Transient error {} on PATCH {}, retrying in {:?} (attempt {}/{})",
+                    status,
+                    url,
+                    delay,
+                    attempt + 1,
+                    MAX_RETRIES
+                );
+                tokio::time::sleep(delay).await;
+                last_error = Some(anyhow::anyhow!("API request failed: {}", status));
+                continue;
+            }
+
+            // Security: Only log sanitized/truncated error body to avoid leaking sensitive data
+            tracing::error!(
+                "API error: {} - {}",
+                status,
+                sanitize_for_log(&response_body)
+            );
+            return Err(anyhow::anyhow!("API request failed: {}", status));
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Request failed after retries")))
+    }
+
     /// Make a DELETE request to a GCP API with retry logic for transient errors
     pub async fn delete(&self, url: &str, token: &str) -> Result<Value> {
         tracing::debug!("DELETE {}", url);
@@ -276,6 +340,10 @@ pub fn format_gcp_error(error: &anyhow::Error) -> String {
     if error_str.contains("500") || error_str.contains("503") {
         return "GCP service temporarily unavailable. Please try again.".to_string();
     }
+    if error_str.contains("412") {
+        return "Precondition failed: the resource changed since it was loaded. Refresh and try again."
+            .to_string();
+    }
     if error_str.contains("409") {
         return "Resource conflict. The resource may already exist or be in use.".to_string();
     }
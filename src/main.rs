@@ -1,12 +1,27 @@
 mod app;
+mod bucket;
+mod chain;
+mod clipboard;
 mod config;
+mod describe;
+mod diff;
+mod edit;
 mod event;
+mod firewall;
+mod form;
 mod gcp;
 mod notification;
+mod preview;
 mod resource;
+mod serial;
 mod shell;
+mod storage;
 mod theme;
+mod transfer;
+mod tunnel;
 mod ui;
+mod versions;
+mod wizard;
 
 /// Version injected at compile time via TGCP_VERSION env var (set by CI/CD),
 /// or "dev" for local builds.
@@ -139,6 +154,7 @@ async fn main() -> Result<()> {
     match result {
         Ok(Some(mut app)) => {
             let run_result = run_app(&mut terminal, &mut app).await;
+            app.tunnels.stop_all();
             cleanup_terminal(&mut terminal)?;
 
             if let Err(err) = run_result {
@@ -336,6 +352,10 @@ where
     B::Error: Send + Sync + 'static,
 {
     loop {
+        if app.terminal_dirty {
+            terminal.clear()?;
+            app.terminal_dirty = false;
+        }
         terminal.draw(|f| ui::render(f, app))?;
 
         if event::handle_events(app).await? {
@@ -346,6 +366,11 @@ where
         if let Err(e) = app.poll_pending_operations().await {
             tracing::warn!("Failed to poll pending operations: {}", e);
         }
+        app.poll_operation_chains().await;
+        app.poll_serial_output().await;
+        app.poll_tunnels();
+        app.poll_run_output();
+        app.poll_transfers().await;
 
         // Auto-refresh (disabled by default)
         if app.needs_refresh() {
@@ -57,7 +57,15 @@ pub enum OperationType {
     Start,
     Stop,
     Reset,
+    Suspend,
+    Resume,
     Delete,
+    Create,
+    Update,
+    Attach,
+    Detach,
+    Download,
+    Upload,
     Other(String),
 }
 
@@ -67,7 +75,22 @@ impl OperationType {
             "start_instance" => Self::Start,
             "stop_instance" => Self::Stop,
             "reset_instance" => Self::Reset,
+            "suspend_instance" => Self::Suspend,
+            "resume_instance" => Self::Resume,
+            "scp_from_instance" => Self::Download,
+            "scp_to_instance" => Self::Upload,
+            "download_object" => Self::Download,
+            "upload_object" => Self::Upload,
+            "toggle_versioning" | "toggle_public_access_prevention" | "restore_object" => {
+                Self::Update
+            },
             m if m.starts_with("delete_") => Self::Delete,
+            m if m.starts_with("insert_") || m.starts_with("create_") => Self::Create,
+            m if m.starts_with("set_") || m.starts_with("resize_") || m.starts_with("patch_") => {
+                Self::Update
+            },
+            m if m.starts_with("attach_") => Self::Attach,
+            m if m.starts_with("detach_") => Self::Detach,
             other => Self::Other(other.to_string()),
         }
     }
@@ -77,7 +100,15 @@ impl OperationType {
             Self::Start => "Start",
             Self::Stop => "Stop",
             Self::Reset => "Reset",
+            Self::Suspend => "Suspend",
+            Self::Resume => "Resume",
             Self::Delete => "Delete",
+            Self::Create => "Create",
+            Self::Update => "Update",
+            Self::Attach => "Attach",
+            Self::Detach => "Detach",
+            Self::Download => "Download",
+            Self::Upload => "Upload",
             Self::Other(name) => name,
         }
     }
@@ -87,7 +118,15 @@ impl OperationType {
             Self::Start => "Started",
             Self::Stop => "Stopped",
             Self::Reset => "Reset",
+            Self::Suspend => "Suspended",
+            Self::Resume => "Resumed",
             Self::Delete => "Deleted",
+            Self::Create => "Created",
+            Self::Update => "Updated",
+            Self::Attach => "Attached",
+            Self::Detach => "Detached",
+            Self::Download => "Downloaded",
+            Self::Upload => "Uploaded",
             Self::Other(_) => "Completed",
         }
     }
@@ -97,7 +136,15 @@ impl OperationType {
             Self::Start => "Starting",
             Self::Stop => "Stopping",
             Self::Reset => "Resetting",
+            Self::Suspend => "Suspending",
+            Self::Resume => "Resuming",
             Self::Delete => "Deleting",
+            Self::Create => "Creating",
+            Self::Update => "Updating",
+            Self::Attach => "Attaching",
+            Self::Detach => "Detaching",
+            Self::Download => "Downloading",
+            Self::Upload => "Uploading",
             Self::Other(_) => "Processing",
         }
     }
@@ -142,6 +189,10 @@ pub struct Notification {
     pub gcp_operation_url: Option<String>,
     pub created_at: Instant,
     pub completed_at: Option<Instant>,
+    /// Position within an operation chain (step, total), 1-based
+    pub chain_step: Option<(usize, usize)>,
+    /// Progress of a running transfer (bytes, throughput, ETA)
+    pub progress: Option<String>,
 }
 
 impl Notification {
@@ -155,6 +206,8 @@ impl Notification {
             gcp_operation_url: None,
             created_at: Instant::now(),
             completed_at: None,
+            chain_step: None,
+            progress: None,
         }
     }
 
@@ -195,9 +248,25 @@ impl Notification {
         }
     }
 
+    /// Action name, prefixed with the chain position for linked operations
+    pub fn action_label(&self) -> String {
+        match self.chain_step {
+            Some((step, total)) => format!(
+                "[{}/{}] {}",
+                step,
+                total,
+                self.operation_type.display_name()
+            ),
+            None => self.operation_type.display_name().to_string(),
+        }
+    }
+
     /// Format notification for toast display (short form)
     pub fn toast_message(&self, detail_level: DetailLevel) -> String {
-        let icon = self.status.icon();
+        let icon = match self.chain_step {
+            Some((step, total)) => format!("{} [{}/{}]", self.status.icon(), step, total),
+            None => self.status.icon().to_string(),
+        };
         let verb = match &self.status {
             NotificationStatus::Pending | NotificationStatus::InProgress => {
                 self.operation_type.present_participle()
@@ -206,6 +275,14 @@ impl Notification {
             NotificationStatus::Error(_) => "Failed",
         };
 
+        let progress = match self.progress {
+            Some(ref progress) if !self.status.is_terminal() => progress.as_str(),
+            _ => "",
+        };
+        if !progress.is_empty() {
+            return format!("{} {} {} {}", icon, verb, self.resource_id, progress);
+        }
+
         match detail_level {
             DetailLevel::Minimal => {
                 format!("{} {} {}", icon, verb, self.resource_id)
@@ -342,6 +419,22 @@ impl NotificationManager {
         }
     }
 
+    /// Record the position of a notification within an operation chain
+    pub fn set_chain_step(&mut self, id: Uuid, step: usize, total: usize) {
+        if let Some(notif) = self.notifications.iter_mut().find(|n| n.id == id) {
+            notif.chain_step = Some((step, total));
+        }
+    }
+
+    /// Update the progress text of a running transfer, keeping its toast
+    /// visible
+    pub fn set_progress(&mut self, id: Uuid, progress: String) {
+        if let Some(notif) = self.notifications.iter_mut().find(|n| n.id == id) {
+            notif.progress = Some(progress);
+            self.last_toast_time = Some(Instant::now());
+        }
+    }
+
     /// Mark a notification as successful
     pub fn mark_success(&mut self, id: Uuid) {
         if let Some(notif) = self.notifications.iter_mut().find(|n| n.id == id) {
@@ -490,10 +583,34 @@ mod tests {
             OperationType::from_method("start_instance"),
             OperationType::Start
         ));
+        assert!(matches!(
+            OperationType::from_method("suspend_instance"),
+            OperationType::Suspend
+        ));
+        assert!(matches!(
+            OperationType::from_method("set_deletion_protection"),
+            OperationType::Update
+        ));
         assert!(matches!(
             OperationType::from_method("delete_disk"),
             OperationType::Delete
         ));
+        assert!(matches!(
+            OperationType::from_method("insert_instance"),
+            OperationType::Create
+        ));
+        assert!(matches!(
+            OperationType::from_method("set_machine_type"),
+            OperationType::Update
+        ));
+        assert!(matches!(
+            OperationType::from_method("resize_disk"),
+            OperationType::Update
+        ));
+        assert!(matches!(
+            OperationType::from_method("detach_disk"),
+            OperationType::Detach
+        ));
         assert!(matches!(
             OperationType::from_method("custom_action"),
             OperationType::Other(_)
@@ -518,5 +635,24 @@ mod tests {
         let msg = notif.toast_message(DetailLevel::Minimal);
         assert!(msg.contains("Started"));
         assert!(msg.contains("✓"));
+
+        // Linked step of an operation chain
+        notif.chain_step = Some((3, 3));
+        let msg = notif.toast_message(DetailLevel::Minimal);
+        assert_eq!(msg, "✓ [3/3] Started my-vm");
+        assert_eq!(notif.action_label(), "[3/3] Start");
+
+        // Running transfer with its progress
+        let mut notif = Notification::new(
+            OperationType::from_method("download_object"),
+            "storage".to_string(),
+            "gs://b/a.txt".to_string(),
+        );
+        notif.set_in_progress(None);
+        notif.progress = Some("1.0 MB / 2.0 MB (50%)".to_string());
+        let msg = notif.toast_message(DetailLevel::Detailed);
+        assert_eq!(msg, "↻ Downloading gs://b/a.txt 1.0 MB / 2.0 MB (50%)");
+        notif.set_success();
+        assert!(!notif.toast_message(DetailLevel::Detailed).contains("50%"));
     }
 }
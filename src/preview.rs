@@ -0,0 +1,482 @@
+//! Object Preview
+//!
+//! State of the Cloud Storage object preview. The object is read in ranged
+//! `alt=media` requests of [`PREVIEW_CHUNK`] bytes, more being loaded on
+//! demand. The bytes read so far are gunzipped when they start with the gzip
+//! magic, then shown according to the object's `contentType` (or its
+//! extension): JSON pretty-printed, CSV as a table, text as is and anything
+//! else as a hex dump.
+
+use flate2::read::MultiGzDecoder;
+use serde_json::Value;
+use std::io::Read;
+
+/// Bytes read by each request of the preview
+pub const PREVIEW_CHUNK: u64 = 64 * 1024;
+
+/// Bytes the preview reads at most (in compressed form for gzip objects)
+pub const MAX_PREVIEW: u64 = 16 * 1024 * 1024;
+
+/// Decompressed bytes shown at most for gzip objects (a small compressed
+/// object may expand to gigabytes)
+pub const MAX_DECODED: u64 = 16 * 1024 * 1024;
+
+/// Bytes per line of the hex dump
+const HEX_WIDTH: usize = 16;
+
+/// How the content is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewFormat {
+    Json,
+    /// Delimited values (comma, or tab for TSV)
+    Csv(char),
+    Text,
+    Hex,
+}
+
+impl PreviewFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv(',') => "csv",
+            Self::Csv(_) => "tsv",
+            Self::Text => "text",
+            Self::Hex => "binary",
+        }
+    }
+}
+
+/// Object preview state
+#[derive(Debug, Clone)]
+pub struct ObjectPreview {
+    pub bucket: String,
+    pub name: String,
+    pub generation: Option<String>,
+    pub content_type: String,
+    /// Object size, when listed
+    pub size: Option<u64>,
+    /// Bytes read so far, as stored
+    pub data: Vec<u8>,
+    /// Whether the last read reached the end of the object
+    pub complete: bool,
+    /// The object is gzip-compressed and shown decompressed
+    pub gzip: bool,
+    /// Size of the decompressed content
+    pub decoded_len: usize,
+    /// The decompressed content reached [`MAX_DECODED`] and was cut there
+    pub decoded_truncated: bool,
+    pub format: PreviewFormat,
+    /// Rendered lines (JSON, text, hex dump)
+    pub lines: Vec<String>,
+    /// Rows of a CSV, the first being the header
+    pub table: Vec<Vec<String>>,
+    /// First visible line or row
+    pub scroll: usize,
+    /// Height of the viewport at the last render (for paging)
+    pub viewport_height: usize,
+    pub error: Option<String>,
+}
+
+impl ObjectPreview {
+    /// Preview of an object row, None for folders
+    pub fn new(item: &Value) -> Option<Self> {
+        if crate::storage::is_folder(item) {
+            return None;
+        }
+        let field = |key: &str| {
+            item.get(key)
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let size = field("size").and_then(|s| s.parse::<u64>().ok());
+        Some(Self {
+            bucket: field("bucket")?,
+            name: field("name")?,
+            generation: field("generation"),
+            content_type: field("contentType").unwrap_or_default(),
+            size,
+            data: Vec::new(),
+            complete: size == Some(0),
+            gzip: false,
+            decoded_len: 0,
+            decoded_truncated: false,
+            format: PreviewFormat::Text,
+            lines: Vec::new(),
+            table: Vec::new(),
+            scroll: 0,
+            viewport_height: 1,
+            error: None,
+        })
+    }
+
+    /// Byte range of the next read, None when everything was read or the
+    /// preview limit is reached
+    pub fn next_range(&self) -> Option<(u64, u64)> {
+        let start = self.data.len() as u64;
+        if self.complete || self.decoded_truncated || start >= MAX_PREVIEW {
+            return None;
+        }
+        let mut len = PREVIEW_CHUNK.min(MAX_PREVIEW - start);
+        if let Some(size) = self.size {
+            len = len.min(size.saturating_sub(start));
+        }
+        (len > 0).then_some((start, len))
+    }
+
+    /// Whether more of the object can be loaded
+    pub fn has_more(&self) -> bool {
+        self.next_range().is_some()
+    }
+
+    /// Append the bytes of a read of `requested` bytes
+    pub fn append(&mut self, bytes: &[u8], requested: u64) {
+        self.data.extend_from_slice(bytes);
+        let read = self.data.len() as u64;
+        self.complete = match self.size {
+            Some(size) => read >= size,
+            None => (bytes.len() as u64) < requested,
+        };
+        self.error = None;
+        self.rebuild();
+    }
+
+    /// Decode the bytes read so far and lay them out
+    fn rebuild(&mut self) {
+        self.gzip = self.data.starts_with(&[0x1f, 0x8b]);
+        let decoded = if self.gzip {
+            let (decoded, error, truncated) = gunzip(&self.data, MAX_DECODED);
+            self.decoded_truncated = truncated;
+            if self.complete && !truncated {
+                if let Some(e) = error {
+                    self.error = Some(format!("Failed to decompress: {}", e));
+                }
+            }
+            decoded
+        } else {
+            self.data.clone()
+        };
+        self.decoded_len = decoded.len();
+
+        let name = if self.gzip {
+            self.name.strip_suffix(".gz").unwrap_or(&self.name)
+        } else {
+            &self.name
+        };
+        // The stored content type of a gzip file describes the archive
+        let content_type = if self.gzip && self.content_type.contains("gzip") {
+            ""
+        } else {
+            &self.content_type
+        };
+        self.format = detect_format(content_type, name, &decoded);
+        self.lines.clear();
+        self.table.clear();
+        match self.format {
+            PreviewFormat::Hex => self.lines = hex_dump(&decoded),
+            PreviewFormat::Csv(delimiter) => {
+                self.table = parse_csv(&decode_text(&decoded), delimiter, self.complete)
+            },
+            PreviewFormat::Json => {
+                let text = decode_text(&decoded);
+                let pretty = serde_json::from_str::<Value>(&text)
+                    .ok()
+                    .and_then(|v| serde_json::to_string_pretty(&v).ok())
+                    .unwrap_or_else(|| pretty_json_partial(&text));
+                self.lines = pretty.lines().map(str::to_string).collect();
+            },
+            PreviewFormat::Text => {
+                self.lines = crate::serial::clean_output(&decode_text(&decoded))
+                    .lines()
+                    .map(str::to_string)
+                    .collect();
+            },
+        }
+        self.scroll = self.scroll.min(self.max_scroll());
+    }
+
+    /// Number of lines (or table rows below the header)
+    pub fn row_count(&self) -> usize {
+        match self.format {
+            PreviewFormat::Csv(_) => self.table.len().saturating_sub(1),
+            _ => self.lines.len(),
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.row_count().saturating_sub(self.viewport_height)
+    }
+
+    pub fn scroll_by(&mut self, delta: isize) {
+        let scroll = self.scroll.saturating_add_signed(delta);
+        self.scroll = scroll.min(self.max_scroll());
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+}
+
+/// Decompress as much of a (possibly truncated) gzip stream as possible, up
+/// to `limit` bytes, with the error that stopped it and whether the output
+/// was cut at the limit
+pub fn gunzip(data: &[u8], limit: u64) -> (Vec<u8>, Option<String>, bool) {
+    let mut decoded = Vec::new();
+    // read_to_end keeps what was decoded before an error; one byte past the
+    // limit tells a cut output from one that ends exactly there
+    let error = MultiGzDecoder::new(data)
+        .take(limit + 1)
+        .read_to_end(&mut decoded)
+        .err()
+        .map(|e| e.to_string());
+    let truncated = decoded.len() as u64 > limit;
+    decoded.truncate(limit as usize);
+    (decoded, error, truncated)
+}
+
+/// Format of the content from its content type, then its extension, then
+/// whether it looks like text
+pub fn detect_format(content_type: &str, name: &str, bytes: &[u8]) -> PreviewFormat {
+    if !looks_like_text(bytes) {
+        return PreviewFormat::Hex;
+    }
+    let content_type = content_type.to_lowercase();
+    if content_type.contains("json") {
+        return PreviewFormat::Json;
+    }
+    if content_type.contains("tab-separated") {
+        return PreviewFormat::Csv('\t');
+    }
+    if content_type.contains("csv") {
+        return PreviewFormat::Csv(',');
+    }
+    let extension = name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "json" => PreviewFormat::Json,
+        "csv" => PreviewFormat::Csv(','),
+        "tsv" => PreviewFormat::Csv('\t'),
+        _ => PreviewFormat::Text,
+    }
+}
+
+/// Whether bytes are UTF-8 text (a character cut at the end is fine) without
+/// NULs or many control characters
+fn looks_like_text(bytes: &[u8]) -> bool {
+    let valid = match std::str::from_utf8(bytes) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+    if !valid || bytes.contains(&0) {
+        return false;
+    }
+    let control = bytes
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\n' | b'\r' | b'\t' | 0x1b))
+        .count();
+    control * 20 <= bytes.len()
+}
+
+/// Bytes as text, dropping a character cut by the end of a range
+fn decode_text(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(e) => String::from_utf8_lossy(&bytes[..e.valid_up_to()]).to_string(),
+    }
+}
+
+/// Indent JSON that may be cut anywhere (serde cannot parse it)
+pub fn pretty_json_partial(text: &str) -> String {
+    let mut out = String::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let newline = |out: &mut String, depth: usize| {
+        out.push('\n');
+        out.push_str(&"  ".repeat(depth));
+    };
+    for c in text.chars() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            },
+            '{' | '[' => {
+                out.push(c);
+                depth += 1;
+                newline(&mut out, depth);
+            },
+            '}' | ']' => {
+                depth = depth.saturating_sub(1);
+                newline(&mut out, depth);
+                out.push(c);
+            },
+            ',' => {
+                out.push(c);
+                newline(&mut out, depth);
+            },
+            ':' => out.push_str(": "),
+            c if c.is_whitespace() => {},
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Split delimited text into rows, honouring quotes. A row cut by the end
+/// of a partial read is dropped.
+pub fn parse_csv(text: &str, delimiter: char, complete: bool) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => row.push(std::mem::take(&mut field)),
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            },
+            '\r' if !in_quotes => {},
+            c => field.push(c),
+        }
+    }
+    if complete && (!field.is_empty() || !row.is_empty()) {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+/// Hex dump lines: offset, bytes and their printable characters
+pub fn hex_dump(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .chunks(HEX_WIDTH)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!(
+                "{:08x}  {:<width$}  |{}|",
+                index * HEX_WIDTH,
+                hex.join(" "),
+                ascii,
+                width = HEX_WIDTH * 3 - 1
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use serde_json::json;
+    use std::io::Write;
+
+    fn object(name: &str, content_type: &str, size: usize) -> ObjectPreview {
+        ObjectPreview::new(&json!({
+            "bucket": "b",
+            "name": name,
+            "contentType": content_type,
+            "size": size.to_string(),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_preview_formats() {
+        let text = br#"{"a":[1,2],"b":"x"}"#;
+        let mut preview = object("config.json", "application/json", text.len());
+        assert_eq!(preview.next_range(), Some((0, text.len() as u64)));
+        preview.append(text, PREVIEW_CHUNK);
+        assert!(preview.complete && !preview.has_more());
+        assert_eq!(preview.format, PreviewFormat::Json);
+        assert_eq!(preview.lines[1], r#"  "a": ["#);
+
+        // Cut JSON is still indented
+        assert_eq!(
+            pretty_json_partial(r#"{"a":{"b":"x,y"#),
+            "{\n  \"a\": {\n    \"b\": \"x,y"
+        );
+
+        let mut preview = object("data.csv", "text/csv", 100);
+        preview.append(b"id,name\n1,\"Doe, J\"\n2,Sm", 24);
+        assert!(preview.has_more());
+        assert_eq!(preview.format, PreviewFormat::Csv(','));
+        assert_eq!(preview.table, vec![vec!["id", "name"], vec!["1", "Doe, J"]]);
+
+        let mut preview = object("blob", "application/octet-stream", 4);
+        preview.append(&[0, 1, b'A', 0xff], 4);
+        assert_eq!(preview.format, PreviewFormat::Hex);
+        assert!(preview.lines[0].starts_with("00000000  00 01 41 ff"));
+        assert!(preview.lines[0].ends_with("|..A.|"));
+
+        assert!(
+            ObjectPreview::new(&json!({ "bucket": "b", "name": "d/", "folder": true })).is_none()
+        );
+    }
+
+    #[test]
+    fn test_preview_gzip() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"name,size\na,1\n").unwrap();
+        let data = encoder.finish().unwrap();
+
+        let mut preview = object("rows.csv.gz", "application/gzip", data.len());
+        preview.append(&data, PREVIEW_CHUNK);
+        assert!(preview.gzip);
+        assert_eq!(preview.format, PreviewFormat::Csv(','));
+        assert_eq!(preview.table.len(), 2);
+        assert!(preview.error.is_none());
+
+        // A truncated stream yields what could be decoded
+        let (decoded, error, truncated) = gunzip(&data[..data.len() - 8], MAX_DECODED);
+        assert_eq!(decoded, b"name,size\na,1\n");
+        assert!(error.is_some() && !truncated);
+
+        // Output beyond the limit is cut, and nothing more is loaded
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&vec![b'a'; 4096]).unwrap();
+        let bomb = encoder.finish().unwrap();
+        let (decoded, error, truncated) = gunzip(&bomb, 1000);
+        assert_eq!(decoded.len(), 1000);
+        assert!(error.is_none() && truncated);
+        let (decoded, _, truncated) = gunzip(&bomb, 4096);
+        assert_eq!(decoded.len(), 4096);
+        assert!(!truncated);
+    }
+}
@@ -304,6 +304,48 @@ pub async fn fetch_resources_paginated(
     Ok(PaginatedResult { items, next_token })
 }
 
+/// Fetch full details for a single resource using its `detail_sdk_method`.
+///
+/// Returns `Ok(None)` when the resource type has no detail method. Each entry
+/// of `detail_sdk_method_params` maps a parameter name to a JSON path that is
+/// read from the list item (e.g. `{"zone": "zone_short"}`).
+pub async fn fetch_resource_detail(
+    resource_key: &str,
+    client: &GcpClient,
+    item: &Value,
+) -> Result<Option<Value>> {
+    let Some(resource_def) = get_resource(resource_key) else {
+        return Err(anyhow::anyhow!("Unknown resource: {}", resource_key));
+    };
+
+    let Some(method) = &resource_def.detail_sdk_method else {
+        return Ok(None);
+    };
+
+    let mut params = serde_json::Map::new();
+    if let Value::Object(map) = &resource_def.detail_sdk_method_params {
+        for (param, path) in map {
+            let Some(path) = path.as_str() else {
+                continue;
+            };
+            let value = extract_json_value(item, path);
+            if value != "-" {
+                params.insert(param.clone(), Value::String(value));
+            }
+        }
+    }
+
+    let response = sdk_dispatch::invoke_sdk(
+        &resource_def.service,
+        method,
+        client,
+        &Value::Object(params),
+    )
+    .await?;
+
+    Ok(Some(post_process_item(response, resource_def)))
+}
+
 /// Extract items from response using the response_path
 fn extract_items(response: &Value, path: &str, resource_def: &ResourceDef) -> Vec<Value> {
     let raw_items = if path.is_empty() {
@@ -347,6 +389,14 @@ fn post_process_item(mut item: Value, resource_def: &ResourceDef) -> Value {
             map.insert("region_short".to_string(), Value::String(short));
         }
 
+        if let Some(protected) = map.get("deletionProtection").and_then(|v| v.as_bool()) {
+            let display = if protected { "Yes" } else { "-" };
+            map.insert(
+                "deletionProtection_display".to_string(),
+                Value::String(display.to_string()),
+            );
+        }
+
         if let Some(machine_type) = map.get("machineType").and_then(|v| v.as_str()) {
             let short = extract_short_name(machine_type);
             map.insert(
@@ -357,6 +407,10 @@ fn post_process_item(mut item: Value, resource_def: &ResourceDef) -> Value {
             // Extract vCPUs from machine type name (e.g., n1-standard-4 -> 4)
             let vcpus = extract_vcpus_from_machine_type(&short);
             map.insert("vcpus".to_string(), Value::String(vcpus));
+
+            // Memory in GB from machine type name (e.g., n2-standard-4 -> 16)
+            let memory = extract_memory_from_machine_type(&short);
+            map.insert("memory_gb".to_string(), Value::String(memory));
         }
 
         if let Some(disk_type) = map.get("type").and_then(|v| v.as_str()) {
@@ -364,6 +418,11 @@ fn post_process_item(mut item: Value, resource_def: &ResourceDef) -> Value {
             map.insert("type_short".to_string(), Value::String(short));
         }
 
+        if let Some(source_disk) = map.get("sourceDisk").and_then(|v| v.as_str()) {
+            let short = extract_short_name(source_disk);
+            map.insert("sourceDisk_short".to_string(), Value::String(short));
+        }
+
         if let Some(network) = map.get("network").and_then(|v| v.as_str()) {
             let short = extract_short_name(network);
             map.insert("network_short".to_string(), Value::String(short));
@@ -423,6 +482,12 @@ fn post_process_item(mut item: Value, resource_def: &ResourceDef) -> Value {
             map.insert("size_display".to_string(), Value::String(display));
         }
 
+        // Snapshot storage size
+        if let Some(bytes) = map.get("storageBytes").and_then(|v| v.as_str()) {
+            let display = format_bytes(bytes.parse().unwrap_or(0));
+            map.insert("storageBytes_display".to_string(), Value::String(display));
+        }
+
         // GKE specific
         if let Some(autopilot) = map
             .get("autopilot")
@@ -727,6 +792,71 @@ fn extract_vcpus_from_machine_type(machine_type: &str) -> String {
     "-".to_string()
 }
 
+/// Extract memory in GB from machine type name
+/// e.g., `n2-standard-4` -> `16`, `n1-highcpu-8` -> `7.2`, `custom-2-6144` -> `6`
+fn extract_memory_from_machine_type(machine_type: &str) -> String {
+    // Custom machine types: [family-]custom-N-M[-ext] where M is memory in MB
+    let parts: Vec<&str> = machine_type.split('-').collect();
+    if let Some(idx) = parts.iter().position(|&p| p == "custom") {
+        if let Some(Ok(memory_mb)) = parts.get(idx + 2).map(|p| p.parse::<u32>()) {
+            return format_gb(f64::from(memory_mb) / 1024.0);
+        }
+    }
+
+    // Shared-core machine types
+    match machine_type {
+        "f1-micro" => return "0.6".to_string(),
+        "g1-small" => return "1.7".to_string(),
+        "e2-micro" => return "1".to_string(),
+        "e2-small" => return "2".to_string(),
+        "e2-medium" => return "4".to_string(),
+        _ => {},
+    }
+
+    // Predefined types: family-class-N, memory is a fixed ratio per vCPU
+    if parts.len() == 3 {
+        if let Ok(vcpus) = parts[2].parse::<u32>() {
+            let per_vcpu = match (parts[0], parts[1]) {
+                ("n1", "standard") => Some(3.75),
+                ("n1", "highmem") => Some(6.5),
+                ("n1", "highcpu") => Some(0.9),
+                ("e2" | "n2" | "n2d", "highcpu") => Some(1.0),
+                ("c2d" | "c3" | "c3d" | "c4" | "c4a" | "n4", "highcpu") => Some(2.0),
+                (_, "standard") => Some(4.0),
+                (_, "highmem") => Some(8.0),
+                // The highcpu ratio differs between families: unknown ones
+                // are not guessed
+                _ => None,
+            };
+            if let Some(per_vcpu) = per_vcpu {
+                return format_gb(f64::from(vcpus) * per_vcpu);
+            }
+        }
+    }
+
+    "-".to_string()
+}
+
+/// Human-readable size of a machine type (e.g. `e2-medium` -> `1 vCPU, 4 GB`)
+pub fn machine_type_summary(machine_type: &str) -> String {
+    let vcpus = extract_vcpus_from_machine_type(machine_type);
+    let memory = extract_memory_from_machine_type(machine_type);
+    match (vcpus.as_str(), memory.as_str()) {
+        ("-", "-") => String::new(),
+        (vcpus, "-") => format!("{} vCPU", vcpus),
+        (vcpus, memory) => format!("{} vCPU, {} GB", vcpus, memory),
+    }
+}
+
+/// Format GB with at most two decimals, without trailing zeros
+fn format_gb(gb: f64) -> String {
+    let formatted = format!("{:.2}", gb);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
 /// Format timestamp to short form
 fn format_timestamp_short(timestamp: &str) -> String {
     // RFC3339 format: 2023-01-15T10:30:00.000Z
@@ -738,7 +868,7 @@ fn format_timestamp_short(timestamp: &str) -> String {
 }
 
 /// Format bytes to human readable
-fn format_bytes(bytes: u64) -> String {
+pub fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -757,6 +887,18 @@ fn format_bytes(bytes: u64) -> String {
     }
 }
 
+/// Format a duration (uptime, ETA) as `45s`, `3m05s` or `1h02m`
+pub fn format_duration(d: std::time::Duration) -> String {
+    let secs = d.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
 /// Enrich VM instances with monitoring metrics (CPU, Network, Disk IO)
 /// This function fetches metrics from Cloud Monitoring API and merges them into the items
 /// with visual indicators for trends and load levels
@@ -985,3 +1127,46 @@ pub fn extract_json_value(item: &Value, path: &str) -> String {
         Value::Object(_) => "[object]".to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_vcpus_from_machine_type() {
+        assert_eq!(extract_vcpus_from_machine_type("n1-standard-4"), "4");
+        assert_eq!(extract_vcpus_from_machine_type("e2-medium"), "1");
+        assert_eq!(extract_vcpus_from_machine_type("n2-custom-6-24576"), "6");
+        assert_eq!(extract_vcpus_from_machine_type("unknown"), "-");
+    }
+
+    #[test]
+    fn test_extract_memory_from_machine_type() {
+        assert_eq!(extract_memory_from_machine_type("n2-standard-4"), "16");
+        assert_eq!(extract_memory_from_machine_type("n1-standard-2"), "7.5");
+        assert_eq!(extract_memory_from_machine_type("n1-highcpu-8"), "7.2");
+        assert_eq!(extract_memory_from_machine_type("e2-highcpu-8"), "8");
+        assert_eq!(extract_memory_from_machine_type("c3-highcpu-4"), "8");
+        assert_eq!(extract_memory_from_machine_type("c2d-highcpu-2"), "4");
+        assert_eq!(extract_memory_from_machine_type("x9-highcpu-2"), "-");
+        assert_eq!(extract_memory_from_machine_type("e2-highmem-2"), "16");
+        assert_eq!(extract_memory_from_machine_type("custom-2-6144"), "6");
+        assert_eq!(extract_memory_from_machine_type("e2-small"), "2");
+        assert_eq!(extract_memory_from_machine_type("m1-ultramem-40"), "-");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        use std::time::Duration;
+        assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(format_duration(Duration::from_secs(185)), "3m05s");
+        assert_eq!(format_duration(Duration::from_secs(3725)), "1h02m");
+    }
+
+    #[test]
+    fn test_machine_type_summary() {
+        assert_eq!(machine_type_summary("e2-medium"), "1 vCPU, 4 GB");
+        assert_eq!(machine_type_summary("m1-ultramem-40"), "40 vCPU");
+        assert_eq!(machine_type_summary("unknown"), "");
+    }
+}
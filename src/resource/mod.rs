@@ -35,8 +35,9 @@ pub mod sdk_dispatch;
 
 #[allow(unused_imports)]
 pub use fetcher::{
-    enrich_with_metrics, extract_json_value, fetch_multiple_resources, fetch_resources,
-    fetch_resources_concurrent, fetch_resources_paginated, MetricsHistory, ResourceFilter,
+    enrich_with_metrics, extract_json_value, fetch_multiple_resources, fetch_resource_detail,
+    fetch_resources, fetch_resources_concurrent, fetch_resources_paginated, format_bytes,
+    format_duration, machine_type_summary, MetricsHistory, ResourceFilter,
 };
 pub use registry::*;
 pub use sdk_dispatch::execute_action;
@@ -75,9 +75,22 @@ pub struct ActionDef {
     /// If true, this action is a shell command (SSH, exec, etc.)
     #[serde(default)]
     pub shell_action: bool,
+    /// If true, this action opens an input form (e.g. a create wizard)
+    /// instead of acting on the selected item
+    #[serde(default)]
+    pub form: bool,
+    /// If true, this action opens a view or works on local state only
+    /// (preview, listing toggles, downloads) and never changes the resource
+    #[serde(default)]
+    pub view: bool,
 }
 
 impl ActionDef {
+    /// Whether the action may change cloud resources (refused read-only)
+    pub fn mutates(&self) -> bool {
+        !self.shell_action && !self.view
+    }
+
     /// Check if this action requires confirmation
     pub fn requires_confirm(&self) -> bool {
         self.confirm.is_some() || self.needs_confirm
@@ -123,9 +136,22 @@ pub struct ResourceDef {
     /// SDK method to call when fetching details for a single resource
     #[serde(default)]
     pub detail_sdk_method: Option<String>,
-    /// Parameters for detail_sdk_method
+    /// Parameters for detail_sdk_method, mapping each parameter name to the
+    /// JSON path of the list item that supplies its value
     #[serde(default)]
     pub detail_sdk_method_params: Value,
+    /// Fields skipped by `:diff` (full dot paths or key names), typically
+    /// volatile values such as fingerprints, timestamps and ids
+    #[serde(default)]
+    pub diff_ignore: Vec<String>,
+    /// URL path patterns of this resource's selfLinks (host excluded, `*`
+    /// matches one segment), used to follow references from the describe view
+    #[serde(default)]
+    pub self_link_patterns: Vec<String>,
+    /// Only listed from a parent row (as its sub-resource): left out of `:`
+    /// completion and refused as a top-level view
+    #[serde(default)]
+    pub parent_only: bool,
 }
 
 /// Root structure of resources/*.json
@@ -173,6 +199,81 @@ pub fn get_all_resource_keys() -> Vec<&'static str> {
         .collect()
 }
 
+/// Hint for opening a parent-only resource, naming the parent it is listed
+/// from and the shortcut that opens it
+pub fn parent_only_hint(key: &str) -> Option<String> {
+    let def = get_resource(key).filter(|def| def.parent_only)?;
+    let parent = get_registry()
+        .resources
+        .iter()
+        .find_map(|(parent_key, parent)| {
+            parent
+                .sub_resources
+                .iter()
+                .find(|sub| sub.resource_key == key)
+                .map(|sub| (parent_key, parent, sub))
+        });
+    Some(match parent {
+        Some((parent_key, parent, sub)) => format!(
+            "{} is opened from a row of {} (:{}) with '{}'",
+            def.display_name, parent.display_name, parent_key, sub.shortcut
+        ),
+        None => format!("{} is opened from its parent resource", def.display_name),
+    })
+}
+
+/// Resource referenced by a googleapis.com URL, with the scope it lives in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfLink {
+    pub resource_key: &'static str,
+    /// Name of the resource (the last path segment)
+    pub name: String,
+    pub project: Option<String>,
+    pub zone: Option<String>,
+    pub region: Option<String>,
+}
+
+/// Resolve a googleapis.com resource URL to a registry key, the name of the
+/// referenced resource and its project, zone or region
+pub fn resolve_self_link(url: &str) -> Option<SelfLink> {
+    let rest = url.strip_prefix("https://")?;
+    let (host, path) = rest.split_once('/')?;
+    if !host.ends_with(".googleapis.com") {
+        return None;
+    }
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let name = segments.last()?.to_string();
+    let scope = |kind: &str| {
+        segments
+            .windows(2)
+            .find(|pair| pair[0] == kind)
+            .map(|pair| pair[1].to_string())
+    };
+
+    let resource_key = get_registry()
+        .resources
+        .iter()
+        .find(|(_, def)| {
+            def.self_link_patterns.iter().any(|pattern| {
+                let parts: Vec<&str> = pattern.split('/').collect();
+                parts.len() == segments.len()
+                    && parts
+                        .iter()
+                        .zip(&segments)
+                        .all(|(p, s)| *p == "*" || p == s)
+            })
+        })
+        .map(|(key, _)| key.as_str())?;
+    Some(SelfLink {
+        resource_key,
+        name,
+        project: scope("projects"),
+        zone: scope("zones"),
+        region: scope("regions"),
+    })
+}
+
 /// Get a color map by name
 pub fn get_color_map(name: &str) -> Option<&'static Vec<ColorDef>> {
     get_registry().color_maps.get(name)
@@ -222,6 +323,112 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parent_only_resources() {
+        for def in get_registry().resources.values() {
+            for sub in &def.sub_resources {
+                assert!(get_resource(&sub.resource_key).is_some());
+            }
+        }
+        assert_eq!(
+            parent_only_hint("compute-router-status").as_deref(),
+            Some("Router Status is opened from a row of Cloud Routers (:compute-routers) with 's'")
+        );
+        assert!(parent_only_hint("compute-managed-instances")
+            .unwrap()
+            .contains("(:compute-instance-groups-managed) with 'i'"));
+        assert_eq!(parent_only_hint("compute-routers"), None);
+    }
+
+    #[test]
+    fn test_detail_methods_defined() {
+        for key in [
+            "compute-instances",
+            "storage-buckets",
+            "gke-clusters",
+            "cdn-backend-services",
+        ] {
+            let resource = get_resource(key).unwrap();
+            assert!(
+                resource.detail_sdk_method.is_some(),
+                "{} should define detail_sdk_method",
+                key
+            );
+            assert!(
+                resource.detail_sdk_method_params.is_object(),
+                "{} should define detail_sdk_method_params",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_actions_refused_read_only() {
+        let action = |key: &str, method: &str| {
+            get_resource(key)
+                .unwrap()
+                .actions
+                .iter()
+                .find(|a| a.sdk_method == method)
+                .unwrap()
+                .mutates()
+        };
+        assert!(!action("storage-objects", "preview_object"));
+        assert!(!action("storage-objects", "download_object"));
+        assert!(action("storage-objects", "upload_object"));
+        assert!(action("storage-objects", "delete_object"));
+        // Copying to a VM writes to it like an upload; copying from it does not
+        assert!(!action("compute-instances", "scp_from_instance"));
+        assert!(action("compute-instances", "scp_to_instance"));
+        // So does running a command on it
+        assert!(action("compute-instances", "run_command"));
+    }
+
+    #[test]
+    fn test_resolve_self_link() {
+        let key_and_name = |url: &str| resolve_self_link(url).map(|l| (l.resource_key, l.name));
+        let disk = resolve_self_link(
+            "https://www.googleapis.com/compute/v1/projects/p/zones/us-central1-a/disks/web-01",
+        )
+        .unwrap();
+        assert_eq!(disk.resource_key, "compute-disks");
+        assert_eq!(disk.name, "web-01");
+        assert_eq!(disk.project.as_deref(), Some("p"));
+        assert_eq!(disk.zone.as_deref(), Some("us-central1-a"));
+        assert_eq!(disk.region, None);
+        let subnet = resolve_self_link(
+            "https://www.googleapis.com/compute/v1/projects/p/regions/us-central1/subnetworks/default",
+        )
+        .unwrap();
+        assert_eq!(subnet.resource_key, "compute-subnetworks");
+        assert_eq!(subnet.region.as_deref(), Some("us-central1"));
+        assert_eq!(
+            key_and_name("https://www.googleapis.com/storage/v1/b/my-bucket"),
+            Some(("storage-buckets", "my-bucket".to_string()))
+        );
+        assert_eq!(
+            key_and_name(
+                "https://www.googleapis.com/compute/v1/projects/p/zones/us-central1-a/machineTypes/e2-medium"
+            ),
+            Some(("compute-machine-types", "e2-medium".to_string()))
+        );
+        assert_eq!(resolve_self_link("https://example.com/compute/v1/x"), None);
+    }
+
+    #[test]
+    fn test_self_link_patterns_are_unique() {
+        let mut seen = std::collections::HashSet::new();
+        for def in get_registry().resources.values() {
+            for pattern in &def.self_link_patterns {
+                assert!(
+                    seen.insert(pattern.clone()),
+                    "duplicate pattern {}",
+                    pattern
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_common_color_maps_exist() {
         let state_map = get_color_map("status");
@@ -56,28 +56,40 @@ pub async fn execute_action(
 async fn invoke_compute(method: &str, client: &GcpClient, params: &Value) -> Result<Value> {
     match method {
         "list_instances" => {
-            if client.zone == "all" {
+            if client.zone == "all" && get_param_str_opt(params, "zone").is_none() {
                 // Use aggregated API to get instances from all zones
                 let url = client.compute_aggregated_url("instances");
                 let url = add_query_params(&url, params);
                 let response = client.get(&url).await?;
                 Ok(flatten_aggregated_response(response))
             } else {
-                let url = client.compute_zonal_url("instances");
+                let url = zonal_url(client, params, "instances");
                 let url = add_query_params(&url, params);
                 client.get(&url).await
             }
         },
         "list_disks" => {
-            if client.zone == "all" {
+            // Disks of an instance (sub-resource): the list API can't filter
+            // on `users`, so match them here
+            let instance = get_param_str_opt(params, "instance");
+            let mut params = params.clone();
+            if let Value::Object(ref mut map) = params {
+                map.remove("instance");
+            }
+
+            let response = if client.zone == "all" {
                 let url = client.compute_aggregated_url("disks");
-                let url = add_query_params(&url, params);
-                let response = client.get(&url).await?;
-                Ok(flatten_aggregated_response(response))
+                let url = add_query_params(&url, &params);
+                flatten_aggregated_response(client.get(&url).await?)
             } else {
                 let url = client.compute_zonal_url("disks");
-                let url = add_query_params(&url, params);
-                client.get(&url).await
+                let url = add_query_params(&url, &params);
+                client.get(&url).await?
+            };
+
+            match instance {
+                Some(instance) => Ok(filter_disks_by_user(response, &instance)),
+                None => Ok(response),
             }
         },
         "list_networks" => {
@@ -86,7 +98,7 @@ async fn invoke_compute(method: &str, client: &GcpClient, params: &Value) -> Res
             client.get(&url).await
         },
         "list_subnetworks" => {
-            let url = client.compute_regional_url("subnetworks");
+            let url = regional_url(client, params, "subnetworks");
             let url = add_query_params(&url, params);
             client.get(&url).await
         },
@@ -95,13 +107,241 @@ async fn invoke_compute(method: &str, client: &GcpClient, params: &Value) -> Res
             let url = add_query_params(&url, params);
             client.get(&url).await
         },
+        "list_routes" => {
+            // Network of the parent as a filter expression
+            let url = client.compute_global_url("routes");
+            let url = add_query_params(&url, &compute_filter_params(params, &["network"]));
+            Ok(enrich_routes(client.get(&url).await?))
+        },
+        "list_routers" => {
+            let response = if client.zone == "all" {
+                let url = client.compute_aggregated_url("routers");
+                let url = add_query_params(&url, params);
+                flatten_aggregated_response(client.get(&url).await?)
+            } else {
+                let url = client.compute_regional_url("routers");
+                let url = add_query_params(&url, params);
+                client.get(&url).await?
+            };
+            Ok(enrich_routers(response))
+        },
+        "list_router_status" => {
+            // Parent router by selfLink
+            let router = get_param_str(params, "router")?;
+            let url = format!("{}/getRouterStatus", compute_link(&router)?);
+            Ok(router_status_rows(&client.get(&url).await?))
+        },
+        "list_instance_group_managers" => {
+            let response = if client.zone == "all" {
+                // Aggregated lists include zonal and regional groups
+                let url = client.compute_aggregated_url("instanceGroupManagers");
+                let url = add_query_params(&url, params);
+                flatten_aggregated_response(client.get(&url).await?)
+            } else {
+                let url = client.compute_zonal_url("instanceGroupManagers");
+                let url = add_query_params(&url, params);
+                let mut response = client.get(&url).await?;
+                // Regional groups of the zone's region, with the first page
+                if get_param_str_opt(params, "pageToken").is_none() {
+                    let url = client.compute_regional_url("instanceGroupManagers");
+                    match get_all_pages(client, &url).await {
+                        Ok(regional) => merge_items(&mut response, regional),
+                        Err(e) => tracing::warn!("Failed to list regional groups: {}", e),
+                    }
+                }
+                response
+            };
+            Ok(enrich_instance_group_managers(response))
+        },
+        "list_managed_instances" => {
+            // Parent group by selfLink (zonal or regional)
+            let manager = get_param_str(params, "instance_group_manager")?;
+            let url = format!("{}/listManagedInstances", compute_link(&manager)?);
+            let mut rest = params.clone();
+            if let Value::Object(ref mut map) = rest {
+                map.remove("instance_group_manager");
+            }
+            let url = add_query_params(&url, &rest);
+            let response = client.post(&url, None).await?;
+            Ok(enrich_managed_instances(response, &manager))
+        },
+        "list_instance_templates" => {
+            let url = client.compute_global_url("instanceTemplates");
+            let url = add_query_params(&url, params);
+            Ok(enrich_instance_templates(client.get(&url).await?))
+        },
+        "list_instance_groups" => {
+            if client.zone == "all" {
+                let url = client.compute_aggregated_url("instanceGroups");
+                let url = add_query_params(&url, params);
+                Ok(flatten_aggregated_response(client.get(&url).await?))
+            } else {
+                let url = client.compute_zonal_url("instanceGroups");
+                let url = add_query_params(&url, params);
+                client.get(&url).await
+            }
+        },
+        "list_snapshots" => {
+            let url = client.compute_global_url("snapshots");
+            let url = add_query_params(&url, &compute_filter_params(params, &["sourceDisk"]));
+            client.get(&url).await
+        },
+        "list_resource_policies" => {
+            let response = if client.zone == "all" {
+                let url = client.compute_aggregated_url("resourcePolicies");
+                let url = add_query_params(&url, params);
+                flatten_aggregated_response(client.get(&url).await?)
+            } else {
+                let url = client.compute_regional_url("resourcePolicies");
+                let url = add_query_params(&url, params);
+                client.get(&url).await?
+            };
+            // Attachments are recorded on the disks, not on the policies
+            let disks_url = client.compute_aggregated_url("disks");
+            let disks = match get_all_pages(client, &disks_url).await {
+                Ok(disks) => disks,
+                Err(e) => {
+                    tracing::warn!("Failed to list disks for resource policies: {}", e);
+                    serde_json::json!({ "items": [] })
+                },
+            };
+            Ok(enrich_resource_policies(response, &disks))
+        },
+        "list_addresses" => {
+            let response = if client.zone == "all" && get_param_str_opt(params, "region").is_none()
+            {
+                let url = client.compute_aggregated_url("addresses");
+                let url = add_query_params(&url, params);
+                flatten_aggregated_response(client.get(&url).await?)
+            } else {
+                let url = regional_url(client, params, "addresses");
+                let url = add_query_params(&url, params);
+                client.get(&url).await?
+            };
+            Ok(enrich_addresses(response))
+        },
+        "list_global_addresses" => {
+            let url = client.compute_global_url("addresses");
+            let url = add_query_params(&url, params);
+            Ok(enrich_addresses(client.get(&url).await?))
+        },
+        "list_machine_types" => {
+            // Forms ask for the types of a zone, the list view uses the
+            // current one
+            let zone = get_param_str_opt(params, "zone").unwrap_or_else(|| client.zone.clone());
+            if zone == "all" {
+                return get_all_pages(client, &client.compute_aggregated_url("machineTypes")).await;
+            }
+            let url = client.compute_url(&format!(
+                "zones/{}/machineTypes",
+                urlencoding::encode(&zone)
+            ));
+            get_all_pages(client, &url).await
+        },
+        "list_images" => {
+            // Images of another (typically public) project, e.g. debian-cloud
+            let project = get_param_str(params, "project")?;
+            let url = client.compute_project_url(&project, "global/images");
+            get_all_pages(client, &url).await
+        },
         "get_instance" => {
             let name = get_param_str(params, "name")?;
             // Security: URL-encode resource name to prevent injection
-            let url =
-                client.compute_zonal_url(&format!("instances/{}", urlencoding::encode(&name)));
+            let url = zonal_url(
+                client,
+                params,
+                &format!("instances/{}", urlencoding::encode(&name)),
+            );
             client.get(&url).await
         },
+        "get_serial_port_output" => {
+            let name = get_param_str(params, "name")?;
+            let port = get_param_str_opt(params, "port").unwrap_or_else(|| "1".to_string());
+            let start = get_param_str_opt(params, "start").unwrap_or_else(|| "0".to_string());
+            let url = zonal_url(
+                client,
+                params,
+                &format!("instances/{}/serialPort", urlencoding::encode(&name)),
+            );
+            let url = format!(
+                "{}?port={}&start={}",
+                url,
+                urlencoding::encode(&port),
+                urlencoding::encode(&start)
+            );
+            client.get(&url).await
+        },
+        "get_effective_firewalls" => {
+            let name = get_param_str(params, "name")?;
+            let nic = get_param_str_opt(params, "network_interface")
+                .unwrap_or_else(|| "nic0".to_string());
+            let url = zonal_url(
+                client,
+                params,
+                &format!(
+                    "instances/{}/getEffectiveFirewalls",
+                    urlencoding::encode(&name)
+                ),
+            );
+            let url = format!("{}?networkInterface={}", url, urlencoding::encode(&nic));
+            let mut response = client.get(&url).await?;
+
+            // Whether network policies apply before VPC rules is set on the
+            // network; the default is after
+            if let Some(network) = get_param_str_opt(params, "network") {
+                let order = match client.get(compute_link(&network)?).await {
+                    Ok(network) => network
+                        .get("networkFirewallPolicyEnforcementOrder")
+                        .cloned()
+                        .unwrap_or(Value::Null),
+                    Err(e) => {
+                        tracing::warn!("Failed to read network {}: {}", network, e);
+                        Value::Null
+                    },
+                };
+                response["networkFirewallPolicyEnforcementOrder"] = order;
+            }
+            Ok(response)
+        },
+        "get_backend_service" => {
+            let name = get_param_str(params, "name")?;
+            let encoded_name = urlencoding::encode(&name);
+            let url = client.compute_global_url(&format!("backendServices/{}", encoded_name));
+            let mut service = client.get(&url).await?;
+
+            // The list response carries no health information, so ask each
+            // backend group for its health and attach the results
+            let groups: Vec<String> = service
+                .get("backends")
+                .and_then(|v| v.as_array())
+                .map(|backends| {
+                    backends
+                        .iter()
+                        .filter_map(|b| b.get("group").and_then(|g| g.as_str()))
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let health_url =
+                client.compute_global_url(&format!("backendServices/{}/getHealth", encoded_name));
+            let mut backend_health = Vec::new();
+            for group in groups {
+                let body = serde_json::json!({ "group": group });
+                match client.post(&health_url, Some(&body)).await {
+                    Ok(health) => backend_health.push(serde_json::json!({
+                        "group": group,
+                        "healthStatus": health.get("healthStatus").cloned().unwrap_or_default(),
+                    })),
+                    Err(e) => tracing::debug!("Failed to get health for {}: {}", group, e),
+                }
+            }
+
+            if let Value::Object(ref mut map) = service {
+                map.insert("backendHealth".to_string(), Value::Array(backend_health));
+            }
+            Ok(service)
+        },
         // CDN / Load Balancing resources
         "list_backend_services" => {
             let url = client.compute_global_url("backendServices");
@@ -194,32 +434,225 @@ async fn execute_compute_action(
     method: &str,
     client: &GcpClient,
     resource_id: &str,
-    _params: &Value,
+    params: &Value,
 ) -> Result<Value> {
     // Security: URL-encode resource_id to prevent URL manipulation
     let encoded_id = urlencoding::encode(resource_id);
 
     match method {
+        "insert_instance" => {
+            let body = params.get("body").context("Missing request body")?;
+            let url = zonal_url(client, params, "instances");
+            client.post(&url, Some(body)).await
+        },
         "start_instance" => {
-            let url = client.compute_zonal_url(&format!("instances/{}/start", encoded_id));
+            let url = zonal_url(client, params, &format!("instances/{}/start", encoded_id));
             client.post(&url, None).await
         },
         "stop_instance" => {
-            let url = client.compute_zonal_url(&format!("instances/{}/stop", encoded_id));
+            let url = zonal_url(client, params, &format!("instances/{}/stop", encoded_id));
             client.post(&url, None).await
         },
         "reset_instance" => {
-            let url = client.compute_zonal_url(&format!("instances/{}/reset", encoded_id));
+            let url = zonal_url(client, params, &format!("instances/{}/reset", encoded_id));
+            client.post(&url, None).await
+        },
+        "suspend_instance" => {
+            let url = zonal_url(client, params, &format!("instances/{}/suspend", encoded_id));
+            client.post(&url, None).await
+        },
+        "resume_instance" => {
+            let url = zonal_url(client, params, &format!("instances/{}/resume", encoded_id));
+            client.post(&url, None).await
+        },
+        "set_deletion_protection" => {
+            // The state confirmed from the list, not a flip of the current one
+            let enable = params
+                .get("enable")
+                .and_then(|v| v.as_bool())
+                .context("Missing enable parameter")?;
+            let url = format!(
+                "{}?deletionProtection={}",
+                zonal_url(
+                    client,
+                    params,
+                    &format!("instances/{}/setDeletionProtection", encoded_id)
+                ),
+                enable
+            );
             client.post(&url, None).await
         },
         "delete_instance" => {
-            let url = client.compute_zonal_url(&format!("instances/{}", encoded_id));
+            let url = zonal_url(client, params, &format!("instances/{}", encoded_id));
+            client.delete(&url).await
+        },
+        "set_machine_type" => {
+            let machine_type = get_param_str(params, "machine_type")?;
+            let zone = get_param_str(params, "zone")?;
+            let body = serde_json::json!({
+                "machineType": format!("zones/{}/machineTypes/{}", zone, machine_type)
+            });
+            let url = zonal_url(
+                client,
+                params,
+                &format!("instances/{}/setMachineType", encoded_id),
+            );
+            client.post(&url, Some(&body)).await
+        },
+        "create_snapshot" => {
+            let body = params.get("body").context("Missing request body")?;
+            let url = zonal_url(
+                client,
+                params,
+                &format!("disks/{}/createSnapshot", encoded_id),
+            );
+            client.post(&url, Some(body)).await
+        },
+        "insert_address" => {
+            let body = params.get("body").context("Missing request body")?;
+            let url = regional_url(client, params, "addresses");
+            client.post(&url, Some(body)).await
+        },
+        "insert_global_address" => {
+            let body = params.get("body").context("Missing request body")?;
+            let url = client.compute_global_url("addresses");
+            client.post(&url, Some(body)).await
+        },
+        "delete_address" => {
+            let url = regional_url(client, params, &format!("addresses/{}", encoded_id));
+            client.delete(&url).await
+        },
+        "delete_global_address" => {
+            let url = client.compute_global_url(&format!("addresses/{}", encoded_id));
+            client.delete(&url).await
+        },
+        "insert_disk" => {
+            let body = params.get("body").context("Missing request body")?;
+            let url = zonal_url(client, params, "disks");
+            client.post(&url, Some(body)).await
+        },
+        "set_instance_labels" | "set_instance_metadata" | "set_instance_tags" => {
+            let body = params.get("body").context("Missing request body")?;
+            let call = match method {
+                "set_instance_labels" => "setLabels",
+                "set_instance_metadata" => "setMetadata",
+                _ => "setTags",
+            };
+            let url = zonal_url(
+                client,
+                params,
+                &format!("instances/{}/{}", encoded_id, call),
+            );
+            client.post(&url, Some(body)).await
+        },
+        "set_disk_labels" => {
+            let body = params.get("body").context("Missing request body")?;
+            let url = zonal_url(client, params, &format!("disks/{}/setLabels", encoded_id));
+            client.post(&url, Some(body)).await
+        },
+        "resize_instance_group" => {
+            let size = get_param_str(params, "size")?;
+            let url = format!(
+                "{}?size={}",
+                group_url(client, params, &format!("{}/resize", encoded_id)),
+                urlencoding::encode(&size)
+            );
+            client.post(&url, None).await
+        },
+        "rolling_restart" | "rolling_replace" => {
+            // Like `gcloud ... rolling-action restart/replace`: renamed
+            // versions make every instance outdated, and a proactive update
+            // policy lets the group roll through them a few at a time
+            let url = group_url(client, params, &encoded_id);
+            let group = client.get(&url).await?;
+            let stamp = chrono::Utc::now().format("%Y%m%d%H%M%S").to_string();
+            let body = rolling_update_body(&group, method == "rolling_restart", &stamp)?;
+            client.patch(&url, &body).await
+        },
+        "recreate_instances" | "abandon_instances" => {
+            // Managed instances carry the link of their group
+            let manager = get_param_str(params, "instance_group_manager")?;
+            let zone = get_param_str(params, "zone")?;
+            let call = if method == "recreate_instances" {
+                "recreateInstances"
+            } else {
+                "abandonInstances"
+            };
+            let body = serde_json::json!({
+                "instances": [format!("zones/{}/instances/{}", zone, resource_id)]
+            });
+            let url = format!("{}/{}", compute_link(&manager)?, call);
+            client.post(&url, Some(&body)).await
+        },
+        "resize_disk" => {
+            let size_gb = get_param_str(params, "size_gb")?;
+            let body = serde_json::json!({ "sizeGb": size_gb });
+            let url = zonal_url(client, params, &format!("disks/{}/resize", encoded_id));
+            client.post(&url, Some(&body)).await
+        },
+        "attach_disk" => {
+            let instance = get_param_str(params, "instance")?;
+            let zone = get_param_str(params, "zone")?;
+            let mode = get_param_str_opt(params, "mode").unwrap_or_else(|| "READ_WRITE".into());
+            let body = serde_json::json!({
+                "source": format!(
+                    "projects/{}/zones/{}/disks/{}",
+                    client.project_id, zone, resource_id
+                ),
+                "mode": mode,
+            });
+            let url = zonal_url(
+                client,
+                params,
+                &format!("instances/{}/attachDisk", urlencoding::encode(&instance)),
+            );
+            client.post(&url, Some(&body)).await
+        },
+        "detach_disk" => {
+            // detachDisk takes the device name, which is only known by the instance
+            let instance = get_param_str(params, "instance")?;
+            let instance_path = format!("instances/{}", urlencoding::encode(&instance));
+            let attached = client
+                .get(&zonal_url(client, params, &instance_path))
+                .await?;
+            let device_name = attached
+                .get("disks")
+                .and_then(|v| v.as_array())
+                .and_then(|disks| {
+                    disks.iter().find(|disk| {
+                        disk.get("source")
+                            .and_then(|v| v.as_str())
+                            .is_some_and(|source| source.rsplit('/').next() == Some(resource_id))
+                    })
+                })
+                .and_then(|disk| disk.get("deviceName"))
+                .and_then(|v| v.as_str())
+                .with_context(|| format!("Disk {} is not attached to {}", resource_id, instance))?;
+            let url = format!(
+                "{}?deviceName={}",
+                zonal_url(client, params, &format!("{}/detachDisk", instance_path)),
+                urlencoding::encode(device_name)
+            );
+            client.post(&url, None).await
+        },
+        "delete_snapshot" => {
+            let url = client.compute_global_url(&format!("snapshots/{}", encoded_id));
             client.delete(&url).await
         },
         "delete_disk" => {
             let url = client.compute_zonal_url(&format!("disks/{}", encoded_id));
             client.delete(&url).await
         },
+        "insert_firewall" => {
+            let body = params.get("body").context("Missing request body")?;
+            let url = client.compute_global_url("firewalls");
+            client.post(&url, Some(body)).await
+        },
+        "patch_firewall" => {
+            let body = params.get("body").context("Missing request body")?;
+            let url = client.compute_global_url(&format!("firewalls/{}", encoded_id));
+            client.patch(&url, body).await
+        },
         "delete_firewall" => {
             let url = client.compute_global_url(&format!("firewalls/{}", encoded_id));
             client.delete(&url).await
@@ -301,16 +734,115 @@ async fn invoke_storage(method: &str, client: &GcpClient, params: &Value) -> Res
             let url = add_query_params(&url, params);
             client.get(&url).await
         },
-        "list_objects" => {
+        "list_objects" | "list_object_versions" => {
             let bucket = get_param_str(params, "bucket")?;
             let url = client.storage_objects_url(&bucket);
             let url = add_query_params(&url, params);
-            client.get(&url).await
+            let response = client.get(&url).await?;
+            Ok(enrich_object_listing(method, response, params))
+        },
+        "get_bucket" => {
+            let bucket = get_param_str(params, "bucket")?;
+            let encoded_bucket = urlencoding::encode(&bucket);
+            let url = format!(
+                "{}?projection=full",
+                client.storage_bucket_url(&encoded_bucket)
+            );
+            let mut details = client.get(&url).await?;
+
+            // IAM bindings are not part of the bucket resource itself
+            let iam_url = format!("{}/iam", client.storage_bucket_url(&encoded_bucket));
+            match client.get(&iam_url).await {
+                Ok(policy) => {
+                    if let Value::Object(ref mut map) = details {
+                        map.insert("iamPolicy".to_string(), policy);
+                    }
+                },
+                Err(e) => tracing::debug!("Failed to get IAM policy for {}: {}", bucket, e),
+            }
+            Ok(details)
         },
         _ => Err(anyhow::anyhow!("Unknown storage method: {}", method)),
     }
 }
 
+/// Rows of an object listing. The object browser (`list_objects`) keeps one
+/// row per object of a `versions=true` listing; `list_object_versions` keeps
+/// every generation for the versions view.
+fn enrich_object_listing(method: &str, response: Value, params: &Value) -> Value {
+    let prefix = get_param_str_opt(params, "prefix").unwrap_or_default();
+    let delimited = get_param_str_opt(params, "delimiter").is_some();
+    let versions = get_param_str_opt(params, "versions").as_deref() == Some("true");
+    let collapse = versions && method == "list_objects";
+    enrich_objects(response, &prefix, delimited, collapse)
+}
+
+/// Add the `prefixes` of a delimited listing as folder rows before the
+/// objects, and names relative to the listed prefix. With `collapse`, a
+/// `versions=true` listing keeps one row per object, deleted ones flagged
+fn enrich_objects(mut response: Value, prefix: &str, delimited: bool, collapse: bool) -> Value {
+    let folders: Vec<Value> = response
+        .get("prefixes")
+        .and_then(|v| v.as_array())
+        .map(|prefixes| {
+            prefixes
+                .iter()
+                .filter_map(|p| p.as_str())
+                .map(|p| {
+                    serde_json::json!({
+                        "kind": "storage#folder",
+                        "id": p,
+                        "name": p,
+                        "name_display": p.strip_prefix(prefix).unwrap_or(p),
+                        "type_display": "Folder",
+                        "folder": true,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut items = response
+        .get_mut("items")
+        .and_then(|v| v.as_array_mut())
+        .map(std::mem::take)
+        .unwrap_or_default();
+    // The placeholder object of the listed folder itself ("logs/"); a leaf
+    // folder, or a later page, has no `prefixes` but may still return it.
+    // Other prefixes are object names (the versions of one object)
+    if delimited && prefix.ends_with('/') {
+        items.retain(|o| o.get("name").and_then(|v| v.as_str()) != Some(prefix));
+    }
+    if collapse {
+        items = crate::storage::collapse_versions(items);
+    }
+    for object in items.iter_mut() {
+        let name = object
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let display = name.strip_prefix(prefix).unwrap_or(name).to_string();
+        let content_type = if crate::storage::is_deleted(object) {
+            "Deleted".to_string()
+        } else {
+            object
+                .get("contentType")
+                .and_then(|v| v.as_str())
+                .unwrap_or("-")
+                .to_string()
+        };
+        if let Value::Object(ref mut map) = object {
+            map.insert("name_display".to_string(), Value::String(display));
+            map.insert("type_display".to_string(), Value::String(content_type));
+        }
+    }
+
+    let mut rows = folders;
+    rows.extend(items);
+    response["items"] = Value::Array(rows);
+    response
+}
+
 async fn execute_storage_action(
     method: &str,
     client: &GcpClient,
@@ -326,11 +858,77 @@ async fn execute_storage_action(
             let url = client.storage_bucket_url(&encoded_id);
             client.delete(&url).await
         },
+        "patch_bucket" => {
+            let body = params.get("body").context("Missing request body")?;
+            let mut url = client.storage_bucket_url(&encoded_id);
+            // Fails with 412 if the bucket changed since it was loaded
+            if let Some(metageneration) = get_param_str_opt(params, "if_metageneration_match") {
+                url = format!(
+                    "{}?ifMetagenerationMatch={}",
+                    url,
+                    urlencoding::encode(&metageneration)
+                );
+            }
+            client.patch(&url, body).await
+        },
+        "toggle_versioning" | "toggle_public_access_prevention" => {
+            let url = client.storage_bucket_url(&encoded_id);
+            let versioning = method == "toggle_versioning";
+            // The state confirmed in the bucket view, checked against the
+            // metageneration it was read at
+            let enable = params
+                .get("enable")
+                .and_then(|v| v.as_bool())
+                .context("Missing enable parameter")?;
+            let metageneration = get_param_str(params, "if_metageneration_match")?;
+            let body = if versioning {
+                crate::bucket::versioning_patch(enable)
+            } else {
+                crate::bucket::public_access_prevention_patch(enable)
+            };
+            let url = format!(
+                "{}?ifMetagenerationMatch={}",
+                url,
+                urlencoding::encode(&metageneration)
+            );
+            client.patch(&url, &body).await
+        },
         "delete_object" => {
             let bucket = get_param_str(params, "bucket")?;
-            let url = format!("{}/{}", client.storage_objects_url(&bucket), encoded_id);
+            let mut url = format!("{}/{}", client.storage_objects_url(&bucket), encoded_id);
+            // A single generation (the live one or a noncurrent version)
+            if let Some(generation) = get_param_str_opt(params, "generation") {
+                url = format!("{}?generation={}", url, urlencoding::encode(&generation));
+            }
             client.delete(&url).await
         },
+        "restore_object" => {
+            let bucket = get_param_str(params, "bucket")?;
+            let generation = get_param_str(params, "generation")?;
+            let object_url = format!("{}/{}", client.storage_objects_url(&bucket), encoded_id);
+            let mut url = format!(
+                "{}/rewriteTo/b/{}/o/{}?sourceGeneration={}",
+                object_url,
+                urlencoding::encode(&bucket),
+                encoded_id,
+                urlencoding::encode(&generation)
+            );
+            // Fails with 412 if the object changed since the versions were listed
+            if let Some(live) = get_param_str_opt(params, "if_generation_match") {
+                url = format!("{}&ifGenerationMatch={}", url, urlencoding::encode(&live));
+            }
+            // Large objects may take several calls, continued by rewriteToken
+            let mut response = client.post(&url, None).await?;
+            while response.get("done").and_then(|v| v.as_bool()) == Some(false) {
+                let token = response
+                    .get("rewriteToken")
+                    .and_then(|v| v.as_str())
+                    .context("Rewrite not done but no rewriteToken returned")?;
+                let next = format!("{}&rewriteToken={}", url, urlencoding::encode(token));
+                response = client.post(&next, None).await?;
+            }
+            Ok(response)
+        },
         _ => Err(anyhow::anyhow!("Unknown storage action: {}", method)),
     }
 }
@@ -355,6 +953,16 @@ async fn invoke_container(method: &str, client: &GcpClient, params: &Value) -> R
                 .container_location_url(&location, &format!("clusters/{}/nodePools", cluster));
             client.get(&url).await
         },
+        "get_cluster" => {
+            let name = get_param_str(params, "name")?;
+            let location =
+                get_param_str_opt(params, "location").unwrap_or_else(|| client.zone.clone());
+            let url = client.container_location_url(
+                &location,
+                &format!("clusters/{}", urlencoding::encode(&name)),
+            );
+            client.get(&url).await
+        },
         _ => Err(anyhow::anyhow!("Unknown container method: {}", method)),
     }
 }
@@ -838,10 +1446,616 @@ async fn fetch_single_metric(
     Ok((metric_name.to_string(), instance_metrics))
 }
 
+/// Add a readable next hop and tags to routes
+fn enrich_routes(mut response: Value) -> Value {
+    const NEXT_HOPS: [(&str, &str); 7] = [
+        ("nextHopGateway", "gateway"),
+        ("nextHopInstance", "instance"),
+        ("nextHopIp", "ip"),
+        ("nextHopIlb", "ilb"),
+        ("nextHopVpnTunnel", "vpn"),
+        ("nextHopPeering", "peering"),
+        ("nextHopNetwork", "network"),
+    ];
+    if let Some(items) = response.get_mut("items").and_then(|v| v.as_array_mut()) {
+        for route in items.iter_mut() {
+            let next_hop = NEXT_HOPS
+                .iter()
+                .find_map(|(field, kind)| {
+                    let value = route.get(*field).and_then(|v| v.as_str())?;
+                    Some(format!("{}: {}", kind, short_name(value)))
+                })
+                .unwrap_or_else(|| "-".to_string());
+            let tags = route
+                .get("tags")
+                .and_then(|v| v.as_array())
+                .map(|tags| {
+                    tags.iter()
+                        .filter_map(|t| t.as_str())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| "-".to_string());
+            if let Value::Object(ref mut map) = route {
+                map.insert("next_hop_display".to_string(), Value::String(next_hop));
+                map.insert("tags_display".to_string(), Value::String(tags));
+            }
+        }
+    }
+    response
+}
+
+/// Add BGP and NAT summaries to Cloud Routers
+fn enrich_routers(mut response: Value) -> Value {
+    if let Some(items) = response.get_mut("items").and_then(|v| v.as_array_mut()) {
+        for router in items.iter_mut() {
+            let nats: Vec<String> = router
+                .get("nats")
+                .and_then(|v| v.as_array())
+                .map(|nats| {
+                    nats.iter()
+                        .map(|nat| {
+                            let name = nat.get("name").and_then(|v| v.as_str()).unwrap_or("-");
+                            match nat.get("natIpAllocateOption").and_then(|v| v.as_str()) {
+                                Some(option) => format!("{} ({})", name, option),
+                                None => name.to_string(),
+                            }
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let nat_display = if nats.is_empty() {
+                "-".to_string()
+            } else {
+                nats.join(", ")
+            };
+            let asn = router
+                .pointer("/bgp/asn")
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let peers = router
+                .get("bgpPeers")
+                .and_then(|v| v.as_array())
+                .map(|p| p.len())
+                .unwrap_or(0);
+            if let Value::Object(ref mut map) = router {
+                map.insert("nat_display".to_string(), Value::String(nat_display));
+                map.insert("bgp_asn".to_string(), Value::String(asn));
+                map.insert("bgpPeers_count".to_string(), Value::from(peers));
+            }
+        }
+    }
+    response
+}
+
+/// Rows of a `routers.getRouterStatus` response: one per BGP peer, then one
+/// per NAT gateway with its mapped IPs
+fn router_status_rows(response: &Value) -> Value {
+    let result = response.get("result").cloned().unwrap_or(Value::Null);
+    let list = |key: &str| {
+        result
+            .get(key)
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default()
+    };
+    let join = |value: Option<&Value>| {
+        value
+            .and_then(|v| v.as_array())
+            .map(|ips| {
+                ips.iter()
+                    .filter_map(|ip| ip.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default()
+    };
+    let text = |value: Option<&Value>| match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => "-".to_string(),
+    };
+
+    let mut rows = Vec::new();
+    for peer in list("bgpPeerStatus") {
+        let state = peer
+            .get("state")
+            .or_else(|| peer.get("status"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("UNKNOWN");
+        rows.push(serde_json::json!({
+            "name": text(peer.get("name")),
+            "kind": "BGP peer",
+            "state_display": state,
+            "address_display": format!(
+                "{} -> {}",
+                text(peer.get("ipAddress")),
+                text(peer.get("peerIpAddress"))
+            ),
+            "count_display": format!("{} routes", text(peer.get("numLearnedRoutes"))),
+            "uptime_display": text(peer.get("uptime")),
+            "status": peer,
+        }));
+    }
+    for nat in list("natStatus") {
+        let mut ips = join(nat.get("autoAllocatedNatIps"));
+        let user_ips = join(nat.get("userAllocatedNatIps"));
+        if !user_ips.is_empty() {
+            if !ips.is_empty() {
+                ips.push_str(", ");
+            }
+            ips.push_str(&user_ips);
+        }
+        let needed = nat
+            .get("minExtraNatIpsNeeded")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0);
+        // More IPs needed means some VMs can't get NAT ports
+        let state = if needed > 0 { "NEEDS_IPS" } else { "ACTIVE" };
+        rows.push(serde_json::json!({
+            "name": text(nat.get("name")),
+            "kind": "NAT",
+            "state_display": state,
+            "address_display": if ips.is_empty() { "-".to_string() } else { ips },
+            "count_display": if needed > 0 {
+                format!(
+                    "{} VMs, {} IPs short",
+                    text(nat.get("numVmEndpointsWithNatMappings")),
+                    needed
+                )
+            } else {
+                format!("{} VMs", text(nat.get("numVmEndpointsWithNatMappings")))
+            },
+            "uptime_display": "-",
+            "status": nat,
+        }));
+    }
+    serde_json::json!({ "items": rows })
+}
+
+/// Add the short names of the resources using each address
+fn enrich_addresses(mut response: Value) -> Value {
+    if let Some(items) = response.get_mut("items").and_then(|v| v.as_array_mut()) {
+        for address in items.iter_mut() {
+            let users: Vec<&str> = address
+                .get("users")
+                .and_then(|v| v.as_array())
+                .map(|users| {
+                    users
+                        .iter()
+                        .filter_map(|u| u.as_str())
+                        .map(short_name)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let display = match users.as_slice() {
+                [] => "-".to_string(),
+                [user] => user.to_string(),
+                [user, rest @ ..] => format!("{} (+{})", user, rest.len()),
+            };
+            if let Value::Object(ref mut map) = address {
+                map.insert("users_display".to_string(), Value::String(display));
+            }
+        }
+    }
+    response
+}
+
+/// Add location, template, stability and current actions to managed
+/// instance groups
+fn enrich_instance_group_managers(mut response: Value) -> Value {
+    if let Some(items) = response.get_mut("items").and_then(|v| v.as_array_mut()) {
+        for group in items.iter_mut() {
+            let location = group
+                .get("zone")
+                .or_else(|| group.get("region"))
+                .and_then(|v| v.as_str())
+                .map(short_name)
+                .unwrap_or("-")
+                .to_string();
+            let scope = if group.get("region").is_some() {
+                "Regional"
+            } else {
+                "Zonal"
+            };
+            let template = group
+                .get("instanceTemplate")
+                .and_then(|v| v.as_str())
+                .map(short_name)
+                .unwrap_or("-")
+                .to_string();
+            let stable = match group.pointer("/status/isStable").and_then(|v| v.as_bool()) {
+                Some(true) => "Stable",
+                Some(false) => "Updating",
+                None => "-",
+            };
+            let actions = describe_current_actions(group.get("currentActions"));
+
+            if let Value::Object(ref mut map) = group {
+                map.insert("location_short".to_string(), Value::String(location));
+                map.insert(
+                    "scope_display".to_string(),
+                    Value::String(scope.to_string()),
+                );
+                map.insert("template_short".to_string(), Value::String(template));
+                map.insert(
+                    "stable_display".to_string(),
+                    Value::String(stable.to_string()),
+                );
+                map.insert("actions_display".to_string(), Value::String(actions));
+            }
+        }
+    }
+    response
+}
+
+/// Non-zero counters of a group's `currentActions` (e.g. "2 creating")
+fn describe_current_actions(actions: Option<&Value>) -> String {
+    let parts: Vec<String> = actions
+        .and_then(|v| v.as_object())
+        .map(|counts| {
+            counts
+                .iter()
+                .filter(|(action, _)| action.as_str() != "none")
+                .filter_map(|(action, count)| {
+                    let count = count.as_i64().filter(|&c| c > 0)?;
+                    Some(format!("{} {}", count, action))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if parts.is_empty() {
+        "none".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Add name, zone, health and template to managed instances, and the group
+/// they belong to
+fn enrich_managed_instances(response: Value, manager: &str) -> Value {
+    let items: Vec<Value> = response
+        .get("managedInstances")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|mut instance| {
+            let url = instance
+                .get("instance")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string();
+            // .../zones/{zone}/instances/{name}
+            let mut segments = url.rsplit('/');
+            let name = segments.next().unwrap_or_default().to_string();
+            let zone_url = url
+                .rsplit_once("/instances/")
+                .map(|(zone, _)| zone.to_string())
+                .unwrap_or_default();
+            let health = instance
+                .get("instanceHealth")
+                .and_then(|v| v.as_array())
+                .and_then(|h| h.first())
+                .and_then(|h| h.get("detailedHealthState"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("-")
+                .to_string();
+            let template = instance
+                .pointer("/version/instanceTemplate")
+                .and_then(|v| v.as_str())
+                .map(short_name)
+                .unwrap_or("-")
+                .to_string();
+
+            if let Value::Object(ref mut map) = instance {
+                map.insert("name".to_string(), Value::String(name));
+                map.insert("zone".to_string(), Value::String(zone_url));
+                map.insert("health_display".to_string(), Value::String(health));
+                map.insert("template_short".to_string(), Value::String(template));
+                map.insert(
+                    "instanceGroupManager".to_string(),
+                    Value::String(manager.to_string()),
+                );
+            }
+            instance
+        })
+        .collect();
+
+    let mut result = serde_json::json!({ "items": items });
+    if let Some(token) = response.get("nextPageToken") {
+        result["nextPageToken"] = token.clone();
+    }
+    result
+}
+
+/// Add machine type, image and boot disk size to instance templates
+fn enrich_instance_templates(mut response: Value) -> Value {
+    if let Some(items) = response.get_mut("items").and_then(|v| v.as_array_mut()) {
+        for template in items.iter_mut() {
+            let machine_type = template
+                .pointer("/properties/machineType")
+                .and_then(|v| v.as_str())
+                .map(short_name)
+                .unwrap_or("-")
+                .to_string();
+            let boot = template
+                .pointer("/properties/disks")
+                .and_then(|v| v.as_array())
+                .and_then(|disks| {
+                    disks
+                        .iter()
+                        .find(|d| d.get("boot").and_then(|v| v.as_bool()) == Some(true))
+                })
+                .and_then(|d| d.get("initializeParams"));
+            let image = boot
+                .and_then(|p| p.get("sourceImage"))
+                .and_then(|v| v.as_str())
+                .map(image_display)
+                .unwrap_or_else(|| "-".to_string());
+            let disk_size = boot
+                .and_then(|p| p.get("diskSizeGb"))
+                .map(|v| match v {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .unwrap_or_else(|| "-".to_string());
+
+            if let Value::Object(ref mut map) = template {
+                map.insert(
+                    "machineType_display".to_string(),
+                    Value::String(machine_type),
+                );
+                map.insert("image_display".to_string(), Value::String(image));
+                map.insert("diskSizeGb_display".to_string(), Value::String(disk_size));
+            }
+        }
+    }
+    response
+}
+
+/// Image of a template: `family/<name>` for family references, otherwise
+/// the image name (the project is dropped)
+fn image_display(source: &str) -> String {
+    match source.rsplit_once("/family/") {
+        Some((_, family)) => format!("family/{}", family),
+        None => short_name(source).to_string(),
+    }
+}
+
+/// Keep the disks attached to an instance
+fn filter_disks_by_user(mut response: Value, instance: &str) -> Value {
+    let suffix = format!("/instances/{}", instance);
+    if let Some(items) = response.get_mut("items").and_then(|v| v.as_array_mut()) {
+        items.retain(|disk| {
+            disk.get("users")
+                .and_then(|v| v.as_array())
+                .is_some_and(|users| {
+                    users
+                        .iter()
+                        .any(|u| u.as_str().is_some_and(|u| u.ends_with(&suffix)))
+                })
+        });
+    }
+    response
+}
+
+/// Add schedule, retention and attached disks to resource policies
+fn enrich_resource_policies(mut response: Value, disks: &Value) -> Value {
+    let disks = disks
+        .get("items")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    if let Some(items) = response.get_mut("items").and_then(|v| v.as_array_mut()) {
+        for policy in items.iter_mut() {
+            let self_link = policy
+                .get("selfLink")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string();
+            let attached: Vec<Value> = disks
+                .iter()
+                .filter(|disk| {
+                    disk.get("resourcePolicies")
+                        .and_then(|v| v.as_array())
+                        .is_some_and(|links| links.iter().any(|l| l.as_str() == Some(&self_link)))
+                })
+                .filter_map(|disk| disk.get("name").cloned())
+                .collect();
+
+            let (kind, schedule, retention) =
+                if let Some(snapshot) = policy.get("snapshotSchedulePolicy") {
+                    let retention = snapshot
+                        .get("retentionPolicy")
+                        .and_then(|r| r.get("maxRetentionDays"))
+                        .and_then(|v| v.as_i64())
+                        .map(|days| format!("{}d", days))
+                        .unwrap_or_else(|| "-".to_string());
+                    (
+                        "Snapshot schedule",
+                        describe_schedule(snapshot.get("schedule")),
+                        retention,
+                    )
+                } else if policy.get("instanceSchedulePolicy").is_some() {
+                    ("Instance schedule", "-".to_string(), "-".to_string())
+                } else if policy.get("groupPlacementPolicy").is_some() {
+                    ("Placement", "-".to_string(), "-".to_string())
+                } else {
+                    ("Other", "-".to_string(), "-".to_string())
+                };
+
+            if let Value::Object(ref mut map) = policy {
+                let names: Vec<&str> = attached.iter().filter_map(|v| v.as_str()).collect();
+                map.insert(
+                    "attached_disks_display".to_string(),
+                    Value::String(if names.is_empty() {
+                        "-".to_string()
+                    } else {
+                        names.join(", ")
+                    }),
+                );
+                map.insert("attachedDisks".to_string(), Value::Array(attached));
+                map.insert("kind_display".to_string(), Value::String(kind.to_string()));
+                map.insert("schedule_display".to_string(), Value::String(schedule));
+                map.insert("retention_display".to_string(), Value::String(retention));
+            }
+        }
+    }
+
+    response
+}
+
+/// Human-readable snapshot schedule (e.g. "every 4h from 04:00")
+fn describe_schedule(schedule: Option<&Value>) -> String {
+    let Some(schedule) = schedule else {
+        return "-".to_string();
+    };
+    let start = |s: &Value| {
+        s.get("startTime")
+            .and_then(|v| v.as_str())
+            .unwrap_or("?")
+            .to_string()
+    };
+
+    if let Some(hourly) = schedule.get("hourlySchedule") {
+        let hours = hourly
+            .get("hoursInCycle")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(1);
+        format!("every {}h from {}", hours, start(hourly))
+    } else if let Some(daily) = schedule.get("dailySchedule") {
+        match daily.get("daysInCycle").and_then(|v| v.as_i64()) {
+            Some(days) if days > 1 => format!("every {}d at {}", days, start(daily)),
+            _ => format!("daily at {}", start(daily)),
+        }
+    } else if let Some(weekly) = schedule.get("weeklySchedule") {
+        let days: Vec<String> = weekly
+            .get("dayOfWeeks")
+            .and_then(|v| v.as_array())
+            .map(|days| {
+                days.iter()
+                    .map(|d| {
+                        let day = d.get("day").and_then(|v| v.as_str()).unwrap_or("?");
+                        format!("{} {}", &day[..day.len().min(3)], start(d))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        format!("weekly {}", days.join(", "))
+    } else {
+        "-".to_string()
+    }
+}
+
 // =============================================================================
 // Helpers
 // =============================================================================
 
+/// Last segment of a resource URL
+fn short_name(url: &str) -> &str {
+    url.rsplit('/').next().unwrap_or(url)
+}
+
+/// Append the items of `other` to `response`
+fn merge_items(response: &mut Value, other: Value) {
+    let Some(extra) = other.get("items").and_then(|v| v.as_array()).cloned() else {
+        return;
+    };
+    match response.get_mut("items").and_then(|v| v.as_array_mut()) {
+        Some(items) => items.extend(extra),
+        None => response["items"] = Value::Array(extra),
+    }
+}
+
+/// Check that a link (e.g. a parent selfLink) points to the Compute API
+/// before calling it
+fn compute_link(link: &str) -> Result<&str> {
+    if link.starts_with("https://compute.googleapis.com/compute/")
+        || link.starts_with("https://www.googleapis.com/compute/")
+    {
+        Ok(link.trim_end_matches('/'))
+    } else {
+        Err(anyhow::anyhow!(
+            "Not a Compute Engine resource link: {}",
+            link
+        ))
+    }
+}
+
+/// URL of a zonal or regional instance group manager, from the `zone` or
+/// `region` param of the group
+fn group_url(client: &GcpClient, params: &Value, path: &str) -> String {
+    match (
+        get_param_str_opt(params, "zone"),
+        get_param_str_opt(params, "region"),
+    ) {
+        (None, Some(_)) => regional_url(client, params, &format!("instanceGroupManagers/{}", path)),
+        _ => zonal_url(client, params, &format!("instanceGroupManagers/{}", path)),
+    }
+}
+
+/// Patch of a managed instance group rolling a restart or replacement
+/// through its instances: one at a time in a zonal group, one per zone in a
+/// regional group. A restart cannot surge extra instances.
+fn rolling_update_body(group: &Value, restart: bool, stamp: &str) -> Result<Value> {
+    let mut versions: Vec<Value> = group
+        .get("versions")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    if versions.is_empty() {
+        let template = group
+            .get("instanceTemplate")
+            .and_then(|v| v.as_str())
+            .context("Instance group has no instance template")?;
+        versions.push(serde_json::json!({ "instanceTemplate": template }));
+    }
+    for (index, version) in versions.iter_mut().enumerate() {
+        version["name"] = Value::String(format!("{}-{}", index, stamp));
+    }
+
+    let zones = group
+        .pointer("/distributionPolicy/zones")
+        .and_then(|v| v.as_array())
+        .map_or(1, |zones| zones.len().max(1));
+    let action = if restart { "RESTART" } else { "REPLACE" };
+    let surge = if restart { 0 } else { zones };
+    Ok(serde_json::json!({
+        "updatePolicy": {
+            "type": "PROACTIVE",
+            "minimalAction": action,
+            "mostDisruptiveAllowedAction": action,
+            "maxSurge": { "fixed": surge },
+            "maxUnavailable": { "fixed": zones },
+        },
+        "versions": versions,
+    }))
+}
+
+/// Turn parent filters (e.g. `sourceDisk`) into a Compute list `filter`
+/// expression, since list methods don't accept arbitrary field parameters
+fn compute_filter_params(params: &Value, fields: &[&str]) -> Value {
+    let Value::Object(map) = params else {
+        return params.clone();
+    };
+
+    let mut rest = map.clone();
+    let mut expressions = Vec::new();
+    for field in fields {
+        if let Some(value) = get_param_str_opt(params, field) {
+            rest.remove(*field);
+            expressions.push(format!("({} = \"{}\")", field, value.replace('"', "")));
+        }
+    }
+    if !expressions.is_empty() {
+        rest.insert("filter".to_string(), Value::String(expressions.join(" ")));
+    }
+    Value::Object(rest)
+}
+
 fn get_param_str(params: &Value, key: &str) -> Result<String> {
     params
         .get(key)
@@ -869,6 +2083,57 @@ fn get_param_str_opt(params: &Value, key: &str) -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Build a zonal Compute URL, preferring the resource's own zone from params.
+/// Needed when the client zone is "all" (aggregated view).
+fn zonal_url(client: &GcpClient, params: &Value, resource: &str) -> String {
+    match get_param_str_opt(params, "zone") {
+        Some(zone) if zone != "-" => client.compute_url(&format!(
+            "zones/{}/{}",
+            urlencoding::encode(&zone),
+            resource
+        )),
+        _ => client.compute_zonal_url(resource),
+    }
+}
+
+/// Build a regional Compute URL, preferring the region from params over the
+/// client's region
+fn regional_url(client: &GcpClient, params: &Value, resource: &str) -> String {
+    match get_param_str_opt(params, "region") {
+        Some(region) if region != "-" => client.compute_url(&format!(
+            "regions/{}/{}",
+            urlencoding::encode(&region),
+            resource
+        )),
+        _ => client.compute_regional_url(resource),
+    }
+}
+
+/// GET a list endpoint and follow nextPageToken, merging all items (the
+/// scopes of an aggregated list are flattened)
+async fn get_all_pages(client: &GcpClient, url: &str) -> Result<Value> {
+    let mut items: Vec<Value> = Vec::new();
+    let mut page_token: Option<String> = None;
+
+    loop {
+        let page_url = match &page_token {
+            Some(token) => format!("{}?pageToken={}", url, urlencoding::encode(token)),
+            None => url.to_string(),
+        };
+        let response = client.get(&page_url).await?;
+        page_token = response
+            .get("nextPageToken")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        items.extend(page_items(response));
+        if page_token.is_none() {
+            break;
+        }
+    }
+
+    Ok(serde_json::json!({ "items": items }))
+}
+
 fn add_query_params(url: &str, params: &Value) -> String {
     let Value::Object(map) = params else {
         return url.to_string();
@@ -878,7 +2143,10 @@ fn add_query_params(url: &str, params: &Value) -> String {
 
     for (key, value) in map {
         // Skip internal params
-        if key == "bucket" || key == "cluster" || key == "location" || key == "name" {
+        if matches!(
+            key.as_str(),
+            "bucket" | "cluster" | "location" | "name" | "region" | "zone"
+        ) {
             continue;
         }
 
@@ -906,6 +2174,19 @@ fn add_query_params(url: &str, params: &Value) -> String {
     }
 }
 
+/// Items of one page, flattening the per-scope map of an aggregated list
+fn page_items(response: Value) -> Vec<Value> {
+    let response = if response.get("items").is_some_and(Value::is_object) {
+        flatten_aggregated_response(response)
+    } else {
+        response
+    };
+    match response.get("items") {
+        Some(Value::Array(items)) => items.clone(),
+        _ => Vec::new(),
+    }
+}
+
 /// Flatten an aggregated API response into a standard list response.
 /// Aggregated responses have format: { "items": { "zones/us-central1-a": { "instances": [...] }, ... } }
 /// We flatten to: { "items": [...all instances...] }
@@ -934,3 +2215,291 @@ fn flatten_aggregated_response(response: Value) -> Value {
 
     serde_json::json!({ "items": all_items })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compute_filter_params() {
+        let params = json!({
+            "sourceDisk": ["https://www.googleapis.com/compute/v1/projects/p/zones/z/disks/d"],
+            "pageToken": "t",
+        });
+        let result = compute_filter_params(&params, &["sourceDisk"]);
+        assert_eq!(
+            result,
+            json!({
+                "pageToken": "t",
+                "filter": "(sourceDisk = \"https://www.googleapis.com/compute/v1/projects/p/zones/z/disks/d\")",
+            })
+        );
+        assert_eq!(
+            compute_filter_params(&json!({}), &["sourceDisk"]),
+            json!({})
+        );
+    }
+
+    #[test]
+    fn test_enrich_resource_policies() {
+        let link =
+            "https://www.googleapis.com/compute/v1/projects/p/regions/r/resourcePolicies/daily";
+        let policies = json!({"items": [{
+            "name": "daily",
+            "selfLink": link,
+            "snapshotSchedulePolicy": {
+                "schedule": {"dailySchedule": {"daysInCycle": 1, "startTime": "04:00"}},
+                "retentionPolicy": {"maxRetentionDays": 14},
+            },
+        }]});
+        // Disks come from an aggregated list, keyed by zone
+        let page = json!({"items": {
+            "zones/us-central1-a": {"disks": [
+                {"name": "data-1", "resourcePolicies": [link]},
+                {"name": "data-2"},
+            ]},
+            "zones/us-east1-b": {"warning": {"code": "NO_RESULTS_ON_PAGE"}},
+        }});
+        let disks = json!({ "items": page_items(page) });
+        let result = enrich_resource_policies(policies, &disks);
+        let policy = &result["items"][0];
+        assert_eq!(policy["schedule_display"], "daily at 04:00");
+        assert_eq!(policy["retention_display"], "14d");
+        assert_eq!(policy["attached_disks_display"], "data-1");
+        assert_eq!(policy["kind_display"], "Snapshot schedule");
+    }
+
+    #[test]
+    fn test_rolling_update_body() {
+        let zonal = json!({
+            "instanceTemplate": "global/instanceTemplates/web-v1",
+            "versions": [{ "instanceTemplate": "global/instanceTemplates/web-v1", "name": "" }],
+        });
+        let body = rolling_update_body(&zonal, true, "20240101").unwrap();
+        assert_eq!(body["updatePolicy"]["type"], "PROACTIVE");
+        assert_eq!(body["updatePolicy"]["minimalAction"], "RESTART");
+        assert_eq!(body["updatePolicy"]["maxSurge"]["fixed"], 0);
+        assert_eq!(body["updatePolicy"]["maxUnavailable"]["fixed"], 1);
+        assert_eq!(body["versions"][0]["name"], "0-20240101");
+
+        let regional = json!({
+            "instanceTemplate": "global/instanceTemplates/web-v2",
+            "distributionPolicy": { "zones": [{ "zone": "a" }, { "zone": "b" }, { "zone": "c" }] },
+        });
+        let body = rolling_update_body(&regional, false, "20240101").unwrap();
+        assert_eq!(body["updatePolicy"]["minimalAction"], "REPLACE");
+        assert_eq!(body["updatePolicy"]["maxSurge"]["fixed"], 3);
+        assert_eq!(body["updatePolicy"]["maxUnavailable"]["fixed"], 3);
+        assert_eq!(
+            body["versions"][0]["instanceTemplate"],
+            "global/instanceTemplates/web-v2"
+        );
+        assert!(rolling_update_body(&json!({}), true, "x").is_err());
+    }
+
+    #[test]
+    fn test_filter_disks_by_user() {
+        let response = json!({"items": [
+            {"name": "boot", "users": ["https://www.googleapis.com/compute/v1/projects/p/zones/z/instances/web"]},
+            {"name": "other", "users": ["https://www.googleapis.com/compute/v1/projects/p/zones/z/instances/web-2"]},
+            {"name": "free"},
+        ]});
+        let result = filter_disks_by_user(response, "web");
+        let names: Vec<&str> = result["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|d| d["name"].as_str())
+            .collect();
+        assert_eq!(names, vec!["boot"]);
+    }
+
+    #[test]
+    fn test_enrich_objects_folders() {
+        let response = json!({
+            "prefixes": ["logs/2024/", "logs/old/"],
+            "items": [
+                { "name": "logs/", "size": "0" },
+                { "name": "logs/app.log", "contentType": "text/plain" }
+            ]
+        });
+        let items = enrich_objects(response, "logs/", true, false)["items"].clone();
+        let items = items.as_array().unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0]["name"], "logs/2024/");
+        assert_eq!(items[0]["name_display"], "2024/");
+        assert_eq!(items[0]["folder"], true);
+        assert_eq!(items[2]["name_display"], "app.log");
+        assert_eq!(items[2]["type_display"], "text/plain");
+
+        // Recursive listings have no prefixes and keep every object
+        let flat = enrich_objects(
+            json!({ "items": [{ "name": "logs/" }] }),
+            "logs/",
+            false,
+            false,
+        );
+        assert_eq!(flat["items"].as_array().unwrap().len(), 1);
+
+        // A leaf folder has no `prefixes`, its placeholder is still dropped
+        let leaf = json!({ "items": [
+            { "name": "logs/old/", "size": "0" },
+            { "name": "logs/old/a.log" }
+        ]});
+        let leaf = enrich_objects(leaf, "logs/old/", true, false);
+        let leaf = leaf["items"].as_array().unwrap();
+        assert_eq!(leaf.len(), 1);
+        assert_eq!(leaf[0]["name_display"], "a.log");
+
+        // The versions of one object: its name is a prefix, not a folder
+        let object = json!({ "prefixes": ["data/"], "items": [{ "name": "data" }] });
+        let object = enrich_objects(object, "data", true, true);
+        assert_eq!(object["items"][1]["name"], "data");
+
+        // Deleted objects of a versions listing
+        let versions = json!({ "items": [
+            { "name": "gone.txt", "generation": "1", "timeDeleted": "t", "contentType": "text/plain" }
+        ]});
+        let versions = enrich_objects(versions, "", true, true);
+        assert_eq!(versions["items"][0]["type_display"], "Deleted");
+    }
+
+    #[test]
+    fn test_object_versions_listing_keeps_generations() {
+        let response = json!({ "items": [
+            { "name": "app.log", "generation": "300" },
+            { "name": "app.log", "generation": "200", "timeDeleted": "t2" },
+            { "name": "app.log", "generation": "100", "timeDeleted": "t1" },
+        ]});
+        let mut view = crate::versions::VersionsView::new("logs", "app.log");
+        let params = view.list_params();
+
+        let listed = enrich_object_listing("list_object_versions", response.clone(), &params);
+        view.set_versions(listed["items"].as_array().unwrap().clone());
+        assert_eq!(view.versions.len(), 3);
+        assert_eq!(view.noncurrent_count(), 2);
+
+        // The object browser shows the object once
+        let browsed = enrich_object_listing("list_objects", response, &params);
+        assert_eq!(browsed["items"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_enrich_routes() {
+        let response = json!({"items": [
+            {"name": "default-route", "nextHopGateway": "https://www.googleapis.com/compute/v1/projects/p/global/gateways/default-internet-gateway"},
+            {"name": "to-proxy", "nextHopIp": "10.0.0.5", "tags": ["egress", "web"]},
+        ]});
+        let response = enrich_routes(response);
+        assert_eq!(
+            response["items"][0]["next_hop_display"],
+            "gateway: default-internet-gateway"
+        );
+        assert_eq!(response["items"][0]["tags_display"], "-");
+        assert_eq!(response["items"][1]["next_hop_display"], "ip: 10.0.0.5");
+        assert_eq!(response["items"][1]["tags_display"], "egress,web");
+    }
+
+    #[test]
+    fn test_router_status_rows() {
+        let response = json!({"result": {
+            "bgpPeerStatus": [{
+                "name": "peer-1", "ipAddress": "169.254.0.1", "peerIpAddress": "169.254.0.2",
+                "status": "UP", "state": "Established", "numLearnedRoutes": 12, "uptime": "3 days",
+            }],
+            "natStatus": [{
+                "name": "egress", "autoAllocatedNatIps": ["34.1.1.1"], "minExtraNatIpsNeeded": 2,
+                "numVmEndpointsWithNatMappings": 40,
+            }],
+        }});
+        let rows = router_status_rows(&response);
+        let rows = rows["items"].as_array().unwrap();
+        assert_eq!(rows[0]["state_display"], "Established");
+        assert_eq!(rows[0]["count_display"], "12 routes");
+        assert_eq!(rows[1]["kind"], "NAT");
+        assert_eq!(rows[1]["state_display"], "NEEDS_IPS");
+        assert_eq!(rows[1]["count_display"], "40 VMs, 2 IPs short");
+        assert_eq!(rows[1]["address_display"], "34.1.1.1");
+    }
+
+    #[test]
+    fn test_enrich_addresses() {
+        let response = json!({"items": [
+            {"name": "leaked", "status": "RESERVED"},
+            {"name": "lb", "users": [
+                "https://www.googleapis.com/compute/v1/projects/p/regions/r/forwardingRules/fr-1",
+                "https://www.googleapis.com/compute/v1/projects/p/regions/r/forwardingRules/fr-2",
+            ]},
+        ]});
+        let response = enrich_addresses(response);
+        assert_eq!(response["items"][0]["users_display"], "-");
+        assert_eq!(response["items"][1]["users_display"], "fr-1 (+1)");
+    }
+
+    #[test]
+    fn test_enrich_instance_group_managers() {
+        let response = json!({"items": [{
+            "name": "web",
+            "region": "https://www.googleapis.com/compute/v1/projects/p/regions/us-central1",
+            "instanceTemplate": "https://www.googleapis.com/compute/v1/projects/p/global/instanceTemplates/web-v2",
+            "status": {"isStable": false},
+            "currentActions": {"none": 2, "creating": 1, "restarting": 0},
+        }]});
+        let group = &enrich_instance_group_managers(response)["items"][0];
+        assert_eq!(group["location_short"], "us-central1");
+        assert_eq!(group["scope_display"], "Regional");
+        assert_eq!(group["template_short"], "web-v2");
+        assert_eq!(group["stable_display"], "Updating");
+        assert_eq!(group["actions_display"], "1 creating");
+    }
+
+    #[test]
+    fn test_enrich_managed_instances() {
+        let manager =
+            "https://www.googleapis.com/compute/v1/projects/p/regions/r/instanceGroupManagers/web";
+        let response = json!({"managedInstances": [{
+            "instance": "https://www.googleapis.com/compute/v1/projects/p/zones/r-a/instances/web-x1",
+            "currentAction": "NONE",
+            "instanceHealth": [{"detailedHealthState": "HEALTHY"}],
+        }]});
+        let instance = &enrich_managed_instances(response, manager)["items"][0];
+        assert_eq!(instance["name"], "web-x1");
+        assert_eq!(
+            instance["zone"],
+            "https://www.googleapis.com/compute/v1/projects/p/zones/r-a"
+        );
+        assert_eq!(instance["health_display"], "HEALTHY");
+        assert_eq!(instance["instanceGroupManager"], manager);
+    }
+
+    #[test]
+    fn test_instance_template_image() {
+        assert_eq!(
+            image_display("projects/debian-cloud/global/images/family/debian-12"),
+            "family/debian-12"
+        );
+        assert_eq!(
+            image_display(
+                "https://www.googleapis.com/compute/v1/projects/p/global/images/custom-1"
+            ),
+            "custom-1"
+        );
+        assert!(compute_link("https://evil.example.com/compute/v1/x").is_err());
+    }
+
+    #[test]
+    fn test_describe_schedule() {
+        let weekly = json!({"weeklySchedule": {"dayOfWeeks": [
+            {"day": "MONDAY", "startTime": "01:00"},
+            {"day": "FRIDAY", "startTime": "02:00"},
+        ]}});
+        assert_eq!(
+            describe_schedule(Some(&weekly)),
+            "weekly MON 01:00, FRI 02:00"
+        );
+        let hourly = json!({"hourlySchedule": {"hoursInCycle": 4, "startTime": "00:00"}});
+        assert_eq!(describe_schedule(Some(&hourly)), "every 4h from 00:00");
+        assert_eq!(describe_schedule(None), "-");
+    }
+}
@@ -0,0 +1,329 @@
+//! Serial Port Output
+//!
+//! State of the serial output viewer. Output is read with
+//! `instances.getSerialPortOutput` from a byte offset: each response carries
+//! the offset it actually started at (later than requested when older output
+//! was dropped from the instance's buffer) and the `next` offset to poll from
+//! in follow mode.
+
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+/// Serial ports of an instance (port 1 is the console)
+pub const SERIAL_PORTS: std::ops::RangeInclusive<u8> = 1..=4;
+
+/// Interval between polls in follow mode
+pub const FOLLOW_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Lines kept in the viewer; older lines are dropped in long follow sessions
+const MAX_LINES: usize = 50_000;
+
+/// Search within the serial output
+#[derive(Debug, Clone, Default)]
+pub struct SerialSearch {
+    /// Whether the search query is being typed
+    pub input_active: bool,
+    /// Current query (case-insensitive)
+    pub query: String,
+    /// Indices of matching lines
+    pub matches: Vec<usize>,
+    /// Index of the current match
+    pub current: usize,
+}
+
+/// Serial output viewer state
+#[derive(Debug, Clone)]
+pub struct SerialState {
+    pub instance: String,
+    pub zone: String,
+    pub port: u8,
+    /// Output split into lines; the last one may still be incomplete
+    pub lines: Vec<String>,
+    /// Offset to read from next
+    pub next: Option<u64>,
+    /// Bytes lost because they were dropped from the buffer before being read
+    pub skipped: u64,
+    /// Poll for new output and stay at the bottom
+    pub follow: bool,
+    /// First visible line
+    pub scroll: usize,
+    /// Height of the viewport at the last render (for paging and follow)
+    pub viewport_height: usize,
+    pub search: SerialSearch,
+    pub error: Option<String>,
+    pub last_poll: Instant,
+}
+
+impl SerialState {
+    pub fn new(instance: &str, zone: &str, port: u8) -> Self {
+        Self {
+            instance: instance.to_string(),
+            zone: zone.to_string(),
+            port,
+            lines: Vec::new(),
+            next: None,
+            skipped: 0,
+            follow: false,
+            scroll: 0,
+            viewport_height: 1,
+            search: SerialSearch::default(),
+            error: None,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Start over on another port
+    pub fn switch_port(&mut self, port: u8) {
+        let follow = self.follow;
+        *self = Self::new(&self.instance, &self.zone, port);
+        self.follow = follow;
+    }
+
+    /// Offset of the next request (0 for the first one)
+    pub fn start_offset(&self) -> u64 {
+        self.next.unwrap_or(0)
+    }
+
+    /// Append a `getSerialPortOutput` response requested from `requested`
+    pub fn append_response(&mut self, requested: u64, response: &Value) {
+        let offset = |key: &str| {
+            response.get(key).and_then(|v| match v {
+                Value::String(s) => s.parse::<u64>().ok(),
+                other => other.as_u64(),
+            })
+        };
+        let start = offset("start").unwrap_or(requested);
+        if self.next.is_some() && start > requested {
+            self.skipped += start - requested;
+        }
+        if let Some(contents) = response.get("contents").and_then(|v| v.as_str()) {
+            self.append(contents);
+        }
+        self.next = offset("next").or(self.next);
+        self.error = None;
+    }
+
+    /// Append raw output, continuing the incomplete last line
+    pub fn append(&mut self, contents: &str) {
+        if contents.is_empty() {
+            return;
+        }
+        let cleaned = clean_output(contents);
+        let mut parts = cleaned.split('\n');
+        if let Some(first) = parts.next() {
+            match self.lines.last_mut() {
+                Some(last) => last.push_str(first),
+                None => self.lines.push(first.to_string()),
+            }
+        }
+        self.lines.extend(parts.map(String::from));
+
+        if self.lines.len() > MAX_LINES {
+            let excess = self.lines.len() - MAX_LINES;
+            self.lines.drain(..excess);
+            self.scroll = self.scroll.saturating_sub(excess);
+        }
+        if !self.search.query.is_empty() {
+            self.update_search();
+        }
+        if self.follow {
+            self.scroll_to_bottom();
+        }
+    }
+
+    /// Whether a follow-mode poll is due
+    pub fn poll_due(&self) -> bool {
+        self.follow && self.last_poll.elapsed() >= FOLLOW_INTERVAL
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.viewport_height)
+    }
+
+    pub fn scroll_by(&mut self, delta: isize) {
+        let scroll = (self.scroll as isize + delta).clamp(0, self.max_scroll() as isize);
+        self.scroll = scroll as usize;
+        // Scrolling up leaves follow mode so the view stops jumping
+        if delta < 0 {
+            self.follow = false;
+        }
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+        self.follow = false;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+
+    pub fn toggle_follow(&mut self) {
+        self.follow = !self.follow;
+        if self.follow {
+            self.scroll_to_bottom();
+        }
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search = SerialSearch::default();
+    }
+
+    /// Recompute the matching lines for the current query
+    pub fn update_search(&mut self) {
+        let query = self.search.query.to_lowercase();
+        self.search.matches = if query.is_empty() {
+            Vec::new()
+        } else {
+            self.lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| line.to_lowercase().contains(&query))
+                .map(|(i, _)| i)
+                .collect()
+        };
+        if self.search.current >= self.search.matches.len() {
+            self.search.current = 0;
+        }
+    }
+
+    /// Jump to the first match at or after the visible area
+    pub fn jump_to_first_match(&mut self) {
+        if let Some(pos) = self.search.matches.iter().position(|&i| i >= self.scroll) {
+            self.search.current = pos;
+        }
+        self.show_current_match();
+    }
+
+    /// Jump to the next (or previous) match, wrapping around
+    pub fn jump_to_match(&mut self, forward: bool) {
+        let count = self.search.matches.len();
+        if count == 0 {
+            return;
+        }
+        self.search.current = if forward {
+            (self.search.current + 1) % count
+        } else {
+            (self.search.current + count - 1) % count
+        };
+        self.show_current_match();
+    }
+
+    fn show_current_match(&mut self) {
+        if let Some(&line) = self.search.matches.get(self.search.current) {
+            self.follow = false;
+            let half = self.viewport_height / 2;
+            self.scroll = line.saturating_sub(half).min(self.max_scroll());
+        }
+    }
+
+    /// Default file name when saving the output
+    pub fn file_name(&self, timestamp: &str) -> String {
+        format!(
+            "{}-serial-port{}-{}.log",
+            self.instance, self.port, timestamp
+        )
+    }
+
+    /// Output as saved to a file
+    pub fn text(&self) -> String {
+        let mut text = self.lines.join("\n");
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Drop ANSI escape sequences and control characters (boot output is full of
+/// color codes and carriage returns)
+pub fn clean_output(contents: &str) -> String {
+    let mut out = String::with_capacity(contents.len());
+    let mut chars = contents.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => {
+                // CSI sequence: ESC [ params final-byte; other escapes: one char
+                if chars.peek() == Some(&'[') {
+                    chars.next();
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                } else {
+                    chars.next();
+                }
+            },
+            '\n' | '\t' => out.push(c),
+            c if c.is_control() => {},
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_clean_output() {
+        assert_eq!(
+            clean_output("\u{1b}[0;32m  OK  \u{1b}[0m Started\r\n"),
+            "  OK   Started\n"
+        );
+    }
+
+    #[test]
+    fn test_append_continues_partial_line() {
+        let mut state = SerialState::new("web-01", "us-central1-a", 1);
+        state.append_response(
+            0,
+            &json!({"contents": "boot\nloading ker", "start": "0", "next": "16"}),
+        );
+        state.append_response(
+            16,
+            &json!({"contents": "nel\nready\n", "start": "16", "next": "26"}),
+        );
+        assert_eq!(state.lines, vec!["boot", "loading kernel", "ready", ""]);
+        assert_eq!(state.start_offset(), 26);
+        assert_eq!(state.skipped, 0);
+        assert_eq!(state.text(), "boot\nloading kernel\nready\n");
+    }
+
+    #[test]
+    fn test_dropped_output_is_counted() {
+        let mut state = SerialState::new("web-01", "us-central1-a", 1);
+        // The first read starts wherever the buffer begins
+        state.append_response(
+            0,
+            &json!({"contents": "a\n", "start": "5000", "next": "5002"}),
+        );
+        assert_eq!(state.skipped, 0);
+        state.append_response(
+            5002,
+            &json!({"contents": "b\n", "start": "9000", "next": "9002"}),
+        );
+        assert_eq!(state.skipped, 3998);
+    }
+
+    #[test]
+    fn test_search_and_follow() {
+        let mut state = SerialState::new("web-01", "us-central1-a", 1);
+        state.viewport_height = 2;
+        state.append("error: disk\nok\nok\nERROR: net\nok\n");
+        state.search.query = "error".to_string();
+        state.update_search();
+        assert_eq!(state.search.matches, vec![0, 3]);
+        state.jump_to_match(true);
+        assert_eq!(state.search.current, 1);
+        assert_eq!(state.scroll, 2);
+
+        state.toggle_follow();
+        assert_eq!(state.scroll, 4);
+        state.scroll_by(-1);
+        assert!(!state.follow);
+    }
+}
@@ -6,13 +6,18 @@
 //!
 //! - SSH to VM instances using `gcloud compute ssh`
 //! - IAP tunnel support for instances without external IPs
+//! - Running a command on several instances at once ([`run`])
+//! - File copies with `gcloud compute scp`
 //! - Serial console access for debugging
 //! - Browser launch for GCP Console
 //!
 //! # Security
 //!
 //! All SSH arguments are validated against a whitelist to prevent
-//! command injection attacks. See [`validate_ssh_extra_args`] for details.
+//! command injection attacks. See [`validate_ssh_extra_args`] for details,
+//! and [`validate_scp_path`] for the paths given to scp.
+
+pub mod run;
 
 use anyhow::{anyhow, Context, Result};
 use std::process::{Command, Stdio};
@@ -171,6 +176,36 @@ pub fn validate_gcp_resource_name(name: &str, resource_type: &str) -> Result<()>
     Ok(())
 }
 
+/// Validate a zone name (lenient: it carries the region prefix)
+pub fn validate_zone(zone: &str) -> Result<()> {
+    if zone.is_empty() || zone.len() > 63 {
+        return Err(anyhow!("Invalid zone name"));
+    }
+    Ok(())
+}
+
+/// Validate a project ID
+pub fn validate_project(project: &str) -> Result<()> {
+    if project.is_empty() || project.len() > 63 {
+        return Err(anyhow!("Invalid project name"));
+    }
+    Ok(())
+}
+
+/// Write a stub `gcloud` running `script` into a fresh directory and return
+/// the directory (tests of the gcloud wrappers)
+#[cfg(all(test, unix))]
+pub fn stub_gcloud(script: &str) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("tgcp-gcloud-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("gcloud");
+    std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    dir
+}
+
 /// SSH connection options
 #[derive(Debug, Clone)]
 pub struct SshOptions {
@@ -224,14 +259,8 @@ pub fn ssh_to_instance(opts: &SshOptions) -> ShellResult {
         return ShellResult::Error(format!("Invalid instance name: {}", e));
     }
 
-    // Zone validation is more lenient (contains region prefix)
-    if opts.zone.is_empty() || opts.zone.len() > 63 {
-        return ShellResult::Error("Invalid zone name".to_string());
-    }
-
-    // Project validation
-    if opts.project.is_empty() || opts.project.len() > 63 {
-        return ShellResult::Error("Invalid project name".to_string());
+    if let Err(e) = validate_zone(&opts.zone).and_then(|_| validate_project(&opts.project)) {
+        return ShellResult::Error(e.to_string());
     }
 
     // Security: Validate extra_args against whitelist
@@ -267,6 +296,123 @@ pub fn ssh_to_instance(opts: &SshOptions) -> ShellResult {
     execute_command("gcloud", &args)
 }
 
+/// Characters refused in scp paths: the remote path is expanded by the
+/// remote shell, so anything that could chain or substitute commands is out
+const DANGEROUS_PATH_CHARS: &[char] = &[
+    ';', '&', '|', '$', '`', '<', '>', '(', ')', '{', '}', '\\', '"', '\'', '!',
+];
+
+/// Validate a path given to `gcloud compute scp`
+///
+/// Security: paths must not look like flags, must not contain control
+/// characters or shell metacharacters, and a local path must not contain ':'
+/// (gcloud would read it as `instance:path`). Globs (`*`, `?`, `[]`) and `~`
+/// are allowed.
+pub fn validate_scp_path(path: &str, remote: bool) -> Result<()> {
+    let kind = if remote { "Remote" } else { "Local" };
+    if path.trim().is_empty() {
+        return Err(anyhow!("{} path cannot be empty", kind));
+    }
+    if path.len() > 4096 {
+        return Err(anyhow!("{} path too long", kind));
+    }
+    if path.starts_with('-') {
+        return Err(anyhow!("{} path '{}' must not start with '-'", kind, path));
+    }
+    if path.chars().any(|c| c.is_control()) {
+        return Err(anyhow!("{} path contains control characters", kind));
+    }
+    if let Some(c) = path.chars().find(|c| DANGEROUS_PATH_CHARS.contains(c)) {
+        return Err(anyhow!(
+            "{} path '{}' contains '{}', which is not allowed for security reasons",
+            kind,
+            path,
+            c
+        ));
+    }
+    if !remote && path.contains(':') {
+        return Err(anyhow!("Local path '{}' must not contain ':'", path));
+    }
+    Ok(())
+}
+
+/// Direction of an scp copy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScpDirection {
+    FromInstance,
+    ToInstance,
+}
+
+/// scp options
+#[derive(Debug, Clone)]
+pub struct ScpOptions {
+    pub instance: String,
+    pub zone: String,
+    pub project: String,
+    pub direction: ScpDirection,
+    /// Path on the instance
+    pub remote_path: String,
+    /// Path on this machine
+    pub local_path: String,
+    /// Copy directories recursively
+    pub recurse: bool,
+    /// Use IAP tunneling
+    pub use_iap: bool,
+}
+
+impl ScpOptions {
+    /// Arguments of `gcloud compute scp`, after validating all inputs
+    pub fn args(&self) -> Result<Vec<String>> {
+        validate_gcp_resource_name(&self.instance, "Instance")?;
+        validate_zone(&self.zone)?;
+        validate_project(&self.project)?;
+        validate_scp_path(&self.remote_path, true)?;
+        validate_scp_path(&self.local_path, false)?;
+
+        let mut args = vec!["compute".to_string(), "scp".to_string()];
+        if self.recurse {
+            args.push("--recurse".to_string());
+        }
+        if self.use_iap {
+            args.push("--tunnel-through-iap".to_string());
+        }
+        args.extend([
+            "--zone".to_string(),
+            self.zone.clone(),
+            "--project".to_string(),
+            self.project.clone(),
+        ]);
+        let remote = format!("{}:{}", self.instance, self.remote_path);
+        match self.direction {
+            ScpDirection::FromInstance => args.extend([remote, self.local_path.clone()]),
+            ScpDirection::ToInstance => args.extend([self.local_path.clone(), remote]),
+        }
+        Ok(args)
+    }
+}
+
+/// Copy files from or to a GCE instance with `gcloud compute scp`
+///
+/// Like [`ssh_to_instance`], meant to run with the TUI suspended so the
+/// progress output is visible.
+pub fn scp_instance(opts: &ScpOptions) -> ShellResult {
+    let args = match opts.args() {
+        Ok(args) => args,
+        Err(e) => return ShellResult::Error(format!("Security validation failed: {}", e)),
+    };
+
+    tracing::info!(
+        "Executing scp: instance={}, zone={}, project={}, direction={:?}, iap={}",
+        opts.instance,
+        opts.zone,
+        opts.project,
+        opts.direction,
+        opts.use_iap
+    );
+
+    execute_command("gcloud", &args)
+}
+
 /// Open URL in browser (for console links)
 pub fn open_browser(url: &str) -> ShellResult {
     let (cmd, args): (&str, Vec<&str>) = if cfg!(target_os = "macos") {
@@ -620,4 +766,67 @@ mod tests {
         let max_name = "a".repeat(63);
         assert!(validate_gcp_resource_name(&max_name, "Instance").is_ok());
     }
+
+    #[test]
+    fn test_zone_and_project() {
+        assert!(validate_zone("us-central1-a").is_ok());
+        assert!(validate_zone("").is_err());
+        assert!(validate_zone(&"a".repeat(64)).is_err());
+        assert!(validate_project("my-project").is_ok());
+        assert!(validate_project("").is_err());
+    }
+
+    #[test]
+    fn test_scp_paths() {
+        assert!(validate_scp_path("/var/log/syslog", true).is_ok());
+        assert!(validate_scp_path("~/logs/*.log", true).is_ok());
+        assert!(validate_scp_path("./backup dir/app.conf", false).is_ok());
+        assert!(validate_scp_path("", true).is_err());
+        assert!(validate_scp_path("-oProxyCommand=evil", true).is_err());
+        assert!(validate_scp_path("/tmp/x; rm -rf ~", true).is_err());
+        assert!(validate_scp_path("/tmp/$(id)", true).is_err());
+        assert!(validate_scp_path("/tmp/`id`", true).is_err());
+        assert!(validate_scp_path("/tmp/a\nb", true).is_err());
+        assert!(validate_scp_path("other-vm:/etc/passwd", false).is_err());
+    }
+
+    #[test]
+    fn test_scp_args() {
+        let mut opts = ScpOptions {
+            instance: "web-1".to_string(),
+            zone: "us-central1-a".to_string(),
+            project: "my-project".to_string(),
+            direction: ScpDirection::FromInstance,
+            remote_path: "/var/log/app.log".to_string(),
+            local_path: ".".to_string(),
+            recurse: false,
+            use_iap: true,
+        };
+        let args = opts.args().unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "compute",
+                "scp",
+                "--tunnel-through-iap",
+                "--zone",
+                "us-central1-a",
+                "--project",
+                "my-project",
+                "web-1:/var/log/app.log",
+                ".",
+            ]
+        );
+
+        opts.direction = ScpDirection::ToInstance;
+        opts.local_path = "app.conf".to_string();
+        let args = opts.args().unwrap();
+        assert_eq!(
+            &args[args.len() - 2..],
+            ["app.conf", "web-1:/var/log/app.log"]
+        );
+
+        opts.instance = "web;id".to_string();
+        assert!(opts.args().is_err());
+    }
 }
@@ -0,0 +1,492 @@
+//! Run a Command on Several Instances
+//!
+//! Runs `gcloud compute ssh <vm> --command=<cmd>` on each target, a limited
+//! number at a time, streaming each host's output into its own buffer. The
+//! command is passed to gcloud as a single argument: it is interpreted by the
+//! remote shell only, never by a local one.
+
+use super::{validate_gcp_resource_name, validate_project, validate_ssh_extra_args, validate_zone};
+use anyhow::{anyhow, Result};
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinHandle;
+
+/// Hosts running the command at the same time by default
+pub const DEFAULT_PARALLELISM: usize = 4;
+
+/// Upper bound of the parallelism asked for in the form
+pub const MAX_PARALLELISM: usize = 32;
+
+/// Longest accepted command
+const MAX_COMMAND_LEN: usize = 4096;
+
+/// Lines kept per host
+const MAX_LINES: usize = 10_000;
+
+/// An instance to run the command on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunTarget {
+    pub instance: String,
+    pub zone: String,
+}
+
+/// Progress of the command on one host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostStatus {
+    /// Waiting for a free slot
+    Queued,
+    Running,
+    Exited(i32),
+    /// gcloud could not be started, or was killed by a signal
+    Failed(String),
+    Cancelled,
+}
+
+impl HostStatus {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, HostStatus::Queued | HostStatus::Running)
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            HostStatus::Queued => "queued".to_string(),
+            HostStatus::Running => "running".to_string(),
+            HostStatus::Exited(code) => format!("exit {}", code),
+            HostStatus::Failed(_) => "failed".to_string(),
+            HostStatus::Cancelled => "cancelled".to_string(),
+        }
+    }
+}
+
+/// Output of one host
+#[derive(Debug, Clone)]
+pub struct HostOutput {
+    pub target: RunTarget,
+    pub status: HostStatus,
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug)]
+enum RunEvent {
+    Started(usize),
+    Line(usize, String),
+    Exited(usize, HostStatus),
+}
+
+/// Check the command typed in the run form
+pub fn validate_command(command: &str) -> Result<()> {
+    if command.trim().is_empty() {
+        return Err(anyhow!("Command cannot be empty"));
+    }
+    if command.len() > MAX_COMMAND_LEN {
+        return Err(anyhow!(
+            "Command too long (max {} characters)",
+            MAX_COMMAND_LEN
+        ));
+    }
+    if command.contains('\0') {
+        return Err(anyhow!("Command contains a NUL character"));
+    }
+    Ok(())
+}
+
+/// Check the number of hosts at a time typed in the run form
+pub fn validate_parallelism(value: &str) -> Result<usize> {
+    match value.trim().parse::<usize>() {
+        Ok(n) if (1..=MAX_PARALLELISM).contains(&n) => Ok(n),
+        _ => Err(anyhow!("Must be between 1 and {}", MAX_PARALLELISM)),
+    }
+}
+
+/// Arguments of `gcloud compute ssh` running `command` on `target`.
+/// Security: the command is a single `--command=` argument, so it can
+/// neither be split locally nor be taken for another flag.
+pub fn ssh_command_args(
+    target: &RunTarget,
+    project: &str,
+    command: &str,
+    use_iap: bool,
+    extra_args: &[String],
+) -> Result<Vec<String>> {
+    validate_gcp_resource_name(&target.instance, "Instance")?;
+    validate_zone(&target.zone)?;
+    validate_project(project)?;
+    validate_command(command)?;
+    validate_ssh_extra_args(extra_args)?;
+
+    let mut args = vec![
+        "compute".to_string(),
+        "ssh".to_string(),
+        target.instance.clone(),
+        "--zone".to_string(),
+        target.zone.clone(),
+        "--project".to_string(),
+        project.to_string(),
+        // Never prompt: stdin is not a terminal
+        "--quiet".to_string(),
+    ];
+    if use_iap {
+        args.push("--tunnel-through-iap".to_string());
+    }
+    args.push(format!("--command={}", command));
+    args.extend(extra_args.iter().cloned());
+    Ok(args)
+}
+
+/// A command running on several hosts. Dropping it kills the processes
+/// still running.
+#[derive(Debug)]
+pub struct RunState {
+    pub command: String,
+    pub use_iap: bool,
+    pub parallelism: usize,
+    pub hosts: Vec<HostOutput>,
+    /// Host whose output is shown
+    pub selected: usize,
+    /// First visible line of the selected host's output
+    pub scroll: usize,
+    /// Stay at the bottom of the output as lines arrive
+    pub follow: bool,
+    /// Height of the output pane at the last render (for paging and follow)
+    pub viewport_height: usize,
+    events: mpsc::UnboundedReceiver<RunEvent>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl RunState {
+    /// Start the command on all targets with `program` (`gcloud`). All
+    /// arguments are checked before anything is started.
+    pub fn start(
+        program: &str,
+        targets: Vec<RunTarget>,
+        project: &str,
+        command: &str,
+        use_iap: bool,
+        extra_args: &[String],
+        parallelism: usize,
+    ) -> Result<Self> {
+        if targets.is_empty() {
+            return Err(anyhow!("No instances to run the command on"));
+        }
+        let all_args = targets
+            .iter()
+            .map(|t| ssh_command_args(t, project, command, use_iap, extra_args))
+            .collect::<Result<Vec<_>>>()?;
+
+        tracing::info!(
+            "Running command on {} instances (parallelism={}, iap={})",
+            targets.len(),
+            parallelism,
+            use_iap
+        );
+        let parallelism = parallelism.clamp(1, MAX_PARALLELISM);
+        let slots = Arc::new(Semaphore::new(parallelism));
+        let (tx, events) = mpsc::unbounded_channel();
+        let tasks = all_args
+            .into_iter()
+            .enumerate()
+            .map(|(index, args)| {
+                tokio::spawn(run_host(
+                    index,
+                    program.to_string(),
+                    args,
+                    Arc::clone(&slots),
+                    tx.clone(),
+                ))
+            })
+            .collect();
+
+        Ok(Self {
+            command: command.to_string(),
+            use_iap,
+            parallelism,
+            hosts: targets
+                .into_iter()
+                .map(|target| HostOutput {
+                    target,
+                    status: HostStatus::Queued,
+                    lines: Vec::new(),
+                })
+                .collect(),
+            selected: 0,
+            scroll: 0,
+            follow: true,
+            viewport_height: 1,
+            events,
+            tasks,
+        })
+    }
+
+    /// Apply the output received since the last poll
+    pub fn poll(&mut self) {
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                RunEvent::Started(index) => {
+                    if self.hosts[index].status == HostStatus::Queued {
+                        self.hosts[index].status = HostStatus::Running;
+                    }
+                },
+                RunEvent::Line(index, line) => {
+                    let lines = &mut self.hosts[index].lines;
+                    lines.push(line);
+                    if lines.len() > MAX_LINES {
+                        lines.drain(..lines.len() - MAX_LINES);
+                    }
+                },
+                RunEvent::Exited(index, status) => {
+                    if self.hosts[index].status != HostStatus::Cancelled {
+                        self.hosts[index].status = status;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Kill the processes still running and drop the queued hosts
+    pub fn cancel(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+        for host in &mut self.hosts {
+            if !host.status.is_finished() {
+                host.status = HostStatus::Cancelled;
+            }
+        }
+    }
+
+    pub fn finished_count(&self) -> usize {
+        self.hosts.iter().filter(|h| h.status.is_finished()).count()
+    }
+
+    /// Hosts that failed or exited with a non-zero code
+    pub fn failed_count(&self) -> usize {
+        self.hosts
+            .iter()
+            .filter(|h| match h.status {
+                HostStatus::Exited(code) => code != 0,
+                HostStatus::Failed(_) => true,
+                _ => false,
+            })
+            .count()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.finished_count() == self.hosts.len()
+    }
+
+    pub fn selected_host(&self) -> Option<&HostOutput> {
+        self.hosts.get(self.selected)
+    }
+
+    pub fn select_by(&mut self, delta: isize) {
+        let last = self.hosts.len().saturating_sub(1) as isize;
+        self.selected = (self.selected as isize)
+            .saturating_add(delta)
+            .clamp(0, last) as usize;
+        self.scroll = 0;
+        self.follow = true;
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.selected_host()
+            .map_or(0, |h| h.lines.len().saturating_sub(self.viewport_height))
+    }
+
+    pub fn scroll_by(&mut self, delta: isize) {
+        let max = self.max_scroll() as isize;
+        self.scroll = (self.scroll as isize).saturating_add(delta).clamp(0, max) as usize;
+        self.follow = self.scroll as isize >= max;
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+        self.follow = false;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+        self.follow = true;
+    }
+
+    /// Default file name when saving the output
+    pub fn file_name(&self, timestamp: &str) -> String {
+        format!("run-{}.log", timestamp)
+    }
+
+    /// Output of all hosts as saved to a file
+    pub fn text(&self) -> String {
+        let mut text = format!("$ {}\n", self.command);
+        for host in &self.hosts {
+            text.push_str(&format!(
+                "\n=== {} ({}): {} ===\n",
+                host.target.instance,
+                host.target.zone,
+                host.status.label()
+            ));
+            if let HostStatus::Failed(ref reason) = host.status {
+                text.push_str(reason);
+                text.push('\n');
+            }
+            for line in &host.lines {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+        text
+    }
+}
+
+impl Drop for RunState {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Run the command on one host once a slot is free
+async fn run_host(
+    index: usize,
+    program: String,
+    args: Vec<String>,
+    slots: Arc<Semaphore>,
+    tx: mpsc::UnboundedSender<RunEvent>,
+) {
+    let Ok(_permit) = slots.acquire_owned().await else {
+        return;
+    };
+    let _ = tx.send(RunEvent::Started(index));
+
+    // Aborting the task drops the child, which kills it
+    let mut child = match Command::new(&program)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            let reason = format!("Failed to execute {}: {}", program, e);
+            let _ = tx.send(RunEvent::Exited(index, HostStatus::Failed(reason)));
+            return;
+        },
+    };
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    tokio::join!(
+        forward_lines(index, stdout, &tx),
+        forward_lines(index, stderr, &tx)
+    );
+
+    let status = match child.wait().await {
+        Ok(status) => match status.code() {
+            Some(code) => HostStatus::Exited(code),
+            None => HostStatus::Failed("Killed by a signal".to_string()),
+        },
+        Err(e) => HostStatus::Failed(format!("Failed to wait for process: {}", e)),
+    };
+    let _ = tx.send(RunEvent::Exited(index, status));
+}
+
+async fn forward_lines<R: AsyncRead + Unpin>(
+    index: usize,
+    stream: Option<R>,
+    tx: &mpsc::UnboundedSender<RunEvent>,
+) {
+    let Some(stream) = stream else {
+        return;
+    };
+    let mut lines = BufReader::new(stream).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let line = crate::serial::clean_output(&line);
+        if tx.send(RunEvent::Line(index, line)).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::shell;
+    use std::path::PathBuf;
+
+    /// Stub `gcloud` printing its arguments one per line, failing on the
+    /// instance named "bad"
+    fn stub_program() -> PathBuf {
+        shell::stub_gcloud(
+            "for arg in \"$@\"; do echo \"arg:$arg\"; done\n\
+             echo \"on stderr\" >&2\n\
+             [ \"$3\" = bad ] && exit 3\n\
+             exit 0",
+        )
+        .join("gcloud")
+    }
+
+    fn target(instance: &str) -> RunTarget {
+        RunTarget {
+            instance: instance.to_string(),
+            zone: "europe-west1-b".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_command_on_hosts() {
+        let program = stub_program();
+        let command = "echo $HOME; uname -a | tee /tmp/x";
+        let mut state = RunState::start(
+            program.to_str().unwrap(),
+            vec![target("web-1"), target("bad"), target("web-2")],
+            "my-project",
+            command,
+            true,
+            &[],
+            2,
+        )
+        .unwrap();
+
+        for _ in 0..200 {
+            state.poll();
+            if state.is_done() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(state.is_done());
+        assert_eq!(state.hosts[0].status, HostStatus::Exited(0));
+        assert_eq!(state.hosts[1].status, HostStatus::Exited(3));
+        assert_eq!(state.failed_count(), 1);
+
+        // The command reaches gcloud untouched, as a single argument
+        let lines = &state.hosts[0].lines;
+        assert!(lines.contains(&format!("arg:--command={}", command)));
+        assert!(lines.contains(&"arg:--tunnel-through-iap".to_string()));
+        assert!(lines.contains(&"on stderr".to_string()));
+        assert!(state
+            .text()
+            .contains("=== bad (europe-west1-b): exit 3 ==="));
+        let _ = std::fs::remove_dir_all(program.parent().unwrap());
+    }
+
+    #[test]
+    fn test_run_command_validation() {
+        assert!(validate_command("  ").is_err());
+        assert!(validate_command("a\0b").is_err());
+        assert!(validate_command("systemctl status myapp").is_ok());
+        assert_eq!(validate_parallelism("8").unwrap(), 8);
+        assert!(validate_parallelism("0").is_err());
+        assert!(validate_parallelism("33").is_err());
+        assert!(ssh_command_args(&target("web;rm"), "p", "ls", false, &[]).is_err());
+        assert!(ssh_command_args(&target("web"), "p", "ls", false, &["--".to_string()]).is_err());
+
+        let args = ssh_command_args(&target("web"), "p", "-rf /", false, &[]).unwrap();
+        assert_eq!(args.last().unwrap(), "--command=-rf /");
+        assert!(!args.contains(&"--tunnel-through-iap".to_string()));
+    }
+}
@@ -0,0 +1,239 @@
+//! Cloud Storage Object Browser
+//!
+//! Objects are browsed like folders: `objects.list` is called with
+//! `delimiter=/` and the current `prefix`, and the returned `prefixes` become
+//! folder rows (see `list_objects`). The recursive mode drops the delimiter
+//! to list every object under the prefix. With deleted objects shown,
+//! `versions=true` lists noncurrent generations too, collapsed to one row
+//! per name (see `collapse_versions`). Collapsing works page by page: an
+//! object whose generations straddle a page boundary shows on both pages.
+
+use crate::resource::ResourceFilter;
+use serde_json::Value;
+
+/// Position in the objects of a bucket
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectBrowser {
+    /// Current folder, empty at the bucket root, otherwise ending with '/'
+    pub prefix: String,
+    /// List every object under the prefix instead of one level
+    pub recursive: bool,
+    /// Also list objects whose live generation was deleted
+    pub show_deleted: bool,
+}
+
+impl ObjectBrowser {
+    /// Query parameters of the listing
+    pub fn filters(&self) -> Vec<ResourceFilter> {
+        let mut filters = Vec::new();
+        if !self.prefix.is_empty() {
+            filters.push(ResourceFilter::new("prefix", vec![self.prefix.clone()]));
+        }
+        if !self.recursive {
+            filters.push(ResourceFilter::new("delimiter", vec!["/".to_string()]));
+        }
+        if self.show_deleted {
+            filters.push(ResourceFilter::new("versions", vec!["true".to_string()]));
+        }
+        filters
+    }
+
+    /// Descend into a folder row (its name is the full prefix)
+    pub fn enter(&mut self, folder: &str) {
+        self.prefix = folder.to_string();
+    }
+
+    /// Go up one folder; returns false at the bucket root
+    pub fn up(&mut self) -> bool {
+        if self.prefix.is_empty() {
+            return false;
+        }
+        self.prefix = parent_prefix(&self.prefix);
+        true
+    }
+
+    /// Breadcrumb segment for the current folder
+    pub fn crumb(&self) -> Option<String> {
+        let mut modes = Vec::new();
+        if self.recursive {
+            modes.push("recursive");
+        }
+        if self.show_deleted {
+            modes.push("with deleted");
+        }
+        if self.prefix.is_empty() && modes.is_empty() {
+            return None;
+        }
+        let modes = if modes.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", modes.join(", "))
+        };
+        Some(format!("/{}{}", self.prefix, modes))
+    }
+}
+
+/// Prefix of the folder containing `prefix` ("a/b/" → "a/", "a/" → "")
+pub fn parent_prefix(prefix: &str) -> String {
+    let trimmed = prefix.strip_suffix('/').unwrap_or(prefix);
+    match trimmed.rfind('/') {
+        Some(index) => trimmed[..=index].to_string(),
+        None => String::new(),
+    }
+}
+
+/// Whether a row of the object list is a folder
+pub fn is_folder(item: &Value) -> bool {
+    item.get("folder").and_then(|v| v.as_bool()) == Some(true)
+}
+
+/// Explanation shown instead of the delete confirmation when some of the
+/// rows to delete are folders (prefixes, not objects)
+pub fn folder_delete_refusal(items: &[&Value]) -> Option<String> {
+    let folders: Vec<&str> = items
+        .iter()
+        .filter(|item| is_folder(item))
+        .filter_map(|item| item.get("name").and_then(|v| v.as_str()))
+        .collect();
+    match folders.as_slice() {
+        [] => None,
+        [folder] if items.len() == 1 => Some(format!(
+            "'{}' is a folder: folders are object name prefixes, delete the objects inside instead",
+            folder
+        )),
+        folders => Some(format!(
+            "Folders cannot be deleted ({}): deselect them and delete the objects inside instead",
+            folders.join(", ")
+        )),
+    }
+}
+
+/// Whether a row of the object list is a deleted object (no live generation)
+pub fn is_deleted(item: &Value) -> bool {
+    item.get("deleted").and_then(|v| v.as_bool()) == Some(true)
+}
+
+/// Reduce a `versions=true` listing to one row per object name: the live
+/// generation, or the newest noncurrent one marked `deleted` when the object
+/// has no live generation
+pub fn collapse_versions(items: Vec<Value>) -> Vec<Value> {
+    let mut rows: Vec<Value> = Vec::new();
+    for item in items {
+        let name = item.get("name").and_then(|v| v.as_str());
+        let same = rows
+            .last()
+            .is_some_and(|last| last.get("name").and_then(|v| v.as_str()) == name);
+        if !same {
+            rows.push(item);
+            continue;
+        }
+        let Some(last) = rows.last_mut() else {
+            continue;
+        };
+        let last_live = crate::versions::is_live(last);
+        let replace = if crate::versions::is_live(&item) {
+            true
+        } else {
+            !last_live && crate::versions::generation(&item) > crate::versions::generation(last)
+        };
+        if replace {
+            *last = item;
+        }
+    }
+    for row in rows.iter_mut() {
+        if !crate::versions::is_live(row) {
+            if let Value::Object(ref mut map) = row {
+                map.insert("deleted".to_string(), Value::Bool(true));
+            }
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_browser_navigation() {
+        let mut browser = ObjectBrowser::default();
+        assert_eq!(browser.crumb(), None);
+        assert_eq!(browser.filters().len(), 1);
+
+        browser.enter("logs/2024/");
+        assert_eq!(browser.crumb().as_deref(), Some("/logs/2024/"));
+        let filters = browser.filters();
+        assert_eq!(filters[0].param, "prefix");
+        assert_eq!(filters[0].values, vec!["logs/2024/"]);
+
+        assert!(browser.up());
+        assert_eq!(browser.prefix, "logs/");
+        assert!(browser.up());
+        assert_eq!(browser.prefix, "");
+        assert!(!browser.up());
+
+        browser.recursive = true;
+        assert!(browser.filters().is_empty());
+        assert_eq!(browser.crumb().as_deref(), Some("/ (recursive)"));
+        browser.show_deleted = true;
+        assert_eq!(browser.filters()[0].param, "versions");
+        assert_eq!(
+            browser.crumb().as_deref(),
+            Some("/ (recursive, with deleted)")
+        );
+        assert_eq!(parent_prefix("a//"), "a/");
+    }
+
+    #[test]
+    fn test_folder_delete_refusal() {
+        use serde_json::json;
+        let folder = json!({ "name": "logs/2024/", "folder": true });
+        let object = json!({ "name": "logs/a.txt" });
+        assert!(folder_delete_refusal(&[&object]).is_none());
+        assert!(folder_delete_refusal(&[&folder])
+            .unwrap()
+            .starts_with("'logs/2024/' is a folder"));
+        // A mixed selection is refused whatever row the cursor is on
+        assert_eq!(
+            folder_delete_refusal(&[&object, &folder]).as_deref(),
+            Some(
+                "Folders cannot be deleted (logs/2024/): \
+                 deselect them and delete the objects inside instead"
+            )
+        );
+    }
+
+    #[test]
+    fn test_collapse_versions() {
+        use serde_json::json;
+        let rows = collapse_versions(vec![
+            json!({ "name": "a.txt", "generation": "1", "timeDeleted": "t" }),
+            json!({ "name": "a.txt", "generation": "2" }),
+            json!({ "name": "a.txt", "generation": "3", "timeDeleted": "t" }),
+            json!({ "name": "b.txt", "generation": "5", "timeDeleted": "t" }),
+            json!({ "name": "b.txt", "generation": "7", "timeDeleted": "t" }),
+            json!({ "name": "c.txt", "generation": "9" }),
+        ]);
+        let summary: Vec<(String, bool)> = rows
+            .iter()
+            .map(|r| {
+                (
+                    format!(
+                        "{}#{}",
+                        r["name"].as_str().unwrap(),
+                        r["generation"].as_str().unwrap()
+                    ),
+                    is_deleted(r),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("a.txt#2".to_string(), false),
+                ("b.txt#7".to_string(), true),
+                ("c.txt#9".to_string(), false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,1180 @@
+//! Cloud Storage Transfers
+//!
+//! Object downloads and uploads run as background tasks reporting their
+//! progress through a channel polled by the app, like command runs.
+//! Downloads stream `alt=media` into a `.part` file next to the destination
+//! and continue with a `Range` request after a failure (a partial file left
+//! by an earlier attempt is picked up the same way). Uploads send small files
+//! in one request and larger ones through a resumable session, in chunks.
+
+use crate::gcp::client::GcpClient;
+use crate::resource::{format_bytes, format_duration};
+use anyhow::{anyhow, Context, Result};
+use reqwest::header::{CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, LOCATION, RANGE};
+use reqwest::{Client, StatusCode};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Files larger than this are uploaded through a resumable session
+pub const RESUMABLE_THRESHOLD: u64 = 8 * 1024 * 1024;
+
+/// Size of the chunks of a resumable upload (a multiple of 256 KiB)
+pub const CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Transfers running at the same time
+pub const MAX_CONCURRENT: usize = 4;
+
+/// Attempts of a transfer before giving up on transient errors
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Whether an HTTP status is worth retrying
+fn is_transient(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Delay before the next attempt
+fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_millis(500 * u64::from(attempt))
+}
+
+/// Failure of one attempt of a transfer
+enum Failure {
+    /// Network error or retryable status: try again from where it stopped
+    Transient(anyhow::Error),
+    /// Not worth retrying (permissions, missing object, local I/O)
+    Fatal(anyhow::Error),
+}
+
+impl Failure {
+    fn transient(e: impl Into<anyhow::Error>) -> Self {
+        Self::Transient(e.into())
+    }
+
+    fn fatal(e: impl Into<anyhow::Error>) -> Self {
+        Self::Fatal(e.into())
+    }
+
+    fn status(status: StatusCode, what: &str) -> Self {
+        let error = anyhow!("{} failed: {}", what, status);
+        if is_transient(status) {
+            Self::Transient(error)
+        } else {
+            Self::Fatal(error)
+        }
+    }
+}
+
+// =============================================================================
+// URLs and paths
+// =============================================================================
+
+/// Media URL of an object below `objects_url` (the bucket's objects URL),
+/// pinned to a generation when known so a resumed download reads the same
+/// bytes
+pub fn media_url(objects_url: &str, object: &str, generation: Option<&str>) -> String {
+    let mut url = format!("{}/{}?alt=media", objects_url, urlencoding::encode(object));
+    if let Some(generation) = generation {
+        url.push_str(&format!("&generation={}", generation));
+    }
+    url
+}
+
+/// Upload URL of an object below `uploads_url` (the bucket's upload URL),
+/// `uploadType=media` or `resumable`
+pub fn upload_url(uploads_url: &str, object: &str, resumable: bool) -> String {
+    format!(
+        "{}?uploadType={}&name={}",
+        uploads_url,
+        if resumable { "resumable" } else { "media" },
+        urlencoding::encode(object)
+    )
+}
+
+/// Partial file of a download, named after the generation so a leftover of
+/// another version is never resumed
+pub fn part_path(dest: &Path, generation: Option<&str>) -> PathBuf {
+    let name = dest
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let part = match generation {
+        Some(generation) => format!("{}.{}.part", name, generation),
+        None => format!("{}.part", name),
+    };
+    dest.with_file_name(part)
+}
+
+/// Local path of a downloaded object. Several objects, or a destination that
+/// is a directory, keep their path relative to the current folder; a single
+/// object may also be saved under another file name.
+///
+/// Object names come from the bucket and may contain `..` or start with `/`:
+/// only their normal components are kept, so the file always lands below
+/// `dest`.
+pub fn download_destination(
+    dest: &str,
+    object: &str,
+    prefix: &str,
+    multiple: bool,
+) -> Result<PathBuf, String> {
+    let dest_path = Path::new(dest);
+    if !(multiple || dest.ends_with('/') || dest_path.is_dir()) {
+        return Ok(dest_path.to_path_buf());
+    }
+    let relative = object.strip_prefix(prefix).unwrap_or(object);
+    let mut safe = PathBuf::new();
+    for component in Path::new(relative).components() {
+        match component {
+            std::path::Component::Normal(part) => safe.push(part),
+            std::path::Component::CurDir => {},
+            _ => {
+                return Err(format!(
+                    "Object name {} escapes the destination directory",
+                    object
+                ))
+            },
+        }
+    }
+    if safe.as_os_str().is_empty() {
+        return Err(format!("Object name {} has no file name", object));
+    }
+    let path = dest_path.join(safe);
+    if !path.starts_with(dest_path) {
+        return Err(format!(
+            "Object name {} escapes the destination directory",
+            object
+        ));
+    }
+    Ok(path)
+}
+
+/// Local files the downloads would replace
+pub fn existing_destinations(jobs: &[TransferJob]) -> Vec<String> {
+    jobs.iter()
+        .filter(|job| job.local.exists())
+        .map(|job| job.local.display().to_string())
+        .collect()
+}
+
+/// Check a local path typed in a transfer form
+pub fn validate_local_path(path: &str) -> Result<(), String> {
+    if path.trim().is_empty() {
+        return Err("Path is required".to_string());
+    }
+    if path.chars().any(char::is_control) {
+        return Err("Path contains control characters".to_string());
+    }
+    Ok(())
+}
+
+/// Object name of an upload: the name typed, or the file name in the
+/// current folder (`prefix`)
+pub fn upload_object_name(local: &Path, name: &str, prefix: &str) -> Result<String, String> {
+    let object = match name.trim() {
+        "" => {
+            let file_name = local
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .ok_or_else(|| "The local path has no file name".to_string())?;
+            format!("{}{}", prefix, file_name)
+        },
+        name => name.trim_start_matches('/').to_string(),
+    };
+    if object.ends_with('/') {
+        return Err("Object name must not end with '/'".to_string());
+    }
+    Ok(object)
+}
+
+/// Content type of an uploaded file, from its extension
+pub fn content_type_for(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "json" => "application/json",
+        "txt" | "log" => "text/plain",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" => "application/javascript",
+        "xml" => "application/xml",
+        "yaml" | "yml" => "application/yaml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "gz" => "application/gzip",
+        "zip" => "application/zip",
+        "tar" => "application/x-tar",
+        _ => "application/octet-stream",
+    }
+}
+
+// =============================================================================
+// Progress
+// =============================================================================
+
+/// Progress of a transfer, with its throughput since it started (bytes
+/// resumed from an earlier attempt do not count)
+#[derive(Debug, Clone)]
+pub struct Progress {
+    pub done: u64,
+    pub total: Option<u64>,
+    base: u64,
+    started: Instant,
+}
+
+impl Progress {
+    pub fn new(done: u64, total: Option<u64>) -> Self {
+        Self {
+            done,
+            total,
+            base: done,
+            started: Instant::now(),
+        }
+    }
+
+    /// Bytes per second since the start
+    pub fn throughput(&self) -> f64 {
+        let secs = self.started.elapsed().as_secs_f64();
+        if secs > 0.0 {
+            self.done.saturating_sub(self.base) as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Remaining time at the current throughput
+    pub fn eta(&self) -> Option<Duration> {
+        let total = self.total?;
+        let rate = self.throughput();
+        if rate <= 0.0 {
+            return None;
+        }
+        let remaining = total.saturating_sub(self.done) as f64;
+        Some(Duration::from_secs_f64(remaining / rate))
+    }
+
+    /// "3.0 MB / 12.0 MB (25%) 1.5 MB/s ETA 6s"
+    pub fn summary(&self) -> String {
+        let mut text = match self.total {
+            Some(total) if total > 0 => format!(
+                "{} / {} ({}%)",
+                format_bytes(self.done),
+                format_bytes(total),
+                self.done.min(total) * 100 / total
+            ),
+            _ => format_bytes(self.done),
+        };
+        text.push_str(&format!(" {}/s", format_bytes(self.throughput() as u64)));
+        if let Some(eta) = self.eta() {
+            text.push_str(&format!(" ETA {}", format_duration(eta)));
+        }
+        text
+    }
+}
+
+// =============================================================================
+// Download
+// =============================================================================
+
+/// Download `url` to `dest` through the partial file `part`, retrying
+/// transient failures from the bytes already received. `size` is the object
+/// size when known. Returns the number of bytes of the object.
+pub async fn download<F>(
+    client: &Client,
+    token: &str,
+    url: &str,
+    dest: &Path,
+    part: &Path,
+    size: Option<u64>,
+    progress: F,
+) -> Result<u64>
+where
+    F: Fn(u64, Option<u64>),
+{
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    let mut attempt = 1;
+    loop {
+        match download_attempt(client, token, url, part, size, &progress).await {
+            Ok(total) => {
+                tokio::fs::rename(part, dest)
+                    .await
+                    .with_context(|| format!("Failed to write {}", dest.display()))?;
+                return Ok(total);
+            },
+            Err(Failure::Transient(e)) if attempt < MAX_ATTEMPTS => {
+                tracing::warn!(
+                    "Download of {} interrupted ({}), resuming (attempt {}/{})",
+                    dest.display(),
+                    e,
+                    attempt + 1,
+                    MAX_ATTEMPTS
+                );
+                tokio::time::sleep(retry_delay(attempt)).await;
+                attempt += 1;
+            },
+            Err(Failure::Transient(e)) | Err(Failure::Fatal(e)) => return Err(e),
+        }
+    }
+}
+
+/// One request of a download, appending to the partial file
+async fn download_attempt<F>(
+    client: &Client,
+    token: &str,
+    url: &str,
+    part: &Path,
+    size: Option<u64>,
+    progress: &F,
+) -> std::result::Result<u64, Failure>
+where
+    F: Fn(u64, Option<u64>),
+{
+    let offset = tokio::fs::metadata(part)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+    if offset > 0 && Some(offset) == size {
+        return Ok(offset);
+    }
+
+    let mut request = client.get(url).bearer_auth(token);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={}-", offset));
+    }
+    let mut response = request.send().await.map_err(Failure::transient)?;
+
+    let status = response.status();
+    let (mut file, mut done) = match status {
+        StatusCode::PARTIAL_CONTENT if offset > 0 => {
+            let file = tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(part)
+                .await
+                .map_err(Failure::fatal)?;
+            (file, offset)
+        },
+        // Full content: the range was ignored or there was nothing to resume
+        StatusCode::OK => {
+            let file = tokio::fs::File::create(part)
+                .await
+                .map_err(Failure::fatal)?;
+            (file, 0)
+        },
+        // The partial file already holds every byte
+        StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => return Ok(offset),
+        status => return Err(Failure::status(status, "Download")),
+    };
+
+    let total = size.or_else(|| response.content_length().map(|len| len + done));
+    progress(done, total);
+    while let Some(chunk) = response.chunk().await.map_err(Failure::transient)? {
+        file.write_all(&chunk).await.map_err(Failure::fatal)?;
+        done += chunk.len() as u64;
+        progress(done, total);
+    }
+    file.flush().await.map_err(Failure::fatal)?;
+
+    match total {
+        Some(total) if done < total => Err(Failure::Transient(anyhow!(
+            "connection closed after {} of {} bytes",
+            done,
+            total
+        ))),
+        _ => Ok(done),
+    }
+}
+
+/// Read up to `len` bytes of `url` from `start` (object preview). A server
+/// ignoring the range gets the leading bytes skipped; a start past the end
+/// reads nothing.
+pub async fn read_range(
+    client: &Client,
+    token: &str,
+    url: &str,
+    start: u64,
+    len: u64,
+) -> Result<Vec<u8>> {
+    let end = start + len.max(1) - 1;
+    let mut response = client
+        .get(url)
+        .bearer_auth(token)
+        .header(RANGE, format!("bytes={}-{}", start, end))
+        .send()
+        .await
+        .context("Failed to send request")?;
+
+    let mut skip = match response.status() {
+        StatusCode::PARTIAL_CONTENT => 0,
+        StatusCode::OK => start,
+        StatusCode::RANGE_NOT_SATISFIABLE => return Ok(Vec::new()),
+        status => return Err(anyhow!("API request failed: {}", status)),
+    };
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await.context("Failed to read object")? {
+        let chunk = if skip > 0 {
+            let skipped = skip.min(chunk.len() as u64);
+            skip -= skipped;
+            chunk.slice(skipped as usize..)
+        } else {
+            chunk
+        };
+        data.extend_from_slice(&chunk);
+        if data.len() as u64 >= len {
+            data.truncate(len as usize);
+            break;
+        }
+    }
+    Ok(data)
+}
+
+// =============================================================================
+// Upload
+// =============================================================================
+
+/// Upload the local file `src` as `object` of `bucket`: in one request up to
+/// [`RESUMABLE_THRESHOLD`], through a resumable session above. Returns the
+/// number of bytes sent.
+pub async fn upload<F>(
+    client: &Client,
+    token: &str,
+    uploads_url: &str,
+    object: &str,
+    src: &Path,
+    progress: F,
+) -> Result<u64>
+where
+    F: Fn(u64, Option<u64>),
+{
+    upload_with(
+        client,
+        token,
+        &UploadTarget {
+            uploads_url,
+            object,
+        },
+        src,
+        RESUMABLE_THRESHOLD,
+        CHUNK_SIZE,
+        &progress,
+    )
+    .await
+}
+
+/// Where an upload goes
+struct UploadTarget<'a> {
+    /// Upload URL of the bucket's objects
+    uploads_url: &'a str,
+    object: &'a str,
+}
+
+async fn upload_with<F>(
+    client: &Client,
+    token: &str,
+    target: &UploadTarget<'_>,
+    src: &Path,
+    threshold: u64,
+    chunk_size: usize,
+    progress: &F,
+) -> Result<u64>
+where
+    F: Fn(u64, Option<u64>),
+{
+    let metadata = tokio::fs::metadata(src)
+        .await
+        .with_context(|| format!("Cannot read {}", src.display()))?;
+    if !metadata.is_file() {
+        return Err(anyhow!("{} is not a file", src.display()));
+    }
+    let total = metadata.len();
+    let content_type = content_type_for(src);
+    progress(0, Some(total));
+
+    if total <= threshold {
+        let data = tokio::fs::read(src)
+            .await
+            .with_context(|| format!("Cannot read {}", src.display()))?;
+        let url = upload_url(target.uploads_url, target.object, false);
+        retry("Upload", || async {
+            let response = client
+                .post(&url)
+                .bearer_auth(token)
+                .header(CONTENT_TYPE, content_type)
+                .body(data.clone())
+                .send()
+                .await
+                .map_err(Failure::transient)?;
+            match response.status() {
+                status if status.is_success() => Ok(()),
+                status => Err(Failure::status(status, "Upload")),
+            }
+        })
+        .await?;
+        progress(total, Some(total));
+        return Ok(total);
+    }
+
+    let session = start_session(client, token, target, content_type, total).await?;
+    let mut file = tokio::fs::File::open(src)
+        .await
+        .with_context(|| format!("Cannot read {}", src.display()))?;
+    let mut offset = 0;
+    let mut attempt = 1;
+    let mut buffer = vec![0u8; chunk_size];
+    loop {
+        file.seek(std::io::SeekFrom::Start(offset)).await?;
+        let len = read_chunk(&mut file, &mut buffer).await?;
+        let end = offset + len as u64;
+        let sent = client
+            .put(&session)
+            .bearer_auth(token)
+            .header(
+                CONTENT_RANGE,
+                format!("bytes {}-{}/{}", offset, end.saturating_sub(1), total),
+            )
+            .body(buffer[..len].to_vec())
+            .send()
+            .await;
+
+        let failure = match sent {
+            Ok(response) => match response.status() {
+                status if status.is_success() => {
+                    progress(total, Some(total));
+                    return Ok(total);
+                },
+                StatusCode::PERMANENT_REDIRECT => {
+                    offset = committed_offset(&response);
+                    attempt = 1;
+                    progress(offset, Some(total));
+                    continue;
+                },
+                status => Failure::status(status, "Upload"),
+            },
+            Err(e) => Failure::transient(e),
+        };
+        match failure {
+            Failure::Transient(e) if attempt < MAX_ATTEMPTS => {
+                tracing::warn!(
+                    "Upload of {} interrupted ({}), resuming (attempt {}/{})",
+                    src.display(),
+                    e,
+                    attempt + 1,
+                    MAX_ATTEMPTS
+                );
+                tokio::time::sleep(retry_delay(attempt)).await;
+                attempt += 1;
+                match query_offset(client, token, &session, total).await {
+                    Ok(None) => {
+                        progress(total, Some(total));
+                        return Ok(total);
+                    },
+                    Ok(Some(committed)) => offset = committed,
+                    // Keep the offset and try the chunk again
+                    Err(Failure::Transient(e)) => {
+                        tracing::warn!("Failed to query the upload session: {}", e)
+                    },
+                    Err(Failure::Fatal(e)) => return Err(e),
+                }
+                progress(offset, Some(total));
+            },
+            Failure::Transient(e) | Failure::Fatal(e) => return Err(e),
+        }
+    }
+}
+
+/// Open a resumable session, returning its URI
+async fn start_session(
+    client: &Client,
+    token: &str,
+    target: &UploadTarget<'_>,
+    content_type: &str,
+    total: u64,
+) -> Result<String> {
+    let url = upload_url(target.uploads_url, target.object, true);
+    retry("Upload", || async {
+        let response = client
+            .post(&url)
+            .bearer_auth(token)
+            .header("X-Upload-Content-Type", content_type)
+            .header("X-Upload-Content-Length", total)
+            .header(CONTENT_LENGTH, 0)
+            .send()
+            .await
+            .map_err(Failure::transient)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Failure::status(status, "Upload"));
+        }
+        response
+            .headers()
+            .get(LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+            .ok_or_else(|| Failure::Fatal(anyhow!("Upload session has no location")))
+    })
+    .await
+}
+
+/// Bytes committed by a session, or None when the upload is complete
+async fn query_offset(
+    client: &Client,
+    token: &str,
+    session: &str,
+    total: u64,
+) -> std::result::Result<Option<u64>, Failure> {
+    let response = client
+        .put(session)
+        .bearer_auth(token)
+        .header(CONTENT_RANGE, format!("bytes */{}", total))
+        .header(CONTENT_LENGTH, 0)
+        .send()
+        .await
+        .map_err(Failure::transient)?;
+    match response.status() {
+        status if status.is_success() => Ok(None),
+        StatusCode::PERMANENT_REDIRECT => Ok(Some(committed_offset(&response))),
+        status => Err(Failure::status(status, "Upload")),
+    }
+}
+
+/// Next offset of a resumable upload from the `Range: bytes=0-N` header of a
+/// 308 response (no header: nothing committed yet)
+fn committed_offset(response: &reqwest::Response) -> u64 {
+    response
+        .headers()
+        .get(RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.rsplit('-').next())
+        .and_then(|end| end.parse::<u64>().ok())
+        .map(|end| end + 1)
+        .unwrap_or(0)
+}
+
+/// Fill `buffer` from the file, returning the bytes read (less at the end)
+async fn read_chunk(file: &mut tokio::fs::File, buffer: &mut [u8]) -> Result<usize> {
+    let mut len = 0;
+    while len < buffer.len() {
+        let n = file.read(&mut buffer[len..]).await?;
+        if n == 0 {
+            break;
+        }
+        len += n;
+    }
+    Ok(len)
+}
+
+/// Run a request until it succeeds, fails for good or runs out of attempts
+async fn retry<T, F, Fut>(what: &str, mut request: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = std::result::Result<T, Failure>>,
+{
+    let mut attempt = 1;
+    loop {
+        match request().await {
+            Ok(value) => return Ok(value),
+            Err(Failure::Transient(e)) if attempt < MAX_ATTEMPTS => {
+                tracing::warn!(
+                    "{} failed ({}), retrying (attempt {}/{})",
+                    what,
+                    e,
+                    attempt + 1,
+                    MAX_ATTEMPTS
+                );
+                tokio::time::sleep(retry_delay(attempt)).await;
+                attempt += 1;
+            },
+            Err(Failure::Transient(e)) | Err(Failure::Fatal(e)) => return Err(e),
+        }
+    }
+}
+
+// =============================================================================
+// Background transfers
+// =============================================================================
+
+/// Direction of a transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferKind {
+    Download,
+    Upload,
+}
+
+/// An object to download or a file to upload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferJob {
+    pub kind: TransferKind,
+    pub bucket: String,
+    pub object: String,
+    pub local: PathBuf,
+    /// Object size (downloads)
+    pub size: Option<u64>,
+    /// Object generation (downloads)
+    pub generation: Option<String>,
+}
+
+impl TransferJob {
+    /// Method name used for the notification
+    pub fn method(&self) -> &'static str {
+        match self.kind {
+            TransferKind::Download => "download_object",
+            TransferKind::Upload => "upload_object",
+        }
+    }
+
+    /// "gs://bucket/object -> path" or the reverse
+    pub fn label(&self) -> String {
+        let remote = format!("gs://{}/{}", self.bucket, self.object);
+        let local = self.local.display();
+        match self.kind {
+            TransferKind::Download => format!("{} -> {}", remote, local),
+            TransferKind::Upload => format!("{} -> {}", local, remote),
+        }
+    }
+
+    async fn run<F>(&self, client: &GcpClient, progress: F) -> Result<u64>
+    where
+        F: Fn(u64, Option<u64>),
+    {
+        let token = client.get_token().await?;
+        match self.kind {
+            TransferKind::Download => {
+                let url = media_url(
+                    &client.storage_objects_url(&self.bucket),
+                    &self.object,
+                    self.generation.as_deref(),
+                );
+                let part = part_path(&self.local, self.generation.as_deref());
+                download(
+                    client.http.inner(),
+                    &token,
+                    &url,
+                    &self.local,
+                    &part,
+                    self.size,
+                    progress,
+                )
+                .await
+            },
+            TransferKind::Upload => {
+                upload(
+                    client.http.inner(),
+                    &token,
+                    &client.storage_upload_url(&self.bucket),
+                    &self.object,
+                    &self.local,
+                    progress,
+                )
+                .await
+            },
+        }
+    }
+}
+
+/// Change of a transfer reported by [`TransferManager::poll`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransferUpdate {
+    /// Progress text (bytes, throughput, ETA)
+    Progress(Uuid, String),
+    /// Finished with the number of bytes transferred
+    Done(Uuid, TransferKind, u64),
+    Failed(Uuid, String),
+}
+
+enum TransferEvent {
+    Progress(Uuid, u64, Option<u64>),
+    Finished(Uuid, Result<u64, String>),
+}
+
+struct Transfer {
+    kind: TransferKind,
+    /// None while waiting for a slot
+    progress: Option<Progress>,
+    task: JoinHandle<()>,
+}
+
+/// Running transfers, identified by their notification
+pub struct TransferManager {
+    transfers: HashMap<Uuid, Transfer>,
+    slots: Arc<Semaphore>,
+    tx: mpsc::UnboundedSender<TransferEvent>,
+    events: mpsc::UnboundedReceiver<TransferEvent>,
+}
+
+impl Default for TransferManager {
+    fn default() -> Self {
+        Self::new(MAX_CONCURRENT)
+    }
+}
+
+impl TransferManager {
+    /// Manager running up to `concurrency` transfers at a time
+    pub fn new(concurrency: usize) -> Self {
+        let (tx, events) = mpsc::unbounded_channel();
+        Self {
+            transfers: HashMap::new(),
+            slots: Arc::new(Semaphore::new(concurrency.max(1))),
+            tx,
+            events,
+        }
+    }
+
+    /// Queue a transfer; it starts when a slot is free
+    pub fn start(&mut self, id: Uuid, client: &GcpClient, job: TransferJob) {
+        tracing::info!("Starting transfer {}", job.label());
+        let kind = job.kind;
+        let client = client.clone();
+        let slots = Arc::clone(&self.slots);
+        let tx = self.tx.clone();
+        let task = tokio::spawn(async move {
+            let Ok(_slot) = slots.acquire_owned().await else {
+                return;
+            };
+            let result = job
+                .run(&client, |done, total| {
+                    let _ = tx.send(TransferEvent::Progress(id, done, total));
+                })
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send(TransferEvent::Finished(id, result));
+        });
+        self.transfers.insert(
+            id,
+            Transfer {
+                kind,
+                progress: None,
+                task,
+            },
+        );
+    }
+
+    /// Apply the events received since the last poll
+    pub fn poll(&mut self) -> Vec<TransferUpdate> {
+        let mut updates = Vec::new();
+        let mut changed = Vec::new();
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                TransferEvent::Progress(id, done, total) => {
+                    if let Some(transfer) = self.transfers.get_mut(&id) {
+                        // The clock starts when the transfer leaves the queue
+                        let progress = transfer
+                            .progress
+                            .get_or_insert_with(|| Progress::new(done, total));
+                        progress.done = done;
+                        progress.total = total;
+                        if !changed.contains(&id) {
+                            changed.push(id);
+                        }
+                    }
+                },
+                TransferEvent::Finished(id, result) => {
+                    changed.retain(|c| *c != id);
+                    let Some(transfer) = self.transfers.remove(&id) else {
+                        continue;
+                    };
+                    updates.push(match result {
+                        Ok(bytes) => TransferUpdate::Done(id, transfer.kind, bytes),
+                        Err(e) => TransferUpdate::Failed(id, e),
+                    });
+                },
+            }
+        }
+        for id in changed {
+            if let Some(progress) = self.transfers.get(&id).and_then(|t| t.progress.as_ref()) {
+                updates.push(TransferUpdate::Progress(id, progress.summary()));
+            }
+        }
+        updates
+    }
+
+    /// Number of transfers queued or running
+    pub fn active_count(&self) -> usize {
+        self.transfers.len()
+    }
+}
+
+impl Drop for TransferManager {
+    fn drop(&mut self) {
+        // Partial downloads stay on disk and are resumed by the next attempt
+        for transfer in self.transfers.values() {
+            transfer.task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use wiremock::matchers::{body_bytes, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tgcp-transfer-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_urls_and_paths() {
+        assert_eq!(
+            media_url("http://h/storage/v1/b/b/o", "logs/a b.txt", Some("42")),
+            "http://h/storage/v1/b/b/o/logs%2Fa%20b.txt?alt=media&generation=42"
+        );
+        assert_eq!(
+            upload_url("http://h/upload/storage/v1/b/b/o", "logs/a.txt", true),
+            "http://h/upload/storage/v1/b/b/o?uploadType=resumable&name=logs%2Fa.txt"
+        );
+        assert_eq!(
+            part_path(Path::new("out/a.txt"), Some("42")),
+            PathBuf::from("out/a.txt.42.part")
+        );
+        assert_eq!(
+            download_destination("out", "logs/2024/a.txt", "logs/", true),
+            Ok(PathBuf::from("out/2024/a.txt"))
+        );
+        assert_eq!(
+            download_destination("copy.txt", "logs/a.txt", "logs/", false),
+            Ok(PathBuf::from("copy.txt"))
+        );
+        assert_eq!(
+            download_destination("out/", "logs/a.txt", "logs/", false),
+            Ok(PathBuf::from("out/a.txt"))
+        );
+        // Names from the bucket never leave the destination directory
+        assert!(download_destination("out", "../x", "", true).is_err());
+        assert!(download_destination("out", "logs/../../x", "logs/", true).is_err());
+        assert!(download_destination("out/", "/etc/x", "", false).is_err());
+        assert_eq!(
+            download_destination("out", "./a//b.txt", "", true),
+            Ok(PathBuf::from("out/a/b.txt"))
+        );
+        assert_eq!(
+            upload_object_name(Path::new("/tmp/r.csv"), "", "logs/"),
+            Ok("logs/r.csv".to_string())
+        );
+        assert_eq!(
+            upload_object_name(Path::new("/tmp/r.csv"), " /other/r.csv", "logs/"),
+            Ok("other/r.csv".to_string())
+        );
+        assert!(upload_object_name(Path::new("/tmp/r.csv"), "dir/", "logs/").is_err());
+        assert!(validate_local_path("a\tb").is_err());
+        assert_eq!(content_type_for(Path::new("a.JSON")), "application/json");
+        assert_eq!(
+            content_type_for(Path::new("blob")),
+            "application/octet-stream"
+        );
+
+        let progress = Progress::new(0, Some(4 * 1024 * 1024));
+        assert!(progress.summary().starts_with("0 B / 4.0 MB (0%)"));
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_after_interruption() {
+        let server = MockServer::start().await;
+        // The rest of the object, once the first response was cut short
+        Mock::given(method("GET"))
+            .and(path("/storage/v1/b/b/o/a.txt"))
+            .and(header("range", "bytes=5-"))
+            .respond_with(ResponseTemplate::new(206).set_body_string(" world"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/storage/v1/b/b/o/a.txt"))
+            .and(query_param("alt", "media"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("hello"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+
+        let dir = temp_dir();
+        let dest = dir.join("sub/a.txt");
+        let part = part_path(&dest, Some("1"));
+        let seen = Mutex::new(Vec::new());
+        let url = media_url(
+            &format!("{}/storage/v1/b/b/o", server.uri()),
+            "a.txt",
+            Some("1"),
+        );
+        let bytes = download(
+            &Client::new(),
+            "token",
+            &url,
+            &dest,
+            &part,
+            Some(11),
+            |done, _| seen.lock().unwrap().push(done),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(bytes, 11);
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "hello world");
+        assert!(!part.exists());
+        assert_eq!(seen.lock().unwrap().last(), Some(&11));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_download_fails_on_missing_object() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = temp_dir();
+        let dest = dir.join("a.txt");
+        let url = media_url(&format!("{}/storage/v1/b/b/o", server.uri()), "a.txt", None);
+        let result = download(
+            &Client::new(),
+            "token",
+            &url,
+            &dest,
+            &part_path(&dest, None),
+            None,
+            |_, _| {},
+        )
+        .await;
+        assert!(result.unwrap_err().to_string().contains("404"));
+        assert!(!dest.exists());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_read_range() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/storage/v1/b/b/o/a.txt"))
+            .and(header("range", "bytes=0-3"))
+            .respond_with(ResponseTemplate::new(206).set_body_string("0123"))
+            .mount(&server)
+            .await;
+        // A server ignoring the range sends the whole object
+        Mock::given(method("GET"))
+            .and(path("/storage/v1/b/b/o/a.txt"))
+            .and(header("range", "bytes=4-7"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("0123456789"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/storage/v1/b/b/o/a.txt"))
+            .and(header("range", "bytes=10-13"))
+            .respond_with(ResponseTemplate::new(416))
+            .mount(&server)
+            .await;
+
+        let client = Client::new();
+        let url = media_url(&format!("{}/storage/v1/b/b/o", server.uri()), "a.txt", None);
+        let read = |start| read_range(&client, "token", &url, start, 4);
+        assert_eq!(read(0).await.unwrap(), b"0123");
+        assert_eq!(read(4).await.unwrap(), b"4567");
+        assert!(read(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_simple_upload() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/upload/storage/v1/b/b/o"))
+            .and(query_param("uploadType", "media"))
+            .and(query_param("name", "docs/a.json"))
+            .and(header("content-type", "application/json"))
+            .and(body_bytes(b"{}".to_vec()))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = temp_dir();
+        let src = dir.join("a.json");
+        std::fs::write(&src, "{}").unwrap();
+        let bytes = upload(
+            &Client::new(),
+            "token",
+            &format!("{}/upload/storage/v1/b/b/o", server.uri()),
+            "docs/a.json",
+            &src,
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(bytes, 2);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_resumable_upload_recovers_from_failed_chunk() {
+        let server = MockServer::start().await;
+        let session = format!("{}/session/1", server.uri());
+        Mock::given(method("POST"))
+            .and(path("/upload/storage/v1/b/b/o"))
+            .and(query_param("uploadType", "resumable"))
+            .and(header("x-upload-content-length", "10"))
+            .respond_with(ResponseTemplate::new(200).insert_header("location", session.as_str()))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/session/1"))
+            .and(header("content-range", "bytes 0-3/10"))
+            .respond_with(ResponseTemplate::new(308).insert_header("range", "bytes=0-3"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // The second chunk fails, and only part of it was committed
+        Mock::given(method("PUT"))
+            .and(path("/session/1"))
+            .and(header("content-range", "bytes 4-7/10"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/session/1"))
+            .and(header("content-range", "bytes */10"))
+            .respond_with(ResponseTemplate::new(308).insert_header("range", "bytes=0-5"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/session/1"))
+            .and(header("content-range", "bytes 6-9/10"))
+            .and(body_bytes(b"6789".to_vec()))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = temp_dir();
+        let src = dir.join("data.bin");
+        std::fs::write(&src, "0123456789").unwrap();
+        let uploads_url = format!("{}/upload/storage/v1/b/b/o", server.uri());
+        let target = UploadTarget {
+            uploads_url: &uploads_url,
+            object: "data.bin",
+        };
+        let bytes = upload_with(&Client::new(), "token", &target, &src, 4, 4, &|_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(bytes, 10);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,438 @@
+//! IAP Tunnels
+//!
+//! Port forwardings started with `gcloud compute start-iap-tunnel`. Each
+//! tunnel is a supervised child process: its exit is noticed on the next
+//! poll (with the last line gcloud wrote to stderr as the reason), it can be
+//! restarted with the same ports, and all tunnels are killed when tgcp exits.
+
+use crate::resource::format_duration;
+use anyhow::{anyhow, Context, Result};
+use std::io::{BufRead, BufReader};
+use std::net::TcpListener;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Parse a port typed in the tunnel form (1-65535)
+pub fn parse_port(value: &str) -> Result<u16, String> {
+    value
+        .trim()
+        .parse::<u16>()
+        .ok()
+        .filter(|p| *p > 0)
+        .ok_or_else(|| "Port must be between 1 and 65535".to_string())
+}
+
+/// What a tunnel forwards
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TunnelSpec {
+    pub instance: String,
+    pub zone: String,
+    pub project: String,
+    /// Port on the instance
+    pub remote_port: u16,
+    /// Port listening on localhost
+    pub local_port: u16,
+}
+
+impl TunnelSpec {
+    /// `instance:port` as shown in the tunnels panel
+    pub fn target(&self) -> String {
+        format!("{}:{}", self.instance, self.remote_port)
+    }
+
+    /// Arguments of `gcloud compute start-iap-tunnel`
+    pub fn args(&self) -> Vec<String> {
+        vec![
+            "compute".to_string(),
+            "start-iap-tunnel".to_string(),
+            self.instance.clone(),
+            self.remote_port.to_string(),
+            format!("--local-host-port=localhost:{}", self.local_port),
+            "--zone".to_string(),
+            self.zone.clone(),
+            "--project".to_string(),
+            self.project.clone(),
+        ]
+    }
+
+    fn validate(&self) -> Result<()> {
+        crate::shell::validate_gcp_resource_name(&self.instance, "Instance")?;
+        crate::shell::validate_zone(&self.zone)?;
+        crate::shell::validate_project(&self.project)?;
+        if self.remote_port == 0 || self.local_port == 0 {
+            return Err(anyhow!("Ports must be between 1 and 65535"));
+        }
+        Ok(())
+    }
+}
+
+/// State of a tunnel process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TunnelStatus {
+    Running,
+    /// The process exited on its own, with the reason
+    Exited(String),
+}
+
+/// A started tunnel
+#[derive(Debug)]
+pub struct Tunnel {
+    pub id: u64,
+    pub spec: TunnelSpec,
+    pub status: TunnelStatus,
+    pub started_at: Instant,
+    /// Set when the process exited, to freeze the uptime
+    pub exited_at: Option<Instant>,
+    pub restarts: u32,
+    child: Option<Child>,
+    /// Last line written to stderr (e.g. "Listening on port [8080].")
+    last_line: Arc<Mutex<String>>,
+}
+
+impl Tunnel {
+    pub fn uptime(&self) -> Duration {
+        self.exited_at
+            .unwrap_or_else(Instant::now)
+            .duration_since(self.started_at)
+    }
+
+    pub fn uptime_display(&self) -> String {
+        format_duration(self.uptime())
+    }
+
+    /// Last line gcloud wrote to stderr
+    pub fn last_message(&self) -> String {
+        self.last_line
+            .lock()
+            .map(|line| line.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status == TunnelStatus::Running
+    }
+
+    /// Kill the process and wait for it so no zombie is left behind
+    fn kill(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            if let Err(e) = child.kill() {
+                tracing::debug!("Tunnel {} already exited: {}", self.spec.target(), e);
+            }
+            let _ = child.wait();
+        }
+    }
+
+    /// Check whether the process exited since the last poll
+    fn poll(&mut self) -> bool {
+        let Some(ref mut child) = self.child else {
+            return false;
+        };
+        let status = match child.try_wait() {
+            Ok(None) => return false,
+            Ok(Some(status)) => match status.code() {
+                Some(code) => format!("exited with code {}", code),
+                None => "killed by a signal".to_string(),
+            },
+            Err(e) => format!("failed to wait: {}", e),
+        };
+        self.child = None;
+        self.exited_at = Some(Instant::now());
+        let message = self.last_message();
+        self.status = TunnelStatus::Exited(if message.is_empty() {
+            status
+        } else {
+            format!("{}: {}", status, message)
+        });
+        true
+    }
+}
+
+/// Running tunnels and the tunnels panel selection
+#[derive(Debug)]
+pub struct TunnelManager {
+    pub tunnels: Vec<Tunnel>,
+    pub selected: usize,
+    /// Program started for each tunnel (`gcloud`)
+    program: String,
+    next_id: u64,
+}
+
+impl Default for TunnelManager {
+    fn default() -> Self {
+        Self::new("gcloud")
+    }
+}
+
+impl TunnelManager {
+    pub fn new(program: &str) -> Self {
+        Self {
+            tunnels: Vec::new(),
+            selected: 0,
+            program: program.to_string(),
+            next_id: 1,
+        }
+    }
+
+    /// Start a tunnel. Fails if the local port is already used by another
+    /// running tunnel or the process cannot be started.
+    pub fn start(&mut self, spec: TunnelSpec) -> Result<u64> {
+        spec.validate()?;
+        self.check_local_port(spec.local_port, None)?;
+
+        let (child, last_line) = self.spawn(&spec)?;
+        let id = self.next_id;
+        self.next_id += 1;
+        tracing::info!(
+            "Started IAP tunnel localhost:{} -> {} (zone={}, project={})",
+            spec.local_port,
+            spec.target(),
+            spec.zone,
+            spec.project
+        );
+        self.tunnels.push(Tunnel {
+            id,
+            spec,
+            status: TunnelStatus::Running,
+            started_at: Instant::now(),
+            exited_at: None,
+            restarts: 0,
+            child: Some(child),
+            last_line,
+        });
+        Ok(id)
+    }
+
+    /// Fail if another running tunnel (other than `except`) listens on the port
+    fn check_local_port(&self, local_port: u16, except: Option<u64>) -> Result<()> {
+        match self
+            .tunnels
+            .iter()
+            .find(|t| Some(t.id) != except && t.is_running() && t.spec.local_port == local_port)
+        {
+            Some(other) => Err(anyhow!(
+                "Local port {} is already used by the tunnel to {}",
+                local_port,
+                other.spec.target()
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn spawn(&self, spec: &TunnelSpec) -> Result<(Child, Arc<Mutex<String>>)> {
+        let mut child = Command::new(&self.program)
+            .args(spec.args())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to execute {}", self.program))?;
+
+        // Drain stderr so gcloud never blocks on a full pipe, keeping the
+        // last line as the status message
+        let last_line = Arc::new(Mutex::new(String::new()));
+        if let Some(stderr) = child.stderr.take() {
+            let last_line = Arc::clone(&last_line);
+            std::thread::spawn(move || {
+                for line in BufReader::new(stderr).lines().map_while(|l| l.ok()) {
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    if let Ok(mut last) = last_line.lock() {
+                        *last = line.to_string();
+                    }
+                }
+            });
+        }
+        Ok((child, last_line))
+    }
+
+    /// Notice tunnels that exited on their own; returns their descriptions
+    pub fn poll(&mut self) -> Vec<String> {
+        self.tunnels
+            .iter_mut()
+            .filter_map(|tunnel| {
+                tunnel.poll().then(|| {
+                    let TunnelStatus::Exited(ref reason) = tunnel.status else {
+                        return String::new();
+                    };
+                    format!(
+                        "Tunnel localhost:{} -> {} {}",
+                        tunnel.spec.local_port,
+                        tunnel.spec.target(),
+                        reason
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Kill a tunnel and remove it from the list
+    pub fn stop(&mut self, id: u64) -> Option<TunnelSpec> {
+        let index = self.tunnels.iter().position(|t| t.id == id)?;
+        let mut tunnel = self.tunnels.remove(index);
+        tunnel.kill();
+        tracing::info!("Stopped IAP tunnel to {}", tunnel.spec.target());
+        self.selected = self.selected.min(self.tunnels.len().saturating_sub(1));
+        Some(tunnel.spec)
+    }
+
+    /// Start a tunnel again with the same ports, killing it first if it is
+    /// still running
+    pub fn restart(&mut self, id: u64) -> Result<()> {
+        let index = self
+            .tunnels
+            .iter()
+            .position(|t| t.id == id)
+            .ok_or_else(|| anyhow!("Tunnel not found"))?;
+        self.check_local_port(self.tunnels[index].spec.local_port, Some(id))?;
+        self.tunnels[index].kill();
+        let (child, last_line) = self.spawn(&self.tunnels[index].spec)?;
+        let tunnel = &mut self.tunnels[index];
+        tunnel.child = Some(child);
+        tunnel.last_line = last_line;
+        tunnel.status = TunnelStatus::Running;
+        tunnel.started_at = Instant::now();
+        tunnel.exited_at = None;
+        tunnel.restarts += 1;
+        tracing::info!("Restarted IAP tunnel to {}", tunnel.spec.target());
+        Ok(())
+    }
+
+    /// Kill all tunnels (on exit)
+    pub fn stop_all(&mut self) {
+        for tunnel in &mut self.tunnels {
+            tunnel.kill();
+        }
+        self.tunnels.clear();
+        self.selected = 0;
+    }
+
+    pub fn running_count(&self) -> usize {
+        self.tunnels.iter().filter(|t| t.is_running()).count()
+    }
+
+    pub fn selected_id(&self) -> Option<u64> {
+        self.tunnels.get(self.selected).map(|t| t.id)
+    }
+
+    pub fn select_by(&mut self, delta: isize) {
+        let last = self.tunnels.len().saturating_sub(1) as isize;
+        self.selected = (self.selected as isize)
+            .saturating_add(delta)
+            .clamp(0, last) as usize;
+    }
+}
+
+impl Drop for TunnelManager {
+    fn drop(&mut self) {
+        self.stop_all();
+    }
+}
+
+/// A local port that is free right now, to suggest as the tunnel's port
+pub fn free_local_port() -> Option<u16> {
+    TcpListener::bind(("127.0.0.1", 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .ok()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::shell::stub_gcloud;
+
+    fn spec(local_port: u16) -> TunnelSpec {
+        TunnelSpec {
+            instance: "web-1".to_string(),
+            zone: "europe-west1-b".to_string(),
+            project: "my-project".to_string(),
+            remote_port: 22,
+            local_port,
+        }
+    }
+
+    fn wait_for(mut condition: impl FnMut() -> bool) -> bool {
+        for _ in 0..100 {
+            if condition() {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        false
+    }
+
+    #[test]
+    fn test_tunnel_start_stop() {
+        let dir = stub_gcloud("echo \"$@\" > \"$(dirname \"$0\")/args\"\nexec sleep 30");
+        let mut manager = TunnelManager::new(dir.join("gcloud").to_str().unwrap());
+
+        let id = manager.start(spec(40022)).unwrap();
+        assert!(manager.start(spec(40022)).is_err());
+        assert!(wait_for(|| dir.join("args").exists()));
+        assert!(wait_for(
+            || std::fs::read_to_string(dir.join("args")).is_ok_and(|args| args.ends_with('\n'))
+        ));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("args")).unwrap().trim(),
+            "compute start-iap-tunnel web-1 22 --local-host-port=localhost:40022 \
+             --zone europe-west1-b --project my-project"
+        );
+        assert!(manager.poll().is_empty());
+        assert_eq!(manager.running_count(), 1);
+
+        manager.restart(id).unwrap();
+        assert_eq!(manager.tunnels[0].restarts, 1);
+        assert!(manager.stop(id).is_some());
+        assert!(manager.tunnels.is_empty());
+
+        manager.start(spec(40023)).unwrap();
+        manager.stop_all();
+        assert!(manager.tunnels.is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_tunnel_exit_is_detected() {
+        let dir = stub_gcloud("echo 'ERROR: (gcloud) permission denied' >&2\nexit 1");
+        let mut manager = TunnelManager::new(dir.join("gcloud").to_str().unwrap());
+
+        manager.start(spec(40024)).unwrap();
+        let mut exited = Vec::new();
+        assert!(wait_for(|| {
+            exited.extend(manager.poll());
+            !exited.is_empty()
+        }));
+        // The reader thread may lag behind the exit
+        assert!(wait_for(
+            || manager.tunnels[0].last_message() == "ERROR: (gcloud) permission denied"
+        ));
+        assert!(exited[0].contains("exited with code 1"));
+        assert!(!manager.tunnels[0].is_running());
+        assert_eq!(manager.running_count(), 0);
+        // An exited tunnel no longer holds its local port, and cannot be
+        // restarted while another tunnel took it over
+        let first = manager.tunnels[0].id;
+        manager.start(spec(40024)).unwrap();
+        let err = manager.restart(first).unwrap_err().to_string();
+        assert!(err.contains("Local port 40024 is already used"), "{}", err);
+        assert_eq!(manager.tunnels[0].restarts, 0);
+        manager.stop_all();
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_tunnel_rejects_invalid_names() {
+        let mut manager = TunnelManager::new("/nonexistent/gcloud");
+        let mut bad = spec(40025);
+        bad.instance = "web;rm -rf /".to_string();
+        assert!(manager.start(bad).is_err());
+        assert!(manager.start(spec(40025)).is_err());
+        assert!(manager.tunnels.is_empty());
+        assert_eq!(parse_port(" 8080"), Ok(8080));
+        assert!(parse_port("0").is_err());
+        assert!(parse_port("65536").is_err());
+    }
+}
@@ -0,0 +1,80 @@
+//! Bucket Detail View
+//!
+//! Settings of a bucket in readable sections, public IAM bindings in red.
+
+use crate::app::App;
+use crate::bucket::LineKind;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
+    let Some(ref mut view) = app.bucket else {
+        return;
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(Span::styled(
+            format!(" Bucket gs://{} ", view.name),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+
+    let status = if let Some(ref error) = view.error {
+        Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red)))
+    } else if view.bucket.is_none() {
+        Line::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::DarkGray),
+        ))
+    } else {
+        match view.risk_count() {
+            0 => Line::from(Span::styled(
+                "No public access found",
+                Style::default().fg(Color::Green),
+            )),
+            n => Line::from(Span::styled(
+                format!("{} public grant(s): anyone can reach this data", n),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )),
+        }
+    };
+    f.render_widget(Paragraph::new(status), chunks[0]);
+
+    let area = chunks[1];
+    let visible = area.height as usize;
+    view.viewport_height = visible.max(1);
+    let lines: Vec<Line> = view
+        .lines
+        .iter()
+        .skip(view.scroll)
+        .take(visible)
+        .map(|line| {
+            let style = match line.kind {
+                LineKind::Heading => Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+                LineKind::Normal => Style::default(),
+                LineKind::Good => Style::default().fg(Color::Green),
+                LineKind::Warning => Style::default().fg(Color::Yellow),
+                LineKind::Risk => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            };
+            Line::from(Span::styled(line.text.clone(), style))
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), area);
+}
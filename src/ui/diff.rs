@@ -0,0 +1,135 @@
+//! Diff View
+//!
+//! Side-by-side structural diff of two resources, one row per changed path.
+
+use crate::app::App;
+use crate::diff::DiffKind;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+use serde_json::Value;
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let Some(ref state) = app.diff_state else {
+        return;
+    };
+
+    let title = format!(" Diff: {} ↔ {} ", state.left_label, state.right_label);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+
+    // Summary line
+    let count = |kind: DiffKind| {
+        state
+            .result
+            .entries
+            .iter()
+            .filter(|e| e.kind == kind)
+            .count()
+    };
+    let mut summary = vec![
+        Span::styled(
+            format!(" ~{} changed ", count(DiffKind::Changed)),
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::styled(
+            format!(" +{} added ", count(DiffKind::Added)),
+            Style::default().fg(Color::Green),
+        ),
+        Span::styled(
+            format!(" -{} removed ", count(DiffKind::Removed)),
+            Style::default().fg(Color::Red),
+        ),
+    ];
+    if state.result.ignored > 0 {
+        summary.push(Span::styled(
+            format!(" ({} ignored)", state.result.ignored),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    f.render_widget(Paragraph::new(Line::from(summary)), chunks[0]);
+
+    if state.result.entries.is_empty() {
+        f.render_widget(
+            Paragraph::new(" No differences").style(Style::default().fg(Color::DarkGray)),
+            chunks[1],
+        );
+        return;
+    }
+
+    let header = Row::new(vec![
+        Cell::from(""),
+        Cell::from("PATH"),
+        Cell::from(state.left_label.clone()),
+        Cell::from(state.right_label.clone()),
+    ])
+    .style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let visible = chunks[1].height.saturating_sub(1) as usize;
+    let rows: Vec<Row> = state
+        .result
+        .entries
+        .iter()
+        .skip(state.scroll)
+        .take(visible)
+        .map(|entry| {
+            let color = match entry.kind {
+                DiffKind::Added => Color::Green,
+                DiffKind::Removed => Color::Red,
+                DiffKind::Changed => Color::Yellow,
+            };
+            Row::new(vec![
+                Cell::from(entry.kind.symbol().to_string()),
+                Cell::from(entry.path.clone()),
+                Cell::from(format_value(entry.left.as_ref())),
+                Cell::from(format_value(entry.right.as_ref())),
+            ])
+            .style(Style::default().fg(color))
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(1),
+            Constraint::Percentage(34),
+            Constraint::Percentage(33),
+            Constraint::Percentage(33),
+        ],
+    )
+    .header(header);
+
+    f.render_widget(table, chunks[1]);
+}
+
+/// Compact single-line rendering of a value ("" when absent)
+fn format_value(value: Option<&Value>) -> String {
+    match value {
+        None => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(v) => v.to_string(),
+    }
+}
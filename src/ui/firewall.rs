@@ -0,0 +1,188 @@
+//! Effective Firewall View
+//!
+//! Rules applying to an instance's network interface in evaluation order,
+//! with the rule deciding the last query highlighted.
+
+use crate::app::App;
+use crate::firewall::{FirewallView, RuleAction};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
+    let Some(ref mut view) = app.firewall else {
+        return;
+    };
+
+    let title = format!(
+        " Effective firewall: {} ({}, {}) ",
+        view.instance, view.network_interface, view.zone
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+    f.render_widget(Paragraph::new(query_line(view)), chunks[0]);
+
+    let area = chunks[1];
+    if view.loading || view.rules.is_empty() {
+        let text = if view.loading {
+            " Loading effective firewall..."
+        } else {
+            " No rules"
+        };
+        f.render_widget(
+            Paragraph::new(text).style(Style::default().fg(Color::DarkGray)),
+            area,
+        );
+        return;
+    }
+
+    let header = Row::new(vec![
+        Cell::from(""),
+        Cell::from("ORIGIN"),
+        Cell::from("RULE"),
+        Cell::from("PRIORITY"),
+        Cell::from("DIR"),
+        Cell::from("ACTION"),
+        Cell::from("PROTOCOLS"),
+        Cell::from("SOURCE / DESTINATION"),
+        Cell::from("TARGETS"),
+    ])
+    .style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let visible = area.height.saturating_sub(1) as usize;
+    view.viewport_height = visible.max(1);
+    let deciding = view.deciding_rule();
+    let rows: Vec<Row> = view
+        .rules
+        .iter()
+        .enumerate()
+        .skip(view.scroll)
+        .take(visible)
+        .map(|(index, rule)| {
+            let action_color = match rule.action {
+                RuleAction::Allow => Color::Green,
+                RuleAction::Deny => Color::Red,
+                RuleAction::GotoNext => Color::DarkGray,
+            };
+            let marker = if Some(index) == deciding { "▶" } else { "" };
+            let mut style = if rule.disabled {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            if Some(index) == deciding {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            if index == view.selected {
+                style = style.bg(Color::DarkGray);
+            }
+            let name = if rule.disabled {
+                format!("{} (disabled)", rule.name)
+            } else {
+                rule.name.clone()
+            };
+            Row::new(vec![
+                Cell::from(marker),
+                Cell::from(rule.origin.clone()),
+                Cell::from(name),
+                Cell::from(rule.priority.to_string()),
+                Cell::from(if rule.ingress { "IN" } else { "OUT" }),
+                Cell::from(Span::styled(
+                    rule.action.label(),
+                    Style::default().fg(action_color),
+                )),
+                Cell::from(rule.protocols_label()),
+                Cell::from(rule.peers_label()),
+                Cell::from(rule.targets.clone()),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(1),
+            Constraint::Length(22),
+            Constraint::Length(24),
+            Constraint::Length(10),
+            Constraint::Length(4),
+            Constraint::Length(10),
+            Constraint::Length(18),
+            Constraint::Min(20),
+            Constraint::Length(20),
+        ],
+    )
+    .header(header);
+    f.render_widget(table, area);
+}
+
+fn query_line(view: &FirewallView) -> Line<'static> {
+    if let Some(ref input) = view.input {
+        return Line::from(vec![
+            Span::styled(
+                format!("Query: {}_", input),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                "  <protocol>[:port] [from|to <address/range>]",
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]);
+    }
+    if let Some(ref error) = view.error {
+        return Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red)));
+    }
+    match view.verdict {
+        Some(Ok(ref verdict)) => {
+            let color = if verdict.allowed {
+                Color::Green
+            } else {
+                Color::Red
+            };
+            Line::from(vec![
+                Span::raw(format!("{}: ", view.query)),
+                Span::styled(
+                    verdict.explanation.clone(),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+            ])
+        },
+        Some(Err(ref error)) => Line::from(Span::styled(
+            format!("{}: {}", view.query, error),
+            Style::default().fg(Color::Red),
+        )),
+        None => Line::from(Span::styled(
+            format!(
+                "{} rules in evaluation order. Press / to check a connection (e.g. {})",
+                view.rules.len(),
+                crate::firewall::EXAMPLE_QUERY
+            ),
+            Style::default().fg(Color::DarkGray),
+        )),
+    }
+}
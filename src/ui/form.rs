@@ -0,0 +1,250 @@
+//! Form View
+//!
+//! Multi-step input forms (create wizards): the fields of the current step,
+//! a dropdown for the focused select field, and the request preview.
+
+use crate::app::App;
+use crate::form::{FieldKind, FormStage, FormState};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Width of the field label column
+const LABEL_WIDTH: usize = 18;
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let Some(ref form) = app.form else {
+        return;
+    };
+
+    let title = match form.stage {
+        FormStage::Preview(_) => format!(" {}: review request ", form.title),
+        FormStage::Edit => format!(" {} ({}/{}) ", form.title, form.step + 1, form.steps.len()),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Steps
+            Constraint::Min(1),    // Fields or preview
+            Constraint::Length(1), // Error
+            Constraint::Length(1), // Key hints
+        ])
+        .split(inner);
+
+    f.render_widget(Paragraph::new(steps_line(form)), chunks[0]);
+
+    let hints = match form.stage {
+        FormStage::Preview(ref body) => {
+            render_preview(f, form, body, chunks[1]);
+            "Enter: submit | j/k: scroll | Esc: back to form"
+        },
+        FormStage::Edit => {
+            render_fields(f, form, chunks[1]);
+            "Tab/↑↓: field | ←/→: choose | type to filter | Enter: next | Esc: back"
+        },
+    };
+
+    if let Some(ref error) = form.error {
+        f.render_widget(
+            Paragraph::new(format!(" {}", error)).style(Style::default().fg(Color::Red)),
+            chunks[2],
+        );
+    }
+    f.render_widget(
+        Paragraph::new(format!(" {}", hints)).style(Style::default().fg(Color::DarkGray)),
+        chunks[3],
+    );
+}
+
+/// Step names with the current step highlighted
+fn steps_line(form: &FormState) -> Line<'static> {
+    let previewing = matches!(form.stage, FormStage::Preview(_));
+    let mut spans = vec![Span::raw(" ")];
+    for (i, name) in form.steps.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" › ", Style::default().fg(Color::DarkGray)));
+        }
+        let style = if i == form.step && !previewing {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else if i < form.step || previewing {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        spans.push(Span::styled(name.clone(), style));
+    }
+    spans.push(Span::styled(" › ", Style::default().fg(Color::DarkGray)));
+    spans.push(Span::styled(
+        "Review",
+        if previewing {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        },
+    ));
+    Line::from(spans)
+}
+
+fn render_fields(f: &mut Frame, form: &FormState, area: Rect) {
+    let indices = form.step_fields();
+    let focused = form.fields.get(form.focused);
+    let help_height = u16::from(focused.is_some_and(|field| field.help.is_some()));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(indices.len() as u16),
+            Constraint::Length(help_height),
+            Constraint::Length(1),
+            Constraint::Min(0), // Dropdown
+        ])
+        .split(area);
+
+    let lines: Vec<Line> = indices
+        .iter()
+        .map(|&i| {
+            let field = &form.fields[i];
+            let is_focused = i == form.focused;
+            let marker = if field.required { "*" } else { " " };
+            let label_style = if is_focused {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let mut spans = vec![
+                Span::styled(if is_focused { " ▸ " } else { "   " }, label_style),
+                Span::styled(
+                    format!(
+                        "{:<width$}",
+                        format!("{}{}", field.label, marker),
+                        width = LABEL_WIDTH
+                    ),
+                    label_style,
+                ),
+            ];
+            let value = field.display_value();
+            let value_style = if is_focused {
+                Style::default().fg(Color::Cyan).bg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::Cyan)
+            };
+            spans.push(Span::styled(value, value_style));
+            match &field.kind {
+                FieldKind::Select { filter, .. } if is_focused && !filter.is_empty() => {
+                    spans.push(Span::styled(
+                        format!("  /{}", filter),
+                        Style::default().fg(Color::Yellow),
+                    ));
+                },
+                FieldKind::Text | FieldKind::Number if is_focused => {
+                    spans.push(Span::styled("▏", Style::default().fg(Color::Yellow)));
+                },
+                _ => {},
+            }
+            Line::from(spans)
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), chunks[0]);
+
+    if let Some(help) = focused.and_then(|field| field.help.as_ref()) {
+        f.render_widget(
+            Paragraph::new(format!("   {}", help)).style(Style::default().fg(Color::DarkGray)),
+            chunks[1],
+        );
+    }
+
+    // Dropdown of the focused select field
+    let Some(field) = focused else {
+        return;
+    };
+    let FieldKind::Select { selected, .. } = field.kind else {
+        return;
+    };
+    let options = field.filtered_options();
+    if options.is_empty() {
+        f.render_widget(
+            Paragraph::new("   No options").style(Style::default().fg(Color::DarkGray)),
+            chunks[3],
+        );
+        return;
+    }
+
+    let items: Vec<ListItem> = options
+        .iter()
+        .map(|option| ListItem::new(format!("   {}", option.label)))
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .border_style(Style::default().fg(Color::DarkGray))
+                .title(format!(" {} ({}) ", field.label, options.len())),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+    let mut list_state = ListState::default();
+    list_state.select(Some(selected));
+    f.render_stateful_widget(list, chunks[3], &mut list_state);
+}
+
+fn render_preview(f: &mut Frame, form: &FormState, body: &serde_json::Value, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(form.notes.len() as u16),
+            Constraint::Min(1),
+        ])
+        .split(area);
+    let notes: Vec<Line> = form
+        .notes
+        .iter()
+        .map(|note| {
+            let color = if note.starts_with("WARNING") {
+                Color::Red
+            } else {
+                Color::Yellow
+            };
+            Line::from(Span::styled(
+                format!(" {}", note),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ))
+        })
+        .collect();
+    f.render_widget(Paragraph::new(notes), chunks[0]);
+    let area = chunks[1];
+
+    let json = serde_json::to_string_pretty(body).unwrap_or_default();
+    let lines: Vec<Line> = json
+        .lines()
+        .skip(form.preview_scroll)
+        .take(area.height as usize)
+        .map(super::highlight_json_line)
+        .collect();
+    f.render_widget(Paragraph::new(lines), area);
+}
@@ -78,6 +78,26 @@ pub fn render(f: &mut Frame, _app: &App) {
             Span::styled("  Enter or d      ", Style::default().fg(Color::Yellow)),
             Span::raw("View resource details (JSON)"),
         ]),
+        Line::from(vec![
+            Span::styled("  Tab (details)   ", Style::default().fg(Color::Yellow)),
+            Span::raw("Toggle list row / full details"),
+        ]),
+        Line::from(vec![
+            Span::styled("  za/zR/zM        ", Style::default().fg(Color::Yellow)),
+            Span::raw("Fold node / unfold all / fold all (details)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  y / yy / Y      ", Style::default().fg(Color::Yellow)),
+            Span::raw("Toggle YAML / copy path / copy value (details)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Enter (details) ", Style::default().fg(Color::Yellow)),
+            Span::raw("Follow resource link under cursor (b: back)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  / then n/N      ", Style::default().fg(Color::Yellow)),
+            Span::raw("Search in details, next/previous match"),
+        ]),
         Line::from(vec![
             Span::styled("  ← or Backspace  ", Style::default().fg(Color::Yellow)),
             Span::raw("Go back"),
@@ -126,6 +146,10 @@ pub fn render(f: &mut Frame, _app: &App) {
             Span::styled("  o               ", Style::default().fg(Color::Yellow)),
             Span::raw("Configure visible columns"),
         ]),
+        Line::from(vec![
+            Span::styled("  y               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Copy name, ID, selfLink, IP, console URL or JSON"),
+        ]),
         Line::from(""),
         // Actions section
         Line::from(vec![Span::styled(
@@ -144,6 +168,34 @@ pub fn render(f: &mut Frame, _app: &App) {
             Span::styled("  r               ", Style::default().fg(Color::Yellow)),
             Span::raw("Reset instance"),
         ]),
+        Line::from(vec![
+            Span::styled("  u / U           ", Style::default().fg(Color::Yellow)),
+            Span::raw("Suspend / resume instance"),
+        ]),
+        Line::from(vec![
+            Span::styled("  P               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Toggle deletion protection"),
+        ]),
+        Line::from(vec![
+            Span::styled("  i               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Promote ephemeral IP to static"),
+        ]),
+        Line::from(vec![
+            Span::styled("  F               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Effective firewall (/ to check a connection)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  c               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Create instance (wizard)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  m               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Resize (change machine type)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  e               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Edit labels/metadata/tags in $EDITOR"),
+        ]),
         Line::from(vec![
             Span::styled("  x               ", Style::default().fg(Color::Green)),
             Span::raw("SSH to instance"),
@@ -156,11 +208,128 @@ pub fn render(f: &mut Frame, _app: &App) {
             Span::styled("  C               ", Style::default().fg(Color::Green)),
             Span::raw("Open in GCP Console"),
         ]),
+        Line::from(vec![
+            Span::styled("  l               ", Style::default().fg(Color::Green)),
+            Span::raw("Serial port output (f: follow, 1-4: port)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  T               ", Style::default().fg(Color::Green)),
+            Span::raw("IAP tunnel to a port (:tunnels to manage)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  !               ", Style::default().fg(Color::Green)),
+            Span::raw("Run a command on the selected instances"),
+        ]),
+        Line::from(vec![
+            Span::styled("  < / >           ", Style::default().fg(Color::Green)),
+            Span::raw("Copy files from / to instance (scp)"),
+        ]),
         Line::from(vec![
             Span::styled("  Delete          ", Style::default().fg(Color::Red)),
             Span::raw("Delete resource (destructive)"),
         ]),
         Line::from(""),
+        Line::from(vec![Span::styled(
+            "Actions (Disks & Snapshots)",
+            Style::default().add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(vec![
+            Span::styled("  s               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Snapshot disk"),
+        ]),
+        Line::from(vec![
+            Span::styled("  S               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Snapshots of the disk"),
+        ]),
+        Line::from(vec![
+            Span::styled("  m               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Resize disk (grow only)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  a / D           ", Style::default().fg(Color::Yellow)),
+            Span::raw("Attach to / detach from instance"),
+        ]),
+        Line::from(vec![
+            Span::styled("  c               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Create disk from snapshot"),
+        ]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "Actions (Firewall Rules)",
+            Style::default().add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(vec![
+            Span::styled("  c / e           ", Style::default().fg(Color::Yellow)),
+            Span::raw("Create / edit rule (with impact preview)"),
+        ]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "Actions (Instance Groups)",
+            Style::default().add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(vec![
+            Span::styled("  i               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Managed instances of the group"),
+        ]),
+        Line::from(vec![
+            Span::styled("  m               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Resize group"),
+        ]),
+        Line::from(vec![
+            Span::styled("  r / U           ", Style::default().fg(Color::Yellow)),
+            Span::raw("Rolling restart / replace"),
+        ]),
+        Line::from(vec![
+            Span::styled("  r / A           ", Style::default().fg(Color::Yellow)),
+            Span::raw("Recreate / abandon managed instance"),
+        ]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "Actions (Storage Buckets)",
+            Style::default().add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(vec![
+            Span::styled("  i               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Bucket details (lifecycle, retention, CORS, IAM)"),
+        ]),
+        Line::from(vec![
+            Span::styled("    v / p         ", Style::default().fg(Color::Yellow)),
+            Span::raw("In details: toggle versioning / public access prevention"),
+        ]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "Actions (Storage Objects)",
+            Style::default().add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(vec![
+            Span::styled("  Enter / b       ", Style::default().fg(Color::Yellow)),
+            Span::raw("Open folder / up one folder"),
+        ]),
+        Line::from(vec![
+            Span::styled("  r               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Toggle recursive (flat) listing"),
+        ]),
+        Line::from(vec![
+            Span::styled("  x               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Show / hide deleted objects (versioned buckets)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  H               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Object versions (s: restore, d: delete generation)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  P               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Preview object content (m: load more)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  D               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Download selected objects"),
+        ]),
+        Line::from(vec![
+            Span::styled("  u               ", Style::default().fg(Color::Yellow)),
+            Span::raw("Upload a file into the current folder"),
+        ]),
+        Line::from(""),
         // Commands section
         Line::from(vec![Span::styled(
             "Commands (:)",
@@ -174,6 +343,10 @@ pub fn render(f: &mut Frame, _app: &App) {
             Span::styled("  :alias a b      ", Style::default().fg(Color::Yellow)),
             Span::raw("Create alias 'a' for resource 'b'"),
         ]),
+        Line::from(vec![
+            Span::styled("  :diff [prev]    ", Style::default().fg(Color::Yellow)),
+            Span::raw("Diff 2 selected rows, or row vs previous refresh"),
+        ]),
         Line::from(""),
         // General section
         Line::from(vec![Span::styled(
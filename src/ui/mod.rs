@@ -14,6 +14,15 @@
 //! - `projects` - Project selector UI
 //! - `zones` - Zone selector UI
 //! - `notifications` - Toast notifications for async operations
+//! - `diff` - Structural diff of two resources (`:diff`)
+//! - `yank` - Copy-to-clipboard menu (`y`)
+//! - `serial` - Serial port output of an instance (`l`)
+//! - `firewall` - Effective firewall of an instance (`F`)
+//! - `tunnels` - Running IAP tunnels (`:tunnels`)
+//! - `run` - Output of a command run on instances (`!`)
+//! - `preview` - Content preview of a Cloud Storage object (`P`)
+//! - `bucket` - Settings and IAM bindings of a bucket (`i`)
+//! - `versions` - Generations of a Cloud Storage object (`H`)
 //!
 //! # Virtual Scrolling
 //!
@@ -28,18 +37,32 @@
 //! - Numbers in light blue
 //! - Booleans in magenta
 //! - Null values in dark gray
+//!
+//! Containers can be folded (`za`/`zR`/`zM`), the document can be rendered as
+//! YAML (`y`), and `/` searches with match highlighting.
 
+mod bucket;
 mod column_config;
 mod command_box;
 mod dialog;
+mod diff;
+mod firewall;
+mod form;
 mod header;
 mod help;
 mod notifications;
+mod preview;
 mod projects;
+mod run;
+mod serial;
 pub mod splash;
+mod tunnels;
+mod versions;
+mod yank;
 mod zones;
 
 use crate::app::{App, Mode};
+use crate::describe::{find_link, DocFormat, LineKind};
 use crate::resource::{extract_json_value, get_color_for_value, ColumnDef};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -77,6 +100,35 @@ pub fn render(f: &mut Frame, app: &mut App) {
         Mode::Describe => {
             render_describe_view(f, app, chunks[1]);
         },
+        Mode::Diff => {
+            diff::render(f, app, chunks[1]);
+        },
+        Mode::Form => {
+            form::render(f, app, chunks[1]);
+        },
+        Mode::Serial => {
+            serial::render(f, app, chunks[1]);
+        },
+        Mode::Firewall => {
+            firewall::render(f, app, chunks[1]);
+        },
+        Mode::Tunnels => {
+            tunnels::render(f, app, chunks[1]);
+        },
+        Mode::RunOutput => {
+            run::render(f, app, chunks[1]);
+        },
+        Mode::Preview => {
+            preview::render(f, app, chunks[1]);
+        },
+        // The toggles of the bucket view are confirmed on top of it
+        Mode::Bucket | Mode::Confirm | Mode::Warning if app.bucket.is_some() => {
+            bucket::render(f, app, chunks[1]);
+        },
+        // Restores and deletes are confirmed on top of the versions view
+        Mode::Versions | Mode::Confirm | Mode::Warning if app.versions.is_some() => {
+            versions::render(f, app, chunks[1]);
+        },
         _ => {
             render_main_content(f, app, chunks[1]);
         },
@@ -102,6 +154,9 @@ pub fn render(f: &mut Frame, app: &mut App) {
         Mode::ColumnConfig => {
             column_config::render(f, app, f.area());
         },
+        Mode::Yank => {
+            yank::render(f, app, f.area());
+        },
         _ => {},
     }
 }
@@ -450,19 +505,36 @@ fn truncate_string(s: &str, max_len: usize) -> String {
     }
 }
 
-fn render_describe_view(f: &mut Frame, app: &App, area: Rect) {
-    let json = app
-        .selected_item_json()
-        .unwrap_or_else(|| "No item selected".to_string());
+fn render_describe_view(f: &mut Frame, app: &mut App, area: Rect) {
+    let show_search = app.describe.search.input_active || !app.describe.search.query.is_empty();
 
-    // Apply JSON syntax highlighting
-    let lines: Vec<Line> = json.lines().map(highlight_json_line).collect();
-    let total_lines = lines.len();
+    let area = if show_search {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(1)])
+            .split(area);
+        render_describe_search_bar(f, app, chunks[0]);
+        chunks[1]
+    } else {
+        area
+    };
+
+    let doc_lines = app.describe.lines();
+    let total_lines = doc_lines.len();
+
+    let source = if app.describe.detail.is_none() {
+        ""
+    } else if app.describe.show_detail {
+        " [detail]"
+    } else {
+        " [list]"
+    };
+    let format = app.describe.format.label();
 
     let title = if let Some(resource) = app.current_resource() {
-        format!(" {} Details ", resource.display_name)
+        format!(" {} Details{} [{}] ", resource.display_name, source, format)
     } else {
-        " Details ".to_string()
+        format!(" Details{} [{}] ", source, format)
     };
 
     let block = Block::default()
@@ -478,14 +550,61 @@ fn render_describe_view(f: &mut Frame, app: &App, area: Rect) {
     let inner_area = block.inner(area);
     f.render_widget(block, area);
 
-    // Calculate max scroll based on inner area (content area without borders)
+    if total_lines == 0 {
+        f.render_widget(Paragraph::new("No item selected"), inner_area);
+        return;
+    }
+
+    // Keep the cursor inside the viewport (virtual scrolling)
     let visible_lines = inner_area.height as usize;
+    app.describe.viewport_height = visible_lines.max(1);
+    app.describe.cursor = app.describe.cursor.min(total_lines - 1);
+    app.describe.ensure_cursor_visible();
     let max_scroll = total_lines.saturating_sub(visible_lines);
-    let scroll = app.describe.scroll.min(max_scroll);
+    app.describe.scroll = app.describe.scroll.min(max_scroll);
+    let scroll = app.describe.scroll;
+
+    let query = app.describe.search.query.to_lowercase();
+    let cursor = app.describe.cursor;
 
-    let paragraph = Paragraph::new(lines.clone()).scroll((scroll as u16, 0));
+    let lines: Vec<Line> = doc_lines
+        .iter()
+        .enumerate()
+        .skip(scroll)
+        .take(visible_lines)
+        .map(|(index, doc_line)| {
+            let gutter = match doc_line.kind {
+                LineKind::Open { folded: true } if doc_line.is_foldable() => "▸ ",
+                LineKind::Open { folded: false } if doc_line.is_foldable() => "▾ ",
+                _ => "  ",
+            };
+            let mut line = match app.describe.format {
+                DocFormat::Json => highlight_json_line(&doc_line.text),
+                DocFormat::Yaml => highlight_yaml_line(&doc_line.text),
+            };
+            if !query.is_empty() {
+                line = highlight_matches(line, &query);
+            }
+            if find_link(&doc_line.text).is_some() {
+                // Resource references can be followed with Enter
+                for span in line.spans.iter_mut() {
+                    if span.content.contains("googleapis.com/") {
+                        span.style = span.style.add_modifier(Modifier::UNDERLINED);
+                    }
+                }
+            }
+            line.spans.insert(
+                0,
+                Span::styled(gutter, Style::default().fg(Color::DarkGray)),
+            );
+            if index == cursor {
+                line = line.style(Style::default().bg(Color::DarkGray));
+            }
+            line
+        })
+        .collect();
 
-    f.render_widget(paragraph, inner_area);
+    f.render_widget(Paragraph::new(lines), inner_area);
 
     // Render scrollbar if content exceeds visible area
     if total_lines > visible_lines {
@@ -497,6 +616,144 @@ fn render_describe_view(f: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+fn render_describe_search_bar(f: &mut Frame, app: &App, area: Rect) {
+    let search = &app.describe.search;
+    let style = if search.input_active {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+
+    let cursor = if search.input_active { "_" } else { "" };
+    let position = if search.matches.is_empty() {
+        if search.query.is_empty() {
+            String::new()
+        } else {
+            " (no matches)".to_string()
+        }
+    } else {
+        format!(" ({}/{})", search.current + 1, search.matches.len())
+    };
+
+    let paragraph = Paragraph::new(Line::from(vec![
+        Span::styled(format!("/{}{}", search.query, cursor), style),
+        Span::styled(position, Style::default().fg(Color::DarkGray)),
+    ]));
+    f.render_widget(paragraph, area);
+}
+
+/// Highlight case-insensitive occurrences of `query` (already lowercased)
+/// across the spans of a line
+fn highlight_matches(line: Line<'static>, query: &str) -> Line<'static> {
+    let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+    // ASCII lowercasing keeps byte offsets aligned with the original text
+    let haystack = text.to_ascii_lowercase();
+    let ranges: Vec<(usize, usize)> = haystack
+        .match_indices(query)
+        .map(|(start, m)| (start, start + m.len()))
+        .filter(|(start, end)| text.is_char_boundary(*start) && text.is_char_boundary(*end))
+        .collect();
+    if ranges.is_empty() {
+        return line;
+    }
+
+    let match_style = Style::default().fg(Color::Black).bg(Color::Yellow);
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for span in line.spans {
+        let content = span.content.to_string();
+        let span_start = offset;
+        let span_end = offset + content.len();
+        offset = span_end;
+
+        // Split the span at match boundaries
+        let mut pos = span_start;
+        for &(start, end) in &ranges {
+            let start = start.max(span_start);
+            let end = end.min(span_end);
+            if start >= end || start < pos {
+                continue;
+            }
+            if start > pos {
+                spans.push(Span::styled(
+                    content[pos - span_start..start - span_start].to_string(),
+                    span.style,
+                ));
+            }
+            spans.push(Span::styled(
+                content[start - span_start..end - span_start].to_string(),
+                match_style,
+            ));
+            pos = end;
+        }
+        if pos < span_end {
+            spans.push(Span::styled(
+                content[pos - span_start..].to_string(),
+                span.style,
+            ));
+        }
+    }
+    Line::from(spans)
+}
+
+/// Apply YAML syntax highlighting to a single line
+fn highlight_yaml_line(line: &str) -> Line<'static> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    spans.push(Span::raw(indent.to_string()));
+
+    let mut rest = trimmed;
+    if let Some(after_dash) = rest.strip_prefix('-') {
+        spans.push(Span::styled("-", Style::default().fg(Color::Yellow)));
+        rest = after_dash;
+    } else if let Some(pos) = yaml_key_end(rest) {
+        spans.push(Span::styled(
+            rest[..pos].to_string(),
+            Style::default().fg(Color::Cyan),
+        ));
+        spans.push(Span::styled(":", Style::default().fg(Color::White)));
+        rest = &rest[pos + 1..];
+    }
+
+    if !rest.is_empty() {
+        let value = rest.trim_start();
+        spans.push(Span::raw(rest[..rest.len() - value.len()].to_string()));
+        spans.push(Span::styled(value.to_string(), get_yaml_value_style(value)));
+    }
+
+    Line::from(spans)
+}
+
+/// Position of the ':' ending a mapping key, if the line starts with one
+fn yaml_key_end(text: &str) -> Option<usize> {
+    if text.starts_with('"') || text.starts_with('\'') {
+        // Quoted key: find the closing quote, then expect ':'
+        let quote = text.chars().next()?;
+        let close = text[1..].find(quote)? + 1;
+        return (text[close + 1..].starts_with(':')).then_some(close + 1);
+    }
+    text.find(": ")
+        .or_else(|| text.ends_with(':').then(|| text.len() - 1))
+}
+
+/// Get style for YAML scalar values
+fn get_yaml_value_style(value: &str) -> Style {
+    if value.starts_with('{') || value.starts_with('[') {
+        Style::default().fg(Color::Yellow)
+    } else if value == "null" || value == "~" {
+        Style::default().fg(Color::DarkGray)
+    } else if value == "true" || value == "false" {
+        Style::default().fg(Color::Magenta)
+    } else if value.parse::<f64>().is_ok() {
+        Style::default().fg(Color::LightBlue)
+    } else {
+        Style::default().fg(Color::Green)
+    }
+}
+
 /// Apply JSON syntax highlighting to a single line
 fn highlight_json_line(line: &str) -> Line<'static> {
     let mut spans: Vec<Span<'static>> = Vec::new();
@@ -660,12 +917,52 @@ fn render_crumb(f: &mut Frame, app: &App, area: Rect) {
 
     let status_text = if let Some(err) = &app.error_message {
         format!("Error: {}", err)
+    } else if let Some(status) = app.current_status() {
+        status.to_string()
     } else if let Some(ref toast) = toast_text {
         toast.clone()
     } else if app.loading {
         "Loading...".to_string()
     } else if app.mode == Mode::Describe {
-        "j/k: scroll | q/d/Esc: back".to_string()
+        let toggle = if app.describe.detail.is_some() {
+            " | Tab: list/detail"
+        } else {
+            ""
+        };
+        format!(
+            "j/k: move | Enter: follow link | za/zR/zM: fold | y: yaml | /: search | yy/Y: copy path/value{} | q: back",
+            toggle
+        )
+    } else if app.mode == Mode::Diff && app.pending_edit.is_some() {
+        "j/k: scroll | Enter: apply | e: edit again | q/Esc: cancel".to_string()
+    } else if app.mode == Mode::Diff {
+        "j/k: scroll | q/Esc: back".to_string()
+    } else if app.mode == Mode::Serial {
+        "j/k: scroll | f: follow | 1-4: port | /: search | n/N: next/prev | s: save | r: reload | q: back"
+            .to_string()
+    } else if app.mode == Mode::Firewall {
+        "j/k: move | /: check a connection | c: clear query | r: reload | q: back".to_string()
+    } else if app.mode == Mode::Tunnels {
+        "j/k: move | s: stop | r: restart | q: back".to_string()
+    } else if app.mode == Mode::RunOutput {
+        "j/k: host | J/K, ^d/^u: scroll | g/G: top/bottom | c: cancel | s: save | q: back (kills running)"
+            .to_string()
+    } else if app.mode == Mode::Bucket {
+        "j/k: scroll | v: toggle versioning | p: toggle public access prevention | r: reload | q: back"
+            .to_string()
+    } else if app.mode == Mode::Versions {
+        "j/k: move | s: restore this generation | d: delete generation | r: reload | q: back"
+            .to_string()
+    } else if app.mode == Mode::Preview {
+        let more = if app.preview.as_ref().is_some_and(|p| p.has_more()) {
+            " | m: load more"
+        } else {
+            ""
+        };
+        format!(
+            "j/k: scroll | ^d/^u: page | g/G: top/bottom{} | q: back",
+            more
+        )
     } else if app.filter_sort.filter_active {
         "Type to filter | Enter: apply | Esc: clear".to_string()
     } else {
@@ -674,6 +971,8 @@ fn render_crumb(f: &mut Frame, app: &App, area: Rect) {
 
     let style = if app.error_message.is_some() {
         Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+    } else if app.current_status().is_some() {
+        Style::default().fg(Color::Cyan)
     } else if toast_text.is_some() {
         // Use different colors based on notification status
         if let Some(notif) = app.notification_manager.current_toast() {
@@ -76,7 +76,7 @@ pub fn render(f: &mut Frame, app: &App) {
             NotificationStatus::Error(_) => ("✗", Color::Red),
         };
 
-        let action = notif.operation_type.display_name();
+        let action = notif.action_label();
         let resource = &notif.resource_id;
         let duration = notif.duration_display();
         let time_ago = format_time_ago(notif.created_at.elapsed());
@@ -92,7 +92,7 @@ pub fn render(f: &mut Frame, app: &App) {
 
     let widths = [
         Constraint::Length(8),
-        Constraint::Length(12),
+        Constraint::Length(16),
         Constraint::Min(20),
         Constraint::Length(12),
         Constraint::Length(12),
@@ -0,0 +1,162 @@
+//! Object Preview View
+//!
+//! First bytes of a Cloud Storage object: JSON highlighted, CSV as a table,
+//! text as is and binary content as a hex dump.
+
+use crate::app::App;
+use crate::preview::{ObjectPreview, PreviewFormat};
+use crate::resource::format_bytes;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+/// Widest column of a CSV table
+const MAX_COLUMN_WIDTH: usize = 40;
+
+pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
+    let Some(ref mut preview) = app.preview else {
+        return;
+    };
+
+    let gzip = if preview.gzip { ", gunzipped" } else { "" };
+    let title = format!(
+        " gs://{}/{} [{}{}] ",
+        preview.bucket,
+        preview.name,
+        preview.format.label(),
+        gzip
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+    f.render_widget(Paragraph::new(status_line(preview)), chunks[0]);
+
+    let area = chunks[1];
+    if preview.row_count() == 0 && preview.table.is_empty() {
+        f.render_widget(
+            Paragraph::new(" Empty object").style(Style::default().fg(Color::DarkGray)),
+            area,
+        );
+        return;
+    }
+    match preview.format {
+        PreviewFormat::Csv(_) => render_table(f, preview, area),
+        format => {
+            let visible = area.height as usize;
+            preview.viewport_height = visible.max(1);
+            let lines: Vec<Line> = preview
+                .lines
+                .iter()
+                .skip(preview.scroll)
+                .take(visible)
+                .map(|text| match format {
+                    PreviewFormat::Json => super::highlight_json_line(text),
+                    PreviewFormat::Hex => hex_line(text),
+                    _ => Line::from(text.clone()),
+                })
+                .collect();
+            f.render_widget(Paragraph::new(lines), area);
+        },
+    }
+}
+
+fn status_line(preview: &ObjectPreview) -> Line<'static> {
+    if let Some(ref error) = preview.error {
+        return Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red)));
+    }
+    let read = preview.data.len() as u64;
+    let mut text = match preview.size {
+        Some(size) => format!("{} of {}", format_bytes(read), format_bytes(size)),
+        None => format_bytes(read),
+    };
+    if preview.gzip {
+        text.push_str(&format!(
+            " ({} decompressed)",
+            format_bytes(preview.decoded_len as u64)
+        ));
+    }
+    if !preview.content_type.is_empty() {
+        text.push_str(&format!("  {}", preview.content_type));
+    }
+    let mut spans = vec![Span::styled(text, Style::default().fg(Color::DarkGray))];
+    if preview.decoded_truncated {
+        spans.push(Span::styled(
+            format!(
+                "  truncated at {} decompressed",
+                format_bytes(crate::preview::MAX_DECODED)
+            ),
+            Style::default().fg(Color::Yellow),
+        ));
+    } else if preview.has_more() {
+        spans.push(Span::styled(
+            "  truncated, m loads more",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Offset and ASCII columns dimmed around the bytes
+fn hex_line(text: &str) -> Line<'static> {
+    let (offset, rest) = text.split_at(text.len().min(8));
+    let (bytes, ascii) = match rest.find('|') {
+        Some(pos) => rest.split_at(pos),
+        None => (rest, ""),
+    };
+    Line::from(vec![
+        Span::styled(offset.to_string(), Style::default().fg(Color::DarkGray)),
+        Span::raw(bytes.to_string()),
+        Span::styled(ascii.to_string(), Style::default().fg(Color::Green)),
+    ])
+}
+
+fn render_table(f: &mut Frame, preview: &mut ObjectPreview, area: Rect) {
+    let visible = area.height.saturating_sub(1) as usize;
+    preview.viewport_height = visible.max(1);
+
+    let Some((header, rows)) = preview.table.split_first() else {
+        return;
+    };
+    let shown: Vec<&Vec<String>> = rows.iter().skip(preview.scroll).take(visible).collect();
+    let columns = preview.table.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<Constraint> = (0..columns)
+        .map(|col| {
+            let width = std::iter::once(header)
+                .chain(shown.iter().copied())
+                .filter_map(|row| row.get(col))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+                .clamp(1, MAX_COLUMN_WIDTH);
+            Constraint::Length(width as u16)
+        })
+        .collect();
+
+    let header = Row::new(header.iter().map(|h| Cell::from(h.clone()))).style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+    let rows: Vec<Row> = shown
+        .into_iter()
+        .map(|row| Row::new(row.iter().map(|cell| Cell::from(cell.clone()))))
+        .collect();
+    f.render_widget(Table::new(rows, widths).header(header), area);
+}
@@ -0,0 +1,177 @@
+//! Run Command Output View
+//!
+//! Hosts of a command run over SSH with their status and exit code, next to
+//! the output of the selected host.
+
+use crate::app::App;
+use crate::shell::run::{HostStatus, RunState};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
+    let Some(ref mut run) = app.run else {
+        return;
+    };
+
+    let iap = if run.use_iap { " [IAP]" } else { "" };
+    let title = format!(" Run command on {} instances{} ", run.hosts.len(), iap);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+    f.render_widget(Paragraph::new(summary_line(run)), rows[0]);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(40), Constraint::Min(20)])
+        .split(rows[1]);
+    render_hosts(f, run, columns[0]);
+    render_output(f, run, columns[1]);
+}
+
+fn status_color(status: &HostStatus) -> Color {
+    match status {
+        HostStatus::Queued | HostStatus::Cancelled => Color::DarkGray,
+        HostStatus::Running => Color::Yellow,
+        HostStatus::Exited(0) => Color::Green,
+        HostStatus::Exited(_) | HostStatus::Failed(_) => Color::Red,
+    }
+}
+
+fn summary_line(run: &RunState) -> Line<'static> {
+    let failed = run.failed_count();
+    let progress = format!(
+        "  {}/{} done, {} failed, {} at a time",
+        run.finished_count(),
+        run.hosts.len(),
+        failed,
+        run.parallelism
+    );
+    Line::from(vec![
+        Span::styled(
+            format!("$ {}", run.command),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            progress,
+            Style::default().fg(if failed > 0 {
+                Color::Red
+            } else {
+                Color::DarkGray
+            }),
+        ),
+    ])
+}
+
+fn render_hosts(f: &mut Frame, run: &RunState, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::RIGHT)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let visible = inner.height.saturating_sub(1) as usize;
+    let scroll = run.selected.saturating_sub(visible.saturating_sub(1));
+    let rows: Vec<Row> = run
+        .hosts
+        .iter()
+        .enumerate()
+        .skip(scroll)
+        .take(visible)
+        .map(|(index, host)| {
+            let mut style = Style::default();
+            if index == run.selected {
+                style = style.bg(Color::DarkGray);
+            }
+            Row::new(vec![
+                Cell::from(host.target.instance.clone()),
+                Cell::from(Span::styled(
+                    host.status.label(),
+                    Style::default().fg(status_color(&host.status)),
+                )),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let header = Row::new(vec![Cell::from("INSTANCE"), Cell::from("STATUS")]).style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+    let table = Table::new(rows, [Constraint::Min(20), Constraint::Length(10)]).header(header);
+    f.render_widget(table, inner);
+}
+
+fn render_output(f: &mut Frame, run: &mut RunState, area: Rect) {
+    let Some(host) = run.selected_host() else {
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(area);
+
+    let follow = if run.follow { " [follow]" } else { "" };
+    let mut header = vec![
+        Span::styled(
+            format!(" {} ({}) ", host.target.instance, host.target.zone),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            host.status.label(),
+            Style::default().fg(status_color(&host.status)),
+        ),
+        Span::styled(follow, Style::default().fg(Color::DarkGray)),
+    ];
+    if let HostStatus::Failed(ref reason) = host.status {
+        header.push(Span::styled(
+            format!("  {}", reason),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    f.render_widget(Paragraph::new(Line::from(header)), chunks[0]);
+
+    let area = chunks[1];
+    let visible = area.height as usize;
+    let total = host.lines.len();
+    let lines: Vec<Line> = if total == 0 {
+        let text = match host.status {
+            HostStatus::Queued => " Waiting for a free slot",
+            HostStatus::Running => " Connecting...",
+            _ => " No output",
+        };
+        vec![Line::from(Span::styled(
+            text,
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        host.lines
+            .iter()
+            .skip(run.scroll)
+            .take(visible)
+            .map(|l| Line::from(format!(" {}", l)))
+            .collect()
+    };
+    f.render_widget(Paragraph::new(lines), area);
+    run.viewport_height = visible.max(1);
+}
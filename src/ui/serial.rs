@@ -0,0 +1,139 @@
+//! Serial Output View
+//!
+//! Scrollable serial port output of an instance, with follow mode and search.
+
+use crate::app::App;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
+    let Some(ref mut state) = app.serial else {
+        return;
+    };
+
+    let follow = if state.follow { " [follow]" } else { "" };
+    let title = format!(
+        " Serial port {}: {} ({}){} ",
+        state.port, state.instance, state.zone, follow
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // Status line: search, dropped output or errors
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+    f.render_widget(Paragraph::new(status_line(state)), chunks[0]);
+
+    let area = chunks[1];
+    let visible = area.height as usize;
+    state.viewport_height = visible.max(1);
+    let max_scroll = state.lines.len().saturating_sub(visible);
+    if state.follow {
+        state.scroll = max_scroll;
+    }
+    state.scroll = state.scroll.min(max_scroll);
+
+    if state.lines.iter().all(|l| l.is_empty()) {
+        let text = if state.error.is_some() {
+            ""
+        } else {
+            " No output on this port yet"
+        };
+        f.render_widget(
+            Paragraph::new(text).style(Style::default().fg(Color::DarkGray)),
+            area,
+        );
+        return;
+    }
+
+    let query = state.search.query.to_lowercase();
+    let current_match = state.search.matches.get(state.search.current).copied();
+    let lines: Vec<Line> = state
+        .lines
+        .iter()
+        .enumerate()
+        .skip(state.scroll)
+        .take(visible)
+        .map(|(index, text)| {
+            let mut line = Line::from(text.clone());
+            if !query.is_empty() {
+                line = super::highlight_matches(line, &query);
+            }
+            if Some(index) == current_match {
+                line = line.style(Style::default().bg(Color::DarkGray));
+            }
+            line
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), area);
+
+    if state.lines.len() > visible {
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"));
+        let mut scrollbar_state = ScrollbarState::new(max_scroll + visible).position(state.scroll);
+        f.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+    }
+}
+
+fn status_line(state: &crate::serial::SerialState) -> Line<'static> {
+    let search = &state.search;
+    if search.input_active || !search.query.is_empty() {
+        let cursor = if search.input_active { "_" } else { "" };
+        let position = if search.matches.is_empty() {
+            " (no matches)".to_string()
+        } else {
+            format!(" ({}/{})", search.current + 1, search.matches.len())
+        };
+        let style = if search.input_active {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        return Line::from(vec![
+            Span::styled(format!("/{}{}", search.query, cursor), style),
+            Span::styled(position, Style::default().fg(Color::DarkGray)),
+        ]);
+    }
+
+    if let Some(ref error) = state.error {
+        return Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red)));
+    }
+
+    let mut spans = vec![Span::styled(
+        format!(
+            "{} lines, next offset {}",
+            state.lines.len(),
+            state.start_offset()
+        ),
+        Style::default().fg(Color::DarkGray),
+    )];
+    if state.skipped > 0 {
+        spans.push(Span::styled(
+            format!(
+                "  ({} bytes dropped from the instance buffer before they were read)",
+                state.skipped
+            ),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    Line::from(spans)
+}
@@ -0,0 +1,105 @@
+//! IAP Tunnels Panel
+//!
+//! Tunnels started with `T` on an instance, with their local port, target
+//! and uptime.
+
+use crate::app::App;
+use crate::tunnel::TunnelStatus;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let manager = &app.tunnels;
+    let title = format!(" IAP tunnels [{} running] ", manager.running_count());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if manager.tunnels.is_empty() {
+        f.render_widget(
+            Paragraph::new(" No tunnels. Press T on a VM instance to start one.")
+                .style(Style::default().fg(Color::DarkGray)),
+            inner,
+        );
+        return;
+    }
+
+    let header = Row::new(vec![
+        Cell::from("LOCAL PORT"),
+        Cell::from("TARGET"),
+        Cell::from("ZONE"),
+        Cell::from("STATUS"),
+        Cell::from("UPTIME"),
+        Cell::from("MESSAGE"),
+    ])
+    .style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let visible = inner.height.saturating_sub(1) as usize;
+    let scroll = manager.selected.saturating_sub(visible.saturating_sub(1));
+    let rows: Vec<Row> = manager
+        .tunnels
+        .iter()
+        .enumerate()
+        .skip(scroll)
+        .take(visible)
+        .map(|(index, tunnel)| {
+            let (status, color, message) = match tunnel.status {
+                TunnelStatus::Running => {
+                    ("RUNNING".to_string(), Color::Green, tunnel.last_message())
+                },
+                TunnelStatus::Exited(ref reason) => {
+                    ("EXITED".to_string(), Color::Red, reason.clone())
+                },
+            };
+            let status = if tunnel.restarts > 0 {
+                format!("{} [{} restarts]", status, tunnel.restarts)
+            } else {
+                status
+            };
+            let mut style = Style::default();
+            if index == manager.selected {
+                style = style.bg(Color::DarkGray);
+            }
+            Row::new(vec![
+                Cell::from(format!("localhost:{}", tunnel.spec.local_port)),
+                Cell::from(tunnel.spec.target()),
+                Cell::from(tunnel.spec.zone.clone()),
+                Cell::from(Span::styled(status, Style::default().fg(color))),
+                Cell::from(tunnel.uptime_display()),
+                Cell::from(Span::styled(message, Style::default().fg(Color::DarkGray))),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(16),
+            Constraint::Length(30),
+            Constraint::Length(18),
+            Constraint::Length(22),
+            Constraint::Length(8),
+            Constraint::Min(20),
+        ],
+    )
+    .header(header);
+    f.render_widget(table, inner);
+}
@@ -0,0 +1,133 @@
+//! Object Versions View
+//!
+//! Generations of an object, newest first, the live one in green.
+
+use crate::app::App;
+use crate::versions::{generation, is_live, size_display};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let Some(ref view) = app.versions else {
+        return;
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(Span::styled(
+            format!(" Versions of gs://{}/{} ", view.bucket, view.name),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+
+    let status = if let Some(ref error) = view.error {
+        Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red)))
+    } else if !view.loaded {
+        Line::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::DarkGray),
+        ))
+    } else if view.versions.is_empty() {
+        Line::from(Span::styled(
+            "No generations found",
+            Style::default().fg(Color::DarkGray),
+        ))
+    } else {
+        let live = match view.live_generation() {
+            Some(_) => Span::styled("live", Style::default().fg(Color::Green)),
+            None => Span::styled(
+                "deleted",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+        };
+        Line::from(vec![
+            Span::raw("Object is "),
+            live,
+            Span::raw(format!(
+                ", {} noncurrent generation(s)",
+                view.noncurrent_count()
+            )),
+        ])
+    };
+    f.render_widget(Paragraph::new(status), chunks[0]);
+
+    let area = chunks[1];
+    let header = Row::new(vec![
+        Cell::from("GENERATION"),
+        Cell::from("STATE"),
+        Cell::from("SIZE"),
+        Cell::from("CREATED"),
+        Cell::from("NONCURRENT SINCE"),
+        Cell::from("STORAGE CLASS"),
+    ])
+    .style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let field = |version: &serde_json::Value, key: &str| {
+        version
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or("-")
+            .to_string()
+    };
+    let visible = area.height.saturating_sub(1) as usize;
+    let scroll = view.selected.saturating_sub(visible.saturating_sub(1));
+    let rows: Vec<Row> = view
+        .versions
+        .iter()
+        .enumerate()
+        .skip(scroll)
+        .take(visible)
+        .map(|(index, version)| {
+            let (state, color) = if is_live(version) {
+                ("LIVE", Color::Green)
+            } else {
+                ("NONCURRENT", Color::DarkGray)
+            };
+            let mut style = Style::default();
+            if index == view.selected {
+                style = style.bg(Color::DarkGray);
+            }
+            Row::new(vec![
+                Cell::from(generation(version).to_string()),
+                Cell::from(Span::styled(state, Style::default().fg(color))),
+                Cell::from(size_display(version)),
+                Cell::from(field(version, "timeCreated")),
+                Cell::from(field(version, "timeDeleted")),
+                Cell::from(field(version, "storageClass")),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(18),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(26),
+            Constraint::Length(26),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header);
+    f.render_widget(table, area);
+}
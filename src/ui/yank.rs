@@ -0,0 +1,117 @@
+//! Yank Menu Overlay
+//!
+//! Lets users pick which value of the selected row(s) to copy to the clipboard.
+
+use crate::app::App;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let Some(ref menu) = app.yank_menu else {
+        return;
+    };
+
+    let popup_area = centered_rect(60, 40, area);
+    f.render_widget(Clear, popup_area);
+
+    let title = if menu.item_count > 1 {
+        format!(" Copy ({} rows) ", menu.item_count)
+    } else {
+        " Copy ".to_string()
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .title_alignment(Alignment::Center);
+
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+
+    let help = Line::from(vec![
+        Span::styled(" key", Style::default().fg(Color::Yellow)),
+        Span::styled(":copy ", Style::default().fg(Color::DarkGray)),
+        Span::styled("Enter", Style::default().fg(Color::Yellow)),
+        Span::styled(":copy selected ", Style::default().fg(Color::DarkGray)),
+        Span::styled("Esc", Style::default().fg(Color::Yellow)),
+        Span::styled(":cancel", Style::default().fg(Color::DarkGray)),
+    ]);
+    f.render_widget(Paragraph::new(help), chunks[0]);
+
+    if menu.options.is_empty() {
+        f.render_widget(
+            Paragraph::new(" Nothing to copy").style(Style::default().fg(Color::DarkGray)),
+            chunks[1],
+        );
+        return;
+    }
+
+    let items: Vec<ListItem> = menu
+        .options
+        .iter()
+        .map(|(field, preview)| {
+            // Only the first line of multi-line values fits the preview
+            let preview = preview.lines().next().unwrap_or_default();
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" {} ", field.key()),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("{:<12} ", field.label()),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(preview.to_string(), Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(menu.selected));
+
+    f.render_stateful_widget(list, chunks[1], &mut list_state);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
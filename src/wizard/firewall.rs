@@ -0,0 +1,307 @@
+//! Firewall Rule Forms
+//!
+//! Creating and editing VPC firewall rules; the parsing, bodies and impact
+//! preview are in [`crate::firewall`].
+
+use super::{items, parse_tags, str_field};
+use crate::firewall::{
+    impact_instance, parse_cidrs, parse_firewall_protocols, parse_priority, parse_service_accounts,
+    RuleEdit,
+};
+use crate::form::{FormField, FormPurpose, FormState, SelectOption};
+use crate::shell::validate_gcp_resource_name;
+use serde_json::{json, Value};
+
+/// Network options from a `networks.list` response (value: network link)
+pub fn network_options(response: &Value) -> Vec<SelectOption> {
+    items(response)
+        .iter()
+        .filter(|n| !str_field(n, "selfLink").is_empty())
+        .map(|n| SelectOption::new(str_field(n, "selfLink"), str_field(n, "name")))
+        .collect()
+}
+
+fn join_strings(value: Option<&Value>) -> String {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .unwrap_or_default()
+}
+
+/// `allowed`/`denied` list of a rule in the form's syntax
+fn protocols_input(list: Option<&Value>) -> String {
+    let mut entries = Vec::new();
+    for protocol in list.and_then(|v| v.as_array()).into_iter().flatten() {
+        let name = str_field(protocol, "IPProtocol");
+        let ports: Vec<&str> = protocol
+            .get("ports")
+            .and_then(|v| v.as_array())
+            .map(|ports| ports.iter().filter_map(|p| p.as_str()).collect())
+            .unwrap_or_default();
+        if ports.is_empty() {
+            entries.push(name.to_string());
+        } else {
+            entries.extend(ports.iter().map(|port| format!("{}:{}", name, port)));
+        }
+    }
+    entries.join(",")
+}
+
+/// Fields of a rule that can be changed after creation (direction, action,
+/// name and network are fixed)
+fn firewall_rule_fields(rule: &Value, step: usize) -> Vec<FormField> {
+    let ingress = str_field(rule, "direction") != "EGRESS";
+    let action = if rule.get("denied").is_some() {
+        "denied"
+    } else {
+        "allowed"
+    };
+    let ranges_key = if ingress {
+        "sourceRanges"
+    } else {
+        "destinationRanges"
+    };
+    vec![
+        FormField::number(
+            "priority",
+            "Priority",
+            rule.get("priority")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(1000),
+        )
+        .required()
+        .with_help("0-65535; lower numbers are evaluated first")
+        .in_step(step),
+        FormField::text(
+            "protocols",
+            "Protocols/ports",
+            &protocols_input(rule.get(action)),
+        )
+        .required()
+        .with_help("e.g. tcp:22,tcp:8000-8080,icmp or all")
+        .with_validator(|v| parse_firewall_protocols(v).map(|_| ()))
+        .in_step(step),
+        FormField::toggle(
+            "disabled",
+            "Disabled",
+            rule.get("disabled").and_then(|v| v.as_bool()) == Some(true),
+        )
+        .in_step(step),
+        FormField::toggle(
+            "logging",
+            "Logging",
+            rule.pointer("/logConfig/enable").and_then(|v| v.as_bool()) == Some(true),
+        )
+        .in_step(step),
+        FormField::text("ranges", "IP ranges", &join_strings(rule.get(ranges_key)))
+            .with_help("Source ranges (ingress) or destination ranges (egress), comma-separated")
+            .with_validator(|v| parse_cidrs(v).map(|_| ()))
+            .in_step(step + 1),
+        FormField::text(
+            "source_tags",
+            "Source tags",
+            &join_strings(rule.get("sourceTags")),
+        )
+        .with_help("Ingress only: instances with these network tags")
+        .with_validator(|v| parse_tags(v).map(|_| ()))
+        .in_step(step + 1),
+        FormField::text(
+            "target_tags",
+            "Target tags",
+            &join_strings(rule.get("targetTags")),
+        )
+        .with_help("Instances the rule applies to; empty with no service accounts: all")
+        .with_validator(|v| parse_tags(v).map(|_| ()))
+        .in_step(step + 1),
+        FormField::text(
+            "target_service_accounts",
+            "Target SAs",
+            &join_strings(rule.get("targetServiceAccounts")),
+        )
+        .with_help("Service account emails, instead of target tags")
+        .with_validator(|v| parse_service_accounts(v).map(|_| ()))
+        .in_step(step + 1),
+    ]
+}
+
+/// Instance fields used for the impact preview, from list items
+fn impact_instances(instances: &[Value]) -> Vec<Value> {
+    instances.iter().map(impact_instance).collect()
+}
+
+/// Build the firewall rule creation form. `instances` are the loaded
+/// instances, for the impact preview.
+pub fn create_firewall_form(
+    networks: Vec<SelectOption>,
+    default_network: &str,
+    instances: &[Value],
+) -> FormState {
+    let mut fields = vec![
+        FormField::text("name", "Name", "")
+            .required()
+            .with_validator(|v| {
+                validate_gcp_resource_name(v, "Firewall rule").map_err(|e| e.to_string())
+            }),
+        FormField::select("network", "Network", networks, default_network).required(),
+        FormField::select(
+            "direction",
+            "Direction",
+            vec![
+                SelectOption::plain("INGRESS"),
+                SelectOption::plain("EGRESS"),
+            ],
+            "INGRESS",
+        ),
+        FormField::select(
+            "action",
+            "Action",
+            vec![
+                SelectOption::new("allowed", "Allow"),
+                SelectOption::new("denied", "Deny"),
+            ],
+            "allowed",
+        ),
+    ];
+    fields.extend(firewall_rule_fields(&json!({}), 1));
+
+    FormState::new(
+        "Create firewall rule",
+        FormPurpose::CreateFirewall,
+        &["Rule", "Match", "Sources & targets"],
+        fields,
+    )
+    .with_context(json!({ "instances": impact_instances(instances) }))
+}
+
+/// Build the edit form of a rule, pre-filled with its current values
+pub fn edit_firewall_form(rule: &Value, instances: &[Value]) -> Result<FormState, String> {
+    let name = str_field(rule, "name");
+    if name.is_empty() {
+        return Err("Firewall rule name unknown".to_string());
+    }
+    let direction = match str_field(rule, "direction") {
+        "" => "INGRESS",
+        direction => direction,
+    };
+    let action = if rule.get("denied").is_some() {
+        "deny"
+    } else {
+        "allow"
+    };
+
+    Ok(FormState::new(
+        &format!("Edit firewall rule {} ({} {})", name, direction, action),
+        FormPurpose::EditFirewall,
+        &["Match", "Sources & targets"],
+        firewall_rule_fields(rule, 0),
+    )
+    .with_context(json!({
+        "name": name,
+        "current": rule,
+        "instances": impact_instances(instances),
+    })))
+}
+
+/// Rule body from the form: the full rule when creating, the changeable
+/// fields (cleared ones as empty lists) when patching
+pub fn firewall_body(form: &FormState) -> Result<Value, String> {
+    let current = &form.context["current"];
+    let (ingress, action) = if form.purpose == FormPurpose::EditFirewall {
+        (
+            str_field(current, "direction") != "EGRESS",
+            if current.get("denied").is_some() {
+                "denied"
+            } else {
+                "allowed"
+            },
+        )
+    } else {
+        (form.value("direction") != "EGRESS", form.value("action"))
+    };
+
+    let rule = RuleEdit {
+        ingress,
+        action: action.to_string(),
+        priority: parse_priority(form.value("priority"))?,
+        protocols: parse_firewall_protocols(form.value("protocols"))?,
+        ranges: parse_cidrs(form.value("ranges"))?,
+        source_tags: parse_tags(form.value("source_tags"))?,
+        target_tags: parse_tags(form.value("target_tags"))?,
+        target_service_accounts: parse_service_accounts(form.value("target_service_accounts"))?,
+        disabled: form.is_on("disabled"),
+        logging: form.is_on("logging"),
+    };
+    if form.purpose == FormPurpose::CreateFirewall {
+        rule.insert_body(form.value("name"), form.value("network"))
+    } else {
+        rule.patch_body()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wizard::preview_notes;
+
+    fn firewall_instances() -> Vec<Value> {
+        let network = "https://www.googleapis.com/compute/v1/projects/p/global/networks/default";
+        vec![
+            json!({"name": "web-01", "networkInterfaces": [{"network": network}], "tags": {"items": ["web"]}}),
+            json!({"name": "db-01", "networkInterfaces": [{"network": network}], "tags": {"items": ["db"]}}),
+            json!({"name": "other", "networkInterfaces": [{"network": "projects/p/global/networks/vpc2"}], "tags": {"items": ["web", "db"]}}),
+        ]
+    }
+
+    #[test]
+    fn test_create_firewall_requires_sources() {
+        let networks = vec![SelectOption::new(
+            "https://www.googleapis.com/compute/v1/projects/p/global/networks/default",
+            "default",
+        )];
+        let mut form = create_firewall_form(networks, "", &firewall_instances());
+        form.field_mut("name").unwrap().value = "allow-web".to_string();
+        form.field_mut("protocols").unwrap().value = "tcp:80,tcp:443".to_string();
+        form.field_mut("target_tags").unwrap().value = "web".to_string();
+        assert!(firewall_body(&form).is_err());
+
+        form.field_mut("ranges").unwrap().value = "0.0.0.0/0".to_string();
+        let body = firewall_body(&form).unwrap();
+        assert_eq!(body["direction"], "INGRESS");
+        assert_eq!(body["allowed"][0]["ports"], json!(["80", "443"]));
+        let notes = preview_notes(&form, &body);
+        assert!(notes[0].starts_with("WARNING"));
+        assert_eq!(notes[1], "Newly matched instances (1 of 3 loaded): web-01");
+    }
+
+    #[test]
+    fn test_edit_firewall_shows_newly_matched() {
+        let rule = json!({
+            "name": "allow-ssh",
+            "network": "https://www.googleapis.com/compute/v1/projects/p/global/networks/default",
+            "direction": "INGRESS",
+            "priority": 1000,
+            "allowed": [{"IPProtocol": "tcp", "ports": ["22"]}],
+            "sourceRanges": ["35.235.240.0/20"],
+            "targetTags": ["web"],
+        });
+        let mut form = edit_firewall_form(&rule, &firewall_instances()).unwrap();
+        assert_eq!(form.value("protocols"), "tcp:22");
+        assert_eq!(form.value("ranges"), "35.235.240.0/20");
+        form.field_mut("target_tags").unwrap().value = "web,db".to_string();
+
+        let body = firewall_body(&form).unwrap();
+        assert!(body.get("name").is_none());
+        assert_eq!(body["sourceTags"], json!([]));
+        let notes = preview_notes(&form, &body);
+        assert_eq!(
+            notes,
+            vec!["Newly matched instances (1 of 3 loaded): db-01"]
+        );
+    }
+}
@@ -0,0 +1,1484 @@
+//! Wizards
+//!
+//! Form definitions for creating and changing resources, the conversion of
+//! API list responses into select options, and the requests built from the
+//! submitted values.
+//!
+//! - [`firewall`] - Creating and editing VPC firewall rules
+//! - [`run`] - Running a command on instances
+//! - [`scp`] - Copying files from or to an instance
+//! - [`transfer`] - Downloading and uploading objects
+//! - [`tunnel`] - Starting IAP tunnels
+
+pub mod firewall;
+pub mod run;
+pub mod scp;
+pub mod transfer;
+pub mod tunnel;
+
+use crate::app::PendingAction;
+use crate::chain::{ChainStep, OperationChain};
+use crate::firewall::impact_notes;
+use crate::form::{FormField, FormPurpose, FormState, SelectOption};
+use crate::shell::validate_gcp_resource_name;
+use serde_json::{json, Map, Value};
+
+/// Public projects whose image families are offered for boot disks
+pub const IMAGE_PROJECTS: &[&str] = &[
+    "debian-cloud",
+    "ubuntu-os-cloud",
+    "rocky-linux-cloud",
+    "rhel-cloud",
+    "cos-cloud",
+    "windows-cloud",
+];
+
+/// Machine type selected by default when available in the zone
+pub const DEFAULT_MACHINE_TYPE: &str = "e2-medium";
+
+/// Image family selected by default when available
+pub const DEFAULT_IMAGE: &str = "debian-cloud/debian-12";
+
+/// Minimum boot disk size in GB
+const MIN_DISK_SIZE_GB: u64 = 10;
+
+/// Scope granted to the instance service account (access is then governed by IAM)
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+
+/// Region of a zone (e.g. `us-central1-a` -> `us-central1`)
+pub fn zone_region(zone: &str) -> &str {
+    zone.rsplit_once('-')
+        .map(|(region, _)| region)
+        .unwrap_or(zone)
+}
+
+/// Build the VM creation form. Machine types, images and subnets are filled
+/// in later from live API data.
+pub fn create_instance_form(zones: &[String], default_zone: &str) -> FormState {
+    let zone_options: Vec<SelectOption> = zones
+        .iter()
+        .filter(|z| z.as_str() != "all")
+        .map(SelectOption::plain)
+        .collect();
+
+    let fields = vec![
+        FormField::text("name", "Name", "")
+            .required()
+            .with_validator(|v| {
+                validate_gcp_resource_name(v, "Instance").map_err(|e| e.to_string())
+            }),
+        FormField::select("zone", "Zone", zone_options, default_zone).required(),
+        FormField::select("machine_type", "Machine type", Vec::new(), "")
+            .required()
+            .with_help("Type to filter, ←/→ to choose")
+            .in_step(1),
+        FormField::select("image", "Image family", Vec::new(), "")
+            .required()
+            .with_help("Latest non-deprecated image of the family")
+            .in_step(2),
+        FormField::select("disk_type", "Disk type", disk_type_options(), "pd-balanced").in_step(2),
+        FormField::number("disk_size", "Disk size (GB)", MIN_DISK_SIZE_GB)
+            .required()
+            .with_validator(validate_disk_size)
+            .in_step(2),
+        FormField::select("subnet", "Subnet", Vec::new(), "").in_step(3),
+        FormField::toggle("external_ip", "External IP", true).in_step(3),
+        FormField::text("tags", "Network tags", "")
+            .with_help("Comma-separated, e.g. http-server,https-server")
+            .with_validator(|v| parse_tags(v).map(|_| ()))
+            .in_step(3),
+        FormField::text("labels", "Labels", "")
+            .with_help("Comma-separated key=value pairs")
+            .with_validator(|v| parse_labels(v).map(|_| ()))
+            .in_step(4),
+        FormField::text("service_account", "Service account", "default")
+            .with_help("'default', 'none' or a service account email")
+            .in_step(4),
+        FormField::select(
+            "provisioning",
+            "Provisioning",
+            vec![
+                SelectOption::new("standard", "Standard"),
+                SelectOption::new("spot", "Spot (stops when preempted)"),
+                SelectOption::new("preemptible", "Preemptible (legacy, max 24h)"),
+            ],
+            "standard",
+        )
+        .in_step(4),
+    ];
+
+    FormState::new(
+        "Create VM instance",
+        FormPurpose::CreateInstance,
+        &["Basics", "Machine", "Boot disk", "Network", "Options"],
+        fields,
+    )
+}
+
+/// Persistent disk types offered for new disks
+fn disk_type_options() -> Vec<SelectOption> {
+    vec![
+        SelectOption::new("pd-balanced", "pd-balanced  Balanced persistent disk"),
+        SelectOption::new("pd-ssd", "pd-ssd  SSD persistent disk"),
+        SelectOption::new("pd-standard", "pd-standard  Standard persistent disk"),
+    ]
+}
+
+fn validate_disk_size(value: &str) -> Result<(), String> {
+    match value.parse::<u64>() {
+        Ok(size) if size >= MIN_DISK_SIZE_GB => Ok(()),
+        _ => Err(format!(
+            "Disk size must be at least {} GB",
+            MIN_DISK_SIZE_GB
+        )),
+    }
+}
+
+fn items(response: &Value) -> &[Value] {
+    response
+        .get("items")
+        .and_then(|v| v.as_array())
+        .map(|v| v.as_slice())
+        .unwrap_or_default()
+}
+
+fn str_field<'a>(item: &'a Value, key: &str) -> &'a str {
+    item.get(key).and_then(|v| v.as_str()).unwrap_or_default()
+}
+
+/// Machine type options from a `machineTypes.list` response, showing vCPUs
+/// and memory
+pub fn machine_type_options(response: &Value) -> Vec<SelectOption> {
+    let mut options: Vec<SelectOption> = items(response)
+        .iter()
+        .filter(|mt| mt.get("deprecated").is_none())
+        .filter_map(|mt| {
+            let name = mt.get("name")?.as_str()?;
+            let cpus = mt.get("guestCpus").and_then(|v| v.as_u64()).unwrap_or(0);
+            let memory_mb = mt.get("memoryMb").and_then(|v| v.as_u64()).unwrap_or(0);
+            Some(SelectOption::new(
+                name,
+                format!(
+                    "{:<24} {} vCPU, {} GB",
+                    name,
+                    cpus,
+                    format_memory_gb(memory_mb)
+                ),
+            ))
+        })
+        .collect();
+    options.sort_by(|a, b| a.value.cmp(&b.value));
+    options
+}
+
+/// Memory in GB with at most one decimal (e.g. 4096 -> "4", 1740 -> "1.7")
+fn format_memory_gb(memory_mb: u64) -> String {
+    let gb = memory_mb as f64 / 1024.0;
+    if gb.fract() < 0.05 {
+        format!("{:.0}", gb)
+    } else {
+        format!("{:.1}", gb)
+    }
+}
+
+/// Image family options (`project/family`) from an `images.list` response
+/// of a public image project, skipping deprecated images
+pub fn image_family_options(project: &str, response: &Value) -> Vec<SelectOption> {
+    let mut families: Vec<&str> = items(response)
+        .iter()
+        .filter(|image| image.get("deprecated").is_none())
+        .map(|image| str_field(image, "family"))
+        .filter(|family| !family.is_empty())
+        .collect();
+    families.sort();
+    families.dedup();
+    families
+        .into_iter()
+        .map(|family| SelectOption::plain(format!("{}/{}", project, family)))
+        .collect()
+}
+
+/// Subnet options from a `subnetworks.list` response (value is the selfLink).
+/// The first option falls back to the project's default network.
+pub fn subnet_options(response: &Value) -> Vec<SelectOption> {
+    let mut options = vec![SelectOption::new("", "(default network)")];
+    let mut subnets: Vec<SelectOption> = items(response)
+        .iter()
+        .filter_map(|subnet| {
+            let link = subnet.get("selfLink")?.as_str()?;
+            let network = str_field(subnet, "network")
+                .rsplit('/')
+                .next()
+                .unwrap_or("");
+            Some(SelectOption::new(
+                link,
+                format!(
+                    "{:<24} {:<18} {}",
+                    str_field(subnet, "name"),
+                    str_field(subnet, "ipCidrRange"),
+                    network
+                ),
+            ))
+        })
+        .collect();
+    subnets.sort_by(|a, b| a.label.cmp(&b.label));
+    options.extend(subnets);
+    options
+}
+
+/// Parse comma-separated network tags
+pub fn parse_tags(input: &str) -> Result<Vec<String>, String> {
+    split_list(input)
+        .map(|tag| {
+            validate_gcp_resource_name(tag, "Network tag")
+                .map(|_| tag.to_string())
+                .map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+/// Parse comma-separated `key=value` labels
+pub fn parse_labels(input: &str) -> Result<Map<String, Value>, String> {
+    let mut labels = Map::new();
+    for pair in split_list(input) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let (key, value) = (key.trim(), value.trim());
+        validate_label(key, value)?;
+        labels.insert(key.to_string(), Value::String(value.to_string()));
+    }
+    Ok(labels)
+}
+
+/// Check a label key and value against the GCP label rules
+pub fn validate_label(key: &str, value: &str) -> Result<(), String> {
+    if !is_label_key(key) {
+        return Err(format!(
+            "Invalid label key '{}': use lowercase letters, digits, '-' or '_', starting with a letter",
+            key
+        ));
+    }
+    if !value.chars().all(is_label_char) || value.len() > 63 {
+        return Err(format!("Invalid value for label '{}'", key));
+    }
+    Ok(())
+}
+
+fn split_list(input: &str) -> impl Iterator<Item = &str> {
+    input.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+fn is_label_char(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_'
+}
+
+fn is_label_key(key: &str) -> bool {
+    key.len() <= 63
+        && key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key.chars().all(is_label_char)
+}
+
+/// Build the `instances.insert` request body from the VM creation form
+pub fn instance_insert_body(form: &FormState) -> Result<Value, String> {
+    let zone = form.value("zone");
+    let (image_project, image_family) = form
+        .value("image")
+        .split_once('/')
+        .ok_or_else(|| "Select an image family".to_string())?;
+    let disk_size = form
+        .value("disk_size")
+        .parse::<u64>()
+        .map_err(|_| "Disk size must be a number".to_string())?;
+
+    let mut interface = Map::new();
+    match form.value("subnet") {
+        "" => {
+            interface.insert("network".into(), json!("global/networks/default"));
+        },
+        subnet => {
+            interface.insert("subnetwork".into(), json!(subnet));
+        },
+    }
+    if form.is_on("external_ip") {
+        interface.insert(
+            "accessConfigs".into(),
+            json!([{ "name": "External NAT", "type": "ONE_TO_ONE_NAT" }]),
+        );
+    }
+
+    let mut body = json!({
+        "name": form.value("name"),
+        "machineType": format!("zones/{}/machineTypes/{}", zone, form.value("machine_type")),
+        "disks": [{
+            "boot": true,
+            "autoDelete": true,
+            "initializeParams": {
+                "sourceImage": format!("projects/{}/global/images/family/{}", image_project, image_family),
+                "diskSizeGb": disk_size.to_string(),
+                "diskType": format!("zones/{}/diskTypes/{}", zone, form.value("disk_type")),
+            },
+        }],
+        "networkInterfaces": [interface],
+    });
+
+    let tags = parse_tags(form.value("tags"))?;
+    if !tags.is_empty() {
+        body["tags"] = json!({ "items": tags });
+    }
+
+    let labels = parse_labels(form.value("labels"))?;
+    if !labels.is_empty() {
+        body["labels"] = Value::Object(labels);
+    }
+
+    match form.value("service_account").trim() {
+        "none" => {},
+        "" | "default" => {
+            body["serviceAccounts"] =
+                json!([{ "email": "default", "scopes": [CLOUD_PLATFORM_SCOPE] }]);
+        },
+        email if email.contains('@') => {
+            body["serviceAccounts"] = json!([{ "email": email, "scopes": [CLOUD_PLATFORM_SCOPE] }]);
+        },
+        other => return Err(format!("Invalid service account '{}'", other)),
+    }
+
+    match form.value("provisioning") {
+        "spot" => {
+            body["scheduling"] = json!({
+                "provisioningModel": "SPOT",
+                "instanceTerminationAction": "STOP",
+                "automaticRestart": false,
+                "onHostMaintenance": "TERMINATE",
+            });
+        },
+        "preemptible" => {
+            body["scheduling"] = json!({
+                "preemptible": true,
+                "automaticRestart": false,
+                "onHostMaintenance": "TERMINATE",
+            });
+        },
+        _ => {},
+    }
+
+    Ok(body)
+}
+
+// =============================================================================
+// Resize (change machine type)
+// =============================================================================
+
+/// Build the resize form for an instance (a list item). Only running or
+/// stopped instances can be resized.
+pub fn resize_instance_form(instance: &Value) -> Result<FormState, String> {
+    let name = str_field(instance, "name");
+    let zone = str_field(instance, "zone_short");
+    let current = str_field(instance, "machineType_short");
+    let status = str_field(instance, "status");
+    if name.is_empty() || zone.is_empty() || current.is_empty() {
+        return Err("Instance name, zone or machine type unknown".to_string());
+    }
+    if status != "RUNNING" && status != "TERMINATED" {
+        return Err(format!(
+            "Cannot resize {} while it is {}: it must be running or stopped",
+            name, status
+        ));
+    }
+
+    let summary = crate::resource::machine_type_summary(current);
+    let fields = vec![
+        FormField::select("machine_type", "Machine type", Vec::new(), "")
+            .required()
+            .with_help("Type to filter, ←/→ to choose"),
+        FormField::toggle("start", "Start afterwards", status == "RUNNING")
+            .with_help("The instance is stopped while its machine type changes"),
+    ];
+    let title = if summary.is_empty() {
+        format!("Resize {} ({})", name, current)
+    } else {
+        format!("Resize {} ({}: {})", name, current, summary)
+    };
+
+    Ok(FormState::new(
+        &title,
+        FormPurpose::ResizeInstance,
+        &["Machine type"],
+        fields,
+    )
+    .with_context(json!({
+        "name": name,
+        "zone": zone,
+        "machine_type": current,
+        "status": status,
+    })))
+}
+
+/// Machine type options for a resize, marking the current type
+pub fn resize_machine_type_options(response: &Value, current: &str) -> Vec<SelectOption> {
+    machine_type_options(response)
+        .into_iter()
+        .map(|mut option| {
+            if option.value == current {
+                option.label.push_str("  (current)");
+            }
+            option
+        })
+        .collect()
+}
+
+/// Steps of a resize, as method names (for the preview and the chain)
+fn resize_steps(form: &FormState) -> Vec<&'static str> {
+    let mut steps = Vec::new();
+    if form.context_str("status") == "RUNNING" {
+        steps.push("stop_instance");
+    }
+    steps.push("set_machine_type");
+    if form.is_on("start") {
+        steps.push("start_instance");
+    }
+    steps
+}
+
+/// Preview of a resize: the steps and the `setMachineType` request body
+pub fn resize_plan(form: &FormState) -> Result<Value, String> {
+    let current = form.context_str("machine_type");
+    let target = form.value("machine_type");
+    if target == current {
+        return Err(format!(
+            "{} already has machine type {}",
+            form.context_str("name"),
+            current
+        ));
+    }
+    Ok(json!({
+        "instance": form.context_str("name"),
+        "zone": form.context_str("zone"),
+        "steps": resize_steps(form),
+        "setMachineType": {
+            "machineType": format!("zones/{}/machineTypes/{}", form.context_str("zone"), target),
+        },
+        "previousMachineType": current,
+    }))
+}
+
+/// Operation chain running a resize, with the state left behind by a failure
+/// of each step
+pub fn resize_chain(form: &FormState) -> OperationChain {
+    let name = form.context_str("name");
+    let zone = form.context_str("zone");
+    let current = form.context_str("machine_type");
+    let target = form.value("machine_type");
+    let was_running = form.context_str("status") == "RUNNING";
+
+    let steps = resize_steps(form)
+        .into_iter()
+        .map(|method| {
+            let (params, on_failure) = match method {
+                "stop_instance" => (
+                    json!({ "zone": zone }),
+                    format!("Resize of {} aborted: stop failed, nothing was changed", name),
+                ),
+                "set_machine_type" => (
+                    json!({ "zone": zone, "machine_type": target }),
+                    if was_running {
+                        format!(
+                            "Resize of {} failed: it is stopped with its original machine type {}; start it again with 's'",
+                            name, current
+                        )
+                    } else {
+                        format!(
+                            "Resize of {} failed: machine type is still {}",
+                            name, current
+                        )
+                    },
+                ),
+                _ => (
+                    json!({ "zone": zone }),
+                    format!(
+                        "{} was resized to {} but did not start; to roll back, set the machine type back to {} with 'm'",
+                        name, target, current
+                    ),
+                ),
+            };
+            ChainStep::new(method, params, on_failure)
+        })
+        .collect();
+
+    OperationChain::new(
+        "compute",
+        name,
+        &format!("Resized {} to {}", name, target),
+        steps,
+    )
+}
+
+// =============================================================================
+// Snapshots
+// =============================================================================
+
+/// Maximum length of a Compute resource name
+const MAX_NAME_LEN: usize = 63;
+
+/// Build the snapshot form for a disk (a list item). The default name is the
+/// disk name followed by `timestamp` (e.g. `20240115-103000`).
+pub fn snapshot_disk_form(disk: &Value, timestamp: &str) -> Result<FormState, String> {
+    let name = str_field(disk, "name");
+    let zone = str_field(disk, "zone_short");
+    if name.is_empty() || zone.is_empty() {
+        return Err("Disk name or zone unknown".to_string());
+    }
+
+    let suffix = format!("-{}", timestamp);
+    let prefix_len = MAX_NAME_LEN.saturating_sub(suffix.len()).min(name.len());
+    let default_name = format!("{}{}", name[..prefix_len].trim_end_matches('-'), suffix);
+
+    let fields = vec![
+        FormField::text("name", "Snapshot name", &default_name)
+            .required()
+            .with_validator(|v| {
+                validate_gcp_resource_name(v, "Snapshot").map_err(|e| e.to_string())
+            }),
+        FormField::text("description", "Description", ""),
+    ];
+
+    Ok(FormState::new(
+        &format!("Snapshot {}", name),
+        FormPurpose::SnapshotDisk,
+        &["Snapshot"],
+        fields,
+    )
+    .with_context(json!({ "name": name, "zone": zone })))
+}
+
+/// `disks.createSnapshot` request body
+pub fn snapshot_body(form: &FormState) -> Result<Value, String> {
+    let mut body = json!({ "name": form.value("name") });
+    let description = form.value("description").trim();
+    if !description.is_empty() {
+        body["description"] = json!(description);
+    }
+    Ok(body)
+}
+
+/// Build the form creating a disk from a snapshot (a list item). The size
+/// defaults to the snapshot's source disk size, the minimum allowed.
+pub fn disk_from_snapshot_form(
+    snapshot: &Value,
+    zones: &[String],
+    default_zone: &str,
+) -> Result<FormState, String> {
+    let name = str_field(snapshot, "name");
+    let self_link = str_field(snapshot, "selfLink");
+    if name.is_empty() || self_link.is_empty() {
+        return Err("Snapshot name or link unknown".to_string());
+    }
+    let status = str_field(snapshot, "status");
+    if !status.is_empty() && status != "READY" {
+        return Err(format!("Snapshot {} is {}, not READY", name, status));
+    }
+    let size = str_field(snapshot, "diskSizeGb")
+        .parse::<u64>()
+        .unwrap_or(MIN_DISK_SIZE_GB);
+
+    let zone_options: Vec<SelectOption> = zones
+        .iter()
+        .filter(|z| z.as_str() != "all")
+        .map(SelectOption::plain)
+        .collect();
+    let default_name = format!(
+        "{}-disk",
+        &name[..name.len().min(MAX_NAME_LEN - "-disk".len())]
+    );
+
+    let fields = vec![
+        FormField::text("name", "Disk name", &default_name)
+            .required()
+            .with_validator(|v| validate_gcp_resource_name(v, "Disk").map_err(|e| e.to_string())),
+        FormField::select("zone", "Zone", zone_options, default_zone).required(),
+        FormField::select("disk_type", "Disk type", disk_type_options(), "pd-balanced"),
+        FormField::number("size", "Size (GB)", size)
+            .required()
+            .with_help("At least the snapshot's source disk size"),
+    ];
+
+    Ok(FormState::new(
+        &format!("Create disk from {}", name),
+        FormPurpose::CreateDiskFromSnapshot,
+        &["Disk"],
+        fields,
+    )
+    .with_context(json!({
+        "name": name,
+        "selfLink": self_link,
+        "diskSizeGb": size,
+    })))
+}
+
+/// `disks.insert` request body for a disk restored from a snapshot
+pub fn disk_from_snapshot_body(form: &FormState) -> Result<Value, String> {
+    let minimum = form
+        .context
+        .get("diskSizeGb")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(MIN_DISK_SIZE_GB);
+    let size = form
+        .value("size")
+        .parse::<u64>()
+        .map_err(|_| "Size must be a number".to_string())?;
+    if size < minimum {
+        return Err(format!(
+            "Size must be at least {} GB (size of the snapshot's source disk)",
+            minimum
+        ));
+    }
+
+    Ok(json!({
+        "name": form.value("name"),
+        "sourceSnapshot": form.context_str("selfLink"),
+        "sizeGb": size.to_string(),
+        "type": format!("zones/{}/diskTypes/{}", form.value("zone"), form.value("disk_type")),
+    }))
+}
+
+// =============================================================================
+// Disk resize, attach and detach
+// =============================================================================
+
+/// Name, zone and size of a disk (a list item)
+fn disk_info(disk: &Value) -> Result<(&str, &str, u64), String> {
+    let name = str_field(disk, "name");
+    let zone = str_field(disk, "zone_short");
+    if name.is_empty() || zone.is_empty() {
+        return Err("Disk name or zone unknown".to_string());
+    }
+    let size = str_field(disk, "sizeGb").parse::<u64>().unwrap_or(0);
+    Ok((name, zone, size))
+}
+
+/// Short names of the instances a disk is attached to
+fn disk_users(disk: &Value) -> Vec<String> {
+    disk.get("users")
+        .and_then(|v| v.as_array())
+        .map(|users| {
+            users
+                .iter()
+                .filter_map(|u| u.as_str())
+                .filter_map(|u| u.rsplit('/').next())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Confirmation of a disk action, run through the regular confirm dialog
+fn disk_action(
+    method: &str,
+    disk: &str,
+    params: Value,
+    message: String,
+    destructive: bool,
+) -> PendingAction {
+    PendingAction {
+        service: "compute".to_string(),
+        sdk_method: method.to_string(),
+        resource_id: disk.to_string(),
+        params,
+        message,
+        destructive,
+        selected_yes: false,
+    }
+}
+
+/// Build the resize form for a disk. Disks can only grow.
+pub fn resize_disk_form(disk: &Value) -> Result<FormState, String> {
+    let (name, zone, size) = disk_info(disk)?;
+    let fields = vec![FormField::number("size", "New size (GB)", size + 1)
+        .required()
+        .with_help("Disks can only grow; the filesystem must be resized on the instance")];
+
+    Ok(FormState::new(
+        &format!("Resize disk {} ({} GB)", name, size),
+        FormPurpose::ResizeDisk,
+        &["Size"],
+        fields,
+    )
+    .with_context(json!({ "name": name, "zone": zone, "size": size })))
+}
+
+/// `disks.resize` confirmation, refusing anything but growth
+pub fn resize_disk_action(form: &FormState) -> Result<PendingAction, String> {
+    let name = form.context_str("name");
+    let current = form
+        .context
+        .get("size")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let size = form
+        .value("size")
+        .parse::<u64>()
+        .map_err(|_| "Size must be a number".to_string())?;
+    if size <= current {
+        return Err(format!(
+            "Disks can only grow: size must be more than {} GB",
+            current
+        ));
+    }
+
+    Ok(disk_action(
+        "resize_disk",
+        name,
+        json!({ "zone": form.context_str("zone"), "size_gb": size.to_string() }),
+        format!("Resize disk '{}' from {} GB to {} GB?", name, current, size),
+        false,
+    ))
+}
+
+/// Build the attach form for a disk from the instances of its zone
+/// (`list_instances` response). Instances already using the disk are left
+/// out; a disk in use elsewhere defaults to read-only, since read-write
+/// disks can only be attached to one instance.
+pub fn attach_disk_form(disk: &Value, instances: &Value) -> Result<FormState, String> {
+    let (name, zone, _) = disk_info(disk)?;
+    let users = disk_users(disk);
+
+    let options: Vec<SelectOption> = items(instances)
+        .iter()
+        .filter(|i| str_field(i, "zone").rsplit('/').next() == Some(zone))
+        .map(|i| str_field(i, "name"))
+        .filter(|n| !n.is_empty() && !users.iter().any(|u| u == n))
+        .map(SelectOption::plain)
+        .collect();
+    if options.is_empty() {
+        return Err(format!(
+            "No other instance in {} to attach {} to",
+            zone, name
+        ));
+    }
+
+    let default_mode = if users.is_empty() {
+        "READ_WRITE"
+    } else {
+        "READ_ONLY"
+    };
+    let fields = vec![
+        FormField::select("instance", "Instance", options, "")
+            .required()
+            .with_help(&format!("Instances in {}", zone)),
+        FormField::select(
+            "mode",
+            "Mode",
+            vec![
+                SelectOption::new("READ_WRITE", "Read-write"),
+                SelectOption::new("READ_ONLY", "Read-only"),
+            ],
+            default_mode,
+        ),
+    ];
+
+    Ok(FormState::new(
+        &format!("Attach disk {}", name),
+        FormPurpose::AttachDisk,
+        &["Instance"],
+        fields,
+    )
+    .with_context(json!({ "name": name, "zone": zone })))
+}
+
+/// `instances.attachDisk` confirmation
+pub fn attach_disk_action(form: &FormState) -> Result<PendingAction, String> {
+    let name = form.context_str("name");
+    let instance = form.value("instance");
+    let mode = form.value("mode");
+    let label = if mode == "READ_ONLY" {
+        "read-only"
+    } else {
+        "read-write"
+    };
+
+    Ok(disk_action(
+        "attach_disk",
+        name,
+        json!({ "zone": form.context_str("zone"), "instance": instance, "mode": mode }),
+        format!("Attach disk '{}' to '{}' ({})?", name, instance, label),
+        false,
+    ))
+}
+
+/// Detach a disk: directly confirmed when there's a single instance to
+/// detach from (the parent instance in an instance's disk view, or the only
+/// user), otherwise a form choosing among the disk's users.
+pub fn detach_disk(disk: &Value, parent_instance: Option<&str>) -> Result<DetachDisk, String> {
+    let (name, zone, _) = disk_info(disk)?;
+    let users = disk_users(disk);
+    let candidates: Vec<String> = match parent_instance {
+        Some(parent) => users.into_iter().filter(|u| u == parent).collect(),
+        None => users,
+    };
+
+    match candidates.as_slice() {
+        [] => Err(format!("Disk {} is not attached to an instance", name)),
+        [instance] => Ok(DetachDisk::Confirm(detach_disk_pending(
+            name, zone, instance,
+        ))),
+        _ => {
+            let options = candidates.iter().map(SelectOption::plain).collect();
+            let fields = vec![FormField::select("instance", "Instance", options, "").required()];
+            Ok(DetachDisk::Choose(
+                FormState::new(
+                    &format!("Detach disk {}", name),
+                    FormPurpose::DetachDisk,
+                    &["Instance"],
+                    fields,
+                )
+                .with_context(json!({ "name": name, "zone": zone })),
+            ))
+        },
+    }
+}
+
+/// What detaching a disk needs
+pub enum DetachDisk {
+    /// Confirm detaching from the only candidate instance
+    Confirm(PendingAction),
+    /// Choose the instance first
+    Choose(FormState),
+}
+
+/// `instances.detachDisk` confirmation for the instance chosen in the form
+pub fn detach_disk_action(form: &FormState) -> Result<PendingAction, String> {
+    Ok(detach_disk_pending(
+        form.context_str("name"),
+        form.context_str("zone"),
+        form.value("instance"),
+    ))
+}
+
+fn detach_disk_pending(disk: &str, zone: &str, instance: &str) -> PendingAction {
+    disk_action(
+        "detach_disk",
+        disk,
+        json!({ "zone": zone, "instance": instance }),
+        format!("Detach disk '{}' from '{}'?", disk, instance),
+        true,
+    )
+}
+
+// =============================================================================
+// Managed instance groups
+// =============================================================================
+
+/// Build the resize form for a managed instance group
+pub fn resize_group_form(group: &Value) -> Result<FormState, String> {
+    let name = str_field(group, "name");
+    if name.is_empty() {
+        return Err("Instance group name unknown".to_string());
+    }
+    let size = group
+        .get("targetSize")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let help = if group.pointer("/status/autoscaler").is_some() {
+        "The group is autoscaled: the autoscaler may change the size again"
+    } else {
+        "Instances are created or deleted to reach the new size"
+    };
+    let fields = vec![FormField::number("size", "Target size", size)
+        .required()
+        .with_help(help)];
+
+    let mut context = crate::app::action_params(group);
+    context["name"] = json!(name);
+    context["size"] = json!(size);
+    Ok(FormState::new(
+        &format!("Resize instance group {} ({} instances)", name, size),
+        FormPurpose::ResizeGroup,
+        &["Size"],
+        fields,
+    )
+    .with_context(context))
+}
+
+/// `instanceGroupManagers.resize` confirmation
+pub fn resize_group_action(form: &FormState) -> Result<PendingAction, String> {
+    let name = form.context_str("name");
+    let current = form
+        .context
+        .get("size")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let size = form
+        .value("size")
+        .parse::<u64>()
+        .map_err(|_| "Size must be a number".to_string())?;
+    if size == current {
+        return Err(format!(
+            "The group already has a target size of {}",
+            current
+        ));
+    }
+
+    // Zonal or regional group
+    let mut params = json!({ "size": size.to_string() });
+    for key in ["zone", "region"] {
+        let value = form.context_str(key);
+        if !value.is_empty() {
+            params[key] = json!(value);
+        }
+    }
+    Ok(PendingAction {
+        service: "compute".to_string(),
+        sdk_method: "resize_instance_group".to_string(),
+        resource_id: name.to_string(),
+        params,
+        message: format!(
+            "Resize instance group '{}' from {} to {} instances?",
+            name, current, size
+        ),
+        destructive: size < current,
+        selected_yes: false,
+    })
+}
+
+// =============================================================================
+// Static IP addresses
+// =============================================================================
+
+/// Network tiers of external addresses
+fn network_tier_options() -> Vec<SelectOption> {
+    vec![
+        SelectOption::new("PREMIUM", "PREMIUM - Google's network, global"),
+        SelectOption::new("STANDARD", "STANDARD - regional, internet transit"),
+    ]
+}
+
+/// Distinct regions of the available zones
+pub fn region_options(zones: &[String]) -> Vec<SelectOption> {
+    let mut regions: Vec<&str> = zones
+        .iter()
+        .filter(|z| z.as_str() != "all")
+        .map(|z| zone_region(z))
+        .collect();
+    regions.sort_unstable();
+    regions.dedup();
+    regions.into_iter().map(SelectOption::plain).collect()
+}
+
+fn address_name_field(default: &str) -> FormField {
+    FormField::text("name", "Address name", default)
+        .required()
+        .with_validator(|v| validate_gcp_resource_name(v, "Address").map_err(|e| e.to_string()))
+}
+
+/// Build the form reserving a new external address. Global addresses are
+/// always on the premium tier.
+pub fn reserve_address_form(global: bool, zones: &[String], default_region: &str) -> FormState {
+    let mut fields = vec![address_name_field("")];
+    if !global {
+        fields.push(
+            FormField::select("region", "Region", region_options(zones), default_region).required(),
+        );
+        fields.push(FormField::select(
+            "tier",
+            "Network tier",
+            network_tier_options(),
+            "PREMIUM",
+        ));
+    }
+    let title = if global {
+        "Reserve global external address"
+    } else {
+        "Reserve external address"
+    };
+    FormState::new(title, FormPurpose::ReserveAddress, &["Address"], fields)
+        .with_context(json!({ "global": global }))
+}
+
+/// `addresses.insert` request body for a new external address
+pub fn reserve_address_body(form: &FormState) -> Result<Value, String> {
+    let mut body = json!({
+        "name": form.value("name"),
+        "addressType": "EXTERNAL",
+    });
+    if !form.value("tier").is_empty() {
+        body["networkTier"] = json!(form.value("tier"));
+    }
+    Ok(body)
+}
+
+/// Build the form promoting the ephemeral external IP of an instance to a
+/// static address, keeping the same IP
+pub fn promote_address_form(instance: &Value) -> Result<FormState, String> {
+    let name = str_field(instance, "name");
+    let zone = str_field(instance, "zone_short");
+    let access_config = instance
+        .get("networkInterfaces")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|nic| nic.get("accessConfigs").and_then(|v| v.as_array()))
+        .flatten()
+        .find(|config| !str_field(config, "natIP").is_empty())
+        .ok_or_else(|| format!("Instance {} has no external IP", name))?;
+    let address = str_field(access_config, "natIP");
+    let tier = match str_field(access_config, "networkTier") {
+        "" => "PREMIUM",
+        tier => tier,
+    };
+
+    let default_name = format!("{}-ip", &name[..name.len().min(MAX_NAME_LEN - "-ip".len())]);
+    let fields = vec![address_name_field(&default_name)
+        .with_help("The IP stays attached to the instance and is kept when it stops")];
+
+    Ok(FormState::new(
+        &format!("Promote {} of {} to a static address", address, name),
+        FormPurpose::PromoteAddress,
+        &["Address"],
+        fields,
+    )
+    .with_context(json!({
+        "instance": name,
+        "address": address,
+        "region": zone_region(zone),
+        "tier": tier,
+    })))
+}
+
+/// `addresses.insert` request body reserving an instance's current IP
+pub fn promote_address_body(form: &FormState) -> Result<Value, String> {
+    Ok(json!({
+        "name": form.value("name"),
+        "address": form.context_str("address"),
+        "addressType": "EXTERNAL",
+        "networkTier": form.context_str("tier"),
+        "description": format!("Promoted from instance {}", form.context_str("instance")),
+    }))
+}
+
+/// Refusal of the promote form when the instance's IP already is a reserved
+/// address, from an `addresses.list` response of its region
+pub fn promote_address_refusal(form: &FormState, addresses: &Value) -> Option<String> {
+    let ip = form.context_str("address");
+    items(addresses)
+        .iter()
+        .find(|a| str_field(a, "address") == ip)
+        .map(|a| {
+            format!(
+                "{} is already the static address {}",
+                ip,
+                str_field(a, "name")
+            )
+        })
+}
+
+// =============================================================================
+// Request preview
+// =============================================================================
+
+/// Notes shown above the request preview: the impact of firewall rules
+pub fn preview_notes(form: &FormState, body: &Value) -> Vec<String> {
+    if !matches!(
+        form.purpose,
+        FormPurpose::CreateFirewall | FormPurpose::EditFirewall
+    ) {
+        return Vec::new();
+    }
+    let instances = form
+        .context
+        .get("instances")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    impact_notes(body, &form.context["current"], instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled_form() -> FormState {
+        let zones = vec!["all".to_string(), "us-central1-a".to_string()];
+        let mut form = create_instance_form(&zones, "us-central1-a");
+        for c in "web-01".chars() {
+            form.field_mut("name").unwrap().input_char(c);
+        }
+        form.field_mut("machine_type")
+            .unwrap()
+            .set_options(vec![SelectOption::plain("e2-medium")], "e2-medium");
+        form.field_mut("image").unwrap().set_options(
+            vec![SelectOption::plain("debian-cloud/debian-12")],
+            DEFAULT_IMAGE,
+        );
+        form
+    }
+
+    #[test]
+    fn test_zone_region() {
+        assert_eq!(zone_region("us-central1-a"), "us-central1");
+        assert_eq!(zone_region("europe-west4-b"), "europe-west4");
+    }
+
+    #[test]
+    fn test_zone_options_exclude_all() {
+        let form = filled_form();
+        let zone = form.field("zone").unwrap();
+        assert_eq!(zone.filtered_options().len(), 1);
+        assert_eq!(zone.value, "us-central1-a");
+    }
+
+    #[test]
+    fn test_machine_type_options() {
+        let response = json!({"items": [
+            {"name": "n2-standard-4", "guestCpus": 4, "memoryMb": 16384},
+            {"name": "e2-small", "guestCpus": 2, "memoryMb": 2048},
+            {"name": "n1-old", "guestCpus": 1, "memoryMb": 3840, "deprecated": {"state": "DEPRECATED"}},
+        ]});
+        let options = machine_type_options(&response);
+        assert_eq!(options.len(), 2);
+        assert_eq!(options[0].value, "e2-small");
+        assert!(options[0].label.ends_with("2 vCPU, 2 GB"));
+        assert!(options[1].label.ends_with("4 vCPU, 16 GB"));
+        assert_eq!(format_memory_gb(1740), "1.7");
+    }
+
+    #[test]
+    fn test_image_family_options() {
+        let response = json!({"items": [
+            {"name": "debian-12-v2", "family": "debian-12"},
+            {"name": "debian-12-v1", "family": "debian-12"},
+            {"name": "debian-10-v1", "family": "debian-10", "deprecated": {"state": "DEPRECATED"}},
+            {"name": "no-family"},
+        ]});
+        let options = image_family_options("debian-cloud", &response);
+        assert_eq!(options, vec![SelectOption::plain("debian-cloud/debian-12")]);
+    }
+
+    #[test]
+    fn test_parse_tags_and_labels() {
+        assert_eq!(
+            parse_tags("http-server, https-server,").unwrap(),
+            vec!["http-server", "https-server"]
+        );
+        assert!(parse_tags("Bad_Tag").is_err());
+
+        let labels = parse_labels("env=prod, team=web,empty").unwrap();
+        assert_eq!(labels.get("env"), Some(&json!("prod")));
+        assert_eq!(labels.get("empty"), Some(&json!("")));
+        assert!(parse_labels("Env=prod").is_err());
+        assert!(parse_labels("env=Prod").is_err());
+    }
+
+    #[test]
+    fn test_instance_insert_body_defaults() {
+        let body = instance_insert_body(&filled_form()).unwrap();
+        assert_eq!(body["name"], "web-01");
+        assert_eq!(
+            body["machineType"],
+            "zones/us-central1-a/machineTypes/e2-medium"
+        );
+        let init = &body["disks"][0]["initializeParams"];
+        assert_eq!(
+            init["sourceImage"],
+            "projects/debian-cloud/global/images/family/debian-12"
+        );
+        assert_eq!(init["diskSizeGb"], "10");
+        assert_eq!(
+            init["diskType"],
+            "zones/us-central1-a/diskTypes/pd-balanced"
+        );
+        assert_eq!(
+            body["networkInterfaces"][0]["network"],
+            "global/networks/default"
+        );
+        assert_eq!(
+            body["networkInterfaces"][0]["accessConfigs"][0]["type"],
+            "ONE_TO_ONE_NAT"
+        );
+        assert_eq!(body["serviceAccounts"][0]["email"], "default");
+        assert!(body.get("scheduling").is_none());
+        assert!(body.get("tags").is_none());
+    }
+
+    #[test]
+    fn test_instance_insert_body_options() {
+        let mut form = filled_form();
+        form.field_mut("subnet").unwrap().set_options(
+            vec![SelectOption::plain(
+                "https://www.googleapis.com/compute/v1/projects/p/regions/us-central1/subnetworks/app",
+            )],
+            "",
+        );
+        form.field_mut("external_ip").unwrap().cycle(true);
+        form.field_mut("tags").unwrap().value = "ssh".to_string();
+        form.field_mut("labels").unwrap().value = "env=dev".to_string();
+        form.field_mut("service_account").unwrap().value = "none".to_string();
+        form.field_mut("provisioning").unwrap().cycle(true);
+
+        let body = instance_insert_body(&form).unwrap();
+        let interface = &body["networkInterfaces"][0];
+        assert!(interface["subnetwork"]
+            .as_str()
+            .unwrap()
+            .ends_with("/subnetworks/app"));
+        assert!(interface.get("accessConfigs").is_none());
+        assert_eq!(body["tags"]["items"], json!(["ssh"]));
+        assert_eq!(body["labels"], json!({"env": "dev"}));
+        assert!(body.get("serviceAccounts").is_none());
+        assert_eq!(body["scheduling"]["provisioningModel"], "SPOT");
+    }
+
+    #[test]
+    fn test_disk_size_validation() {
+        let mut form = filled_form();
+        form.field_mut("disk_size").unwrap().value = "5".to_string();
+        assert!(form.field("disk_size").unwrap().validate().is_err());
+        form.field_mut("name").unwrap().value = "Web".to_string();
+        assert!(form.field("name").unwrap().validate().is_err());
+    }
+    fn resize_form(status: &str) -> FormState {
+        let instance = json!({
+            "name": "web-01",
+            "zone_short": "us-central1-a",
+            "machineType_short": "e2-medium",
+            "status": status,
+        });
+        let mut form = resize_instance_form(&instance).unwrap();
+        let response = json!({"items": [
+            {"name": "e2-medium", "guestCpus": 2, "memoryMb": 4096},
+            {"name": "n2-standard-4", "guestCpus": 4, "memoryMb": 16384},
+        ]});
+        let options = resize_machine_type_options(&response, "e2-medium");
+        assert!(options[0].label.ends_with("(current)"));
+        form.field_mut("machine_type")
+            .unwrap()
+            .set_options(options, "e2-medium");
+        form
+    }
+
+    #[test]
+    fn test_resize_requires_running_or_stopped() {
+        let instance = json!({
+            "name": "web-01",
+            "zone_short": "us-central1-a",
+            "machineType_short": "e2-medium",
+            "status": "STOPPING",
+        });
+        assert!(resize_instance_form(&instance).is_err());
+    }
+
+    #[test]
+    fn test_resize_plan_of_running_instance() {
+        let mut form = resize_form("RUNNING");
+        assert!(form.title.contains("1 vCPU, 4 GB"));
+        assert!(resize_plan(&form).is_err());
+
+        form.field_mut("machine_type").unwrap().cycle(true);
+        let plan = resize_plan(&form).unwrap();
+        assert_eq!(
+            plan["steps"],
+            json!(["stop_instance", "set_machine_type", "start_instance"])
+        );
+        assert_eq!(
+            plan["setMachineType"]["machineType"],
+            "zones/us-central1-a/machineTypes/n2-standard-4"
+        );
+
+        let mut chain = resize_chain(&form);
+        assert_eq!(chain.steps.len(), 3);
+        assert_eq!(chain.steps[1].params["machine_type"], "n2-standard-4");
+        assert!(chain.advance());
+        assert!(chain
+            .failure_message("quota")
+            .contains("original machine type e2-medium"));
+    }
+
+    #[test]
+    fn test_resize_stopped_instance_skips_stop() {
+        let mut form = resize_form("TERMINATED");
+        assert!(!form.is_on("start"));
+        form.field_mut("machine_type").unwrap().cycle(true);
+        let chain = resize_chain(&form);
+        assert_eq!(chain.steps.len(), 1);
+        assert_eq!(chain.steps[0].sdk_method, "set_machine_type");
+    }
+
+    #[test]
+    fn test_snapshot_default_name() {
+        let disk = json!({ "name": "data", "zone_short": "us-central1-a" });
+        let form = snapshot_disk_form(&disk, "20240115-103000").unwrap();
+        assert_eq!(form.value("name"), "data-20240115-103000");
+        assert_eq!(form.context_str("zone"), "us-central1-a");
+        assert_eq!(
+            snapshot_body(&form).unwrap(),
+            json!({ "name": "data-20240115-103000" })
+        );
+
+        // Long disk names are shortened to keep the name within 63 characters
+        let long = json!({ "name": "a".repeat(60), "zone_short": "us-central1-a" });
+        let form = snapshot_disk_form(&long, "20240115-103000").unwrap();
+        assert_eq!(form.value("name").len(), 63);
+        assert!(form.value("name").ends_with("-20240115-103000"));
+    }
+
+    #[test]
+    fn test_disk_from_snapshot_body() {
+        let snapshot = json!({
+            "name": "data-snap",
+            "status": "READY",
+            "selfLink": "https://www.googleapis.com/compute/v1/projects/p/global/snapshots/data-snap",
+            "diskSizeGb": "50",
+        });
+        let zones = vec!["all".to_string(), "us-central1-a".to_string()];
+        let mut form = disk_from_snapshot_form(&snapshot, &zones, "us-central1-a").unwrap();
+        assert_eq!(form.value("name"), "data-snap-disk");
+        assert_eq!(form.value("size"), "50");
+
+        let body = disk_from_snapshot_body(&form).unwrap();
+        assert_eq!(body["sourceSnapshot"], snapshot["selfLink"]);
+        assert_eq!(body["sizeGb"], "50");
+        assert_eq!(body["type"], "zones/us-central1-a/diskTypes/pd-balanced");
+
+        form.field_mut("size").unwrap().value = "20".to_string();
+        assert!(disk_from_snapshot_body(&form).is_err());
+
+        let creating = json!({ "name": "s", "selfLink": "x", "status": "CREATING" });
+        assert!(disk_from_snapshot_form(&creating, &zones, "us-central1-a").is_err());
+    }
+
+    fn disk(users: &[&str]) -> Value {
+        json!({
+            "name": "data",
+            "zone_short": "us-central1-a",
+            "sizeGb": "100",
+            "users": users
+                .iter()
+                .map(|u| format!("https://www.googleapis.com/compute/v1/projects/p/zones/us-central1-a/instances/{}", u))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    #[test]
+    fn test_resize_disk_only_grows() {
+        let mut form = resize_disk_form(&disk(&[])).unwrap();
+        assert_eq!(form.value("size"), "101");
+        let pending = resize_disk_action(&form).unwrap();
+        assert_eq!(pending.sdk_method, "resize_disk");
+        assert_eq!(pending.params["size_gb"], "101");
+        assert_eq!(pending.params["zone"], "us-central1-a");
+
+        form.field_mut("size").unwrap().value = "100".to_string();
+        assert!(resize_disk_action(&form).is_err());
+    }
+
+    #[test]
+    fn test_promote_address() {
+        let instance = json!({
+            "name": "web-01",
+            "zone_short": "us-central1-a",
+            "networkInterfaces": [{"accessConfigs": [
+                {"natIP": "34.1.2.3", "networkTier": "STANDARD"}
+            ]}],
+        });
+        let form = promote_address_form(&instance).unwrap();
+        assert_eq!(form.value("name"), "web-01-ip");
+        assert_eq!(form.context_str("region"), "us-central1");
+        let body = promote_address_body(&form).unwrap();
+        assert_eq!(body["address"], "34.1.2.3");
+        assert_eq!(body["networkTier"], "STANDARD");
+        assert_eq!(promote_address_refusal(&form, &json!({})), None);
+        let reserved = json!({"items": [{"name": "web-ip", "address": "34.1.2.3"}]});
+        assert_eq!(
+            promote_address_refusal(&form, &reserved).as_deref(),
+            Some("34.1.2.3 is already the static address web-ip")
+        );
+
+        let internal = json!({"name": "db", "networkInterfaces": [{"networkIP": "10.0.0.2"}]});
+        assert!(promote_address_form(&internal).is_err());
+    }
+
+    #[test]
+    fn test_reserve_address() {
+        let zones = vec![
+            "all".to_string(),
+            "us-central1-a".to_string(),
+            "us-central1-b".to_string(),
+            "europe-west1-b".to_string(),
+        ];
+        let options: Vec<String> = region_options(&zones)
+            .into_iter()
+            .map(|o| o.value)
+            .collect();
+        assert_eq!(options, vec!["europe-west1", "us-central1"]);
+
+        let form = reserve_address_form(true, &zones, "us-central1");
+        assert!(form.field("region").is_none());
+        let body = reserve_address_body(&form).unwrap();
+        assert!(body.get("networkTier").is_none());
+    }
+
+    #[test]
+    fn test_resize_group() {
+        let group = json!({
+            "name": "web",
+            "region_short": "us-central1",
+            "targetSize": 3,
+        });
+        let mut form = resize_group_form(&group).unwrap();
+        assert_eq!(form.value("size"), "3");
+        assert!(resize_group_action(&form).is_err());
+
+        form.field_mut("size").unwrap().value = "1".to_string();
+        let pending = resize_group_action(&form).unwrap();
+        assert_eq!(pending.sdk_method, "resize_instance_group");
+        assert_eq!(pending.params["region"], "us-central1");
+        assert_eq!(pending.params["size"], "1");
+        assert!(pending.params.get("zone").is_none());
+        assert!(pending.destructive);
+    }
+
+    #[test]
+    fn test_attach_disk_offers_instances_of_the_zone() {
+        let instances = json!({"items": [
+            {"name": "web", "zone": "https://www.googleapis.com/compute/v1/projects/p/zones/us-central1-a"},
+            {"name": "db", "zone": "https://www.googleapis.com/compute/v1/projects/p/zones/us-central1-a"},
+            {"name": "far", "zone": "https://www.googleapis.com/compute/v1/projects/p/zones/europe-west1-b"},
+        ]});
+        let form = attach_disk_form(&disk(&["web"]), &instances).unwrap();
+        let options: Vec<&str> = form
+            .field("instance")
+            .unwrap()
+            .filtered_options()
+            .iter()
+            .map(|o| o.value.as_str())
+            .collect();
+        assert_eq!(options, vec!["db"]);
+        // Already attached elsewhere: read-only by default
+        assert_eq!(form.value("mode"), "READ_ONLY");
+
+        let pending = attach_disk_action(&form).unwrap();
+        assert_eq!(pending.params["instance"], "db");
+        assert_eq!(pending.params["mode"], "READ_ONLY");
+
+        assert!(attach_disk_form(&disk(&["web", "db"]), &instances).is_err());
+    }
+
+    #[test]
+    fn test_detach_disk() {
+        assert!(detach_disk(&disk(&[]), None).is_err());
+
+        let Ok(DetachDisk::Confirm(pending)) = detach_disk(&disk(&["web"]), None) else {
+            panic!("expected a confirmation");
+        };
+        assert_eq!(pending.params["instance"], "web");
+        assert!(pending.destructive);
+
+        // From an instance's disk view, the parent instance is the one
+        assert!(matches!(
+            detach_disk(&disk(&["web", "db"]), Some("db")),
+            Ok(DetachDisk::Confirm(p)) if p.params["instance"] == "db"
+        ));
+        assert!(matches!(
+            detach_disk(&disk(&["web", "db"]), None),
+            Ok(DetachDisk::Choose(_))
+        ));
+    }
+}
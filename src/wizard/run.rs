@@ -0,0 +1,63 @@
+//! Run Command Form
+//!
+//! Running a command on instances over SSH ([`crate::shell::run`]).
+
+use super::str_field;
+use crate::form::{FormField, FormPurpose, FormState};
+use crate::shell::run::{validate_command, validate_parallelism, RunTarget, DEFAULT_PARALLELISM};
+use serde_json::{json, Value};
+
+/// Build the form running a command on `instances` over SSH
+pub fn run_command_form(instances: &[&Value], use_iap: bool) -> Result<FormState, String> {
+    let targets: Vec<Value> = instances
+        .iter()
+        .map(|item| json!({ "name": str_field(item, "name"), "zone": str_field(item, "zone_short") }))
+        .filter(|t| t["name"] != "" && t["zone"] != "")
+        .collect();
+    if targets.is_empty() {
+        return Err("No instance selected".to_string());
+    }
+    let parallelism = DEFAULT_PARALLELISM.min(targets.len()) as u64;
+    let fields = vec![
+        FormField::text("command", "Command", "")
+            .required()
+            .with_validator(|v| validate_command(v).map_err(|e| e.to_string()))
+            .with_help("Run by the remote shell, e.g. systemctl status myapp"),
+        FormField::toggle("iap", "Through IAP", use_iap),
+        FormField::number("parallelism", "Hosts at a time", parallelism)
+            .required()
+            .with_validator(|v| {
+                validate_parallelism(v)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            }),
+    ];
+    let title = match targets.as_slice() {
+        [target] => format!(
+            "Run command on {}",
+            target["name"].as_str().unwrap_or_default()
+        ),
+        _ => format!("Run command on {} instances", targets.len()),
+    };
+    Ok(
+        FormState::new(&title, FormPurpose::RunCommand, &["Command"], fields)
+            .with_context(json!({ "targets": targets })),
+    )
+}
+
+/// Instances the run form applies to
+pub fn run_targets(form: &FormState) -> Vec<RunTarget> {
+    form.context
+        .get("targets")
+        .and_then(|v| v.as_array())
+        .map(|targets| {
+            targets
+                .iter()
+                .map(|t| RunTarget {
+                    instance: str_field(t, "name").to_string(),
+                    zone: str_field(t, "zone").to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,72 @@
+//! Copy Files Form
+//!
+//! Copying files from or to an instance with `gcloud compute scp`
+//! ([`crate::shell::ScpOptions`]).
+
+use super::str_field;
+use crate::form::{FormField, FormPurpose, FormState};
+use crate::shell::{validate_scp_path, ScpDirection, ScpOptions};
+use serde_json::{json, Value};
+
+/// Build the form copying files from or to an instance
+pub fn copy_files_form(
+    instance: &Value,
+    direction: ScpDirection,
+    use_iap: bool,
+) -> Result<FormState, String> {
+    let name = str_field(instance, "name");
+    let zone = str_field(instance, "zone_short");
+    if name.is_empty() || zone.is_empty() {
+        return Err("Instance name or zone unknown".to_string());
+    }
+    let remote = FormField::text("remote_path", "Path on the instance", "")
+        .required()
+        .with_validator(|v| validate_scp_path(v, true).map_err(|e| e.to_string()))
+        .with_help("Relative paths start in the home directory; globs are allowed");
+    let local = FormField::text("local_path", "Local path", ".")
+        .required()
+        .with_validator(|v| validate_scp_path(v, false).map_err(|e| e.to_string()));
+    let (title, mut fields) = match direction {
+        ScpDirection::FromInstance => (
+            format!("Copy from {} ({})", name, zone),
+            vec![remote, local],
+        ),
+        ScpDirection::ToInstance => (
+            format!("Copy to {} ({})", name, zone),
+            vec![local.with_help("File or directory to upload"), remote],
+        ),
+    };
+    fields.push(FormField::toggle(
+        "recurse",
+        "Recursive (directories)",
+        false,
+    ));
+    fields.push(FormField::toggle("iap", "Through IAP", use_iap));
+
+    let to_instance = direction == ScpDirection::ToInstance;
+    Ok(
+        FormState::new(&title, FormPurpose::CopyFiles, &["Paths"], fields).with_context(json!({
+            "name": name,
+            "zone": zone,
+            "to_instance": to_instance,
+        })),
+    )
+}
+
+/// scp options described by the copy form
+pub fn scp_options(form: &FormState, project: &str) -> ScpOptions {
+    ScpOptions {
+        instance: form.context_str("name").to_string(),
+        zone: form.context_str("zone").to_string(),
+        project: project.to_string(),
+        direction: if form.context["to_instance"] == true {
+            ScpDirection::ToInstance
+        } else {
+            ScpDirection::FromInstance
+        },
+        remote_path: form.value("remote_path").to_string(),
+        local_path: form.value("local_path").to_string(),
+        recurse: form.is_on("recurse"),
+        use_iap: form.is_on("iap"),
+    }
+}
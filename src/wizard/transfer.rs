@@ -0,0 +1,187 @@
+//! Object Transfer Forms
+//!
+//! Downloading objects and uploading a local file ([`crate::transfer`]).
+
+use super::str_field;
+use crate::form::{FormField, FormPurpose, FormState};
+use crate::transfer::{
+    download_destination, existing_destinations, upload_object_name, validate_local_path,
+    TransferJob, TransferKind,
+};
+use serde_json::{json, Value};
+
+/// Build the form downloading objects of the current folder (`prefix`)
+pub fn download_objects_form(objects: &[&Value], prefix: &str) -> Result<FormState, String> {
+    let objects: Vec<Value> = objects
+        .iter()
+        .filter(|o| !crate::storage::is_folder(o))
+        .map(|o| {
+            json!({
+                "bucket": str_field(o, "bucket"),
+                "name": str_field(o, "name"),
+                "size": str_field(o, "size"),
+                "generation": str_field(o, "generation"),
+            })
+        })
+        .collect();
+    if objects.is_empty() {
+        return Err("Select objects to download (folders are skipped)".to_string());
+    }
+    let (title, help) = match objects.as_slice() {
+        [object] => (
+            format!("Download {}", str_field(object, "name")),
+            "Directory, or the file to write",
+        ),
+        _ => (
+            format!("Download {} objects", objects.len()),
+            "Directory; paths below the current folder are kept",
+        ),
+    };
+    let fields = vec![FormField::text("destination", "Save to", ".")
+        .required()
+        .with_validator(validate_local_path)
+        .with_help(help)];
+    Ok(FormState::new(
+        &title,
+        FormPurpose::DownloadObjects,
+        &["Destination"],
+        fields,
+    )
+    .with_context(json!({ "objects": objects, "prefix": prefix })))
+}
+
+/// Downloads described by the download form
+pub fn download_jobs(form: &FormState) -> Result<Vec<TransferJob>, String> {
+    let objects = form
+        .context
+        .get("objects")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let prefix = form.context_str("prefix");
+    let multiple = objects.len() > 1;
+    objects
+        .iter()
+        .map(|o| {
+            let name = str_field(o, "name");
+            let optional = |key| Some(str_field(o, key)).filter(|v| !v.is_empty());
+            Ok(TransferJob {
+                kind: TransferKind::Download,
+                bucket: str_field(o, "bucket").to_string(),
+                object: name.to_string(),
+                local: download_destination(form.value("destination"), name, prefix, multiple)?,
+                size: optional("size").and_then(|v| v.parse().ok()),
+                generation: optional("generation").map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// Refuse downloads that would replace local files until the form is
+/// submitted a second time with the same destination
+pub fn confirm_download_overwrite(
+    form: &mut FormState,
+    jobs: &[TransferJob],
+) -> Result<(), String> {
+    let existing = existing_destinations(jobs);
+    let destination = form.value("destination").to_string();
+    if existing.is_empty() || form.context_str("overwrite") == destination {
+        return Ok(());
+    }
+    form.context["overwrite"] = Value::String(destination);
+    let shown = match existing.len() {
+        1 => existing[0].clone(),
+        n => format!("{} and {} more", existing[0], n - 1),
+    };
+    Err(format!(
+        "{} already exists; submit again to overwrite",
+        shown
+    ))
+}
+
+/// Build the form uploading a local file into the current folder
+pub fn upload_object_form(bucket: &str, prefix: &str) -> FormState {
+    let fields = vec![
+        FormField::text("local_path", "Local file", "")
+            .required()
+            .with_validator(|v| {
+                validate_local_path(v)?;
+                if std::path::Path::new(v).is_file() {
+                    Ok(())
+                } else {
+                    Err("Not a file".to_string())
+                }
+            }),
+        FormField::text("name", "Object name", "")
+            .with_help(&format!("Defaults to {}<file name>", prefix)),
+    ];
+    FormState::new(
+        &format!("Upload to gs://{}/{}", bucket, prefix),
+        FormPurpose::UploadObject,
+        &["File"],
+        fields,
+    )
+    .with_context(json!({ "bucket": bucket, "prefix": prefix }))
+}
+
+/// Upload described by the upload form
+pub fn upload_job(form: &FormState) -> Result<TransferJob, String> {
+    let local = std::path::PathBuf::from(form.value("local_path"));
+    let object = upload_object_name(&local, form.value("name"), form.context_str("prefix"))?;
+    Ok(TransferJob {
+        kind: TransferKind::Upload,
+        bucket: form.context_str("bucket").to_string(),
+        object,
+        local,
+        size: None,
+        generation: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_forms() {
+        let objects = [
+            json!({ "bucket": "b", "name": "logs/", "folder": true }),
+            json!({ "bucket": "b", "name": "logs/a.txt", "size": "12", "generation": "7" }),
+            json!({ "bucket": "b", "name": "logs/2024/b.txt", "size": "3" }),
+        ];
+        let refs: Vec<&Value> = objects.iter().collect();
+        assert!(download_objects_form(&refs[..1], "logs/").is_err());
+
+        let mut form = download_objects_form(&refs, "logs/").unwrap();
+        form.field_mut("destination").unwrap().input_char('/');
+        let mut jobs = download_jobs(&form).unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].local, std::path::PathBuf::from("./a.txt"));
+        assert_eq!(jobs[0].size, Some(12));
+        assert_eq!(jobs[0].generation.as_deref(), Some("7"));
+        assert_eq!(jobs[1].local, std::path::PathBuf::from("./2024/b.txt"));
+        assert_eq!(jobs[1].generation, None);
+
+        // An existing local file is only replaced on a second submit
+        let existing = std::env::temp_dir().join(format!("tgcp-dl-{}", std::process::id()));
+        std::fs::write(&existing, b"x").unwrap();
+        jobs[0].local = existing.clone();
+        assert!(confirm_download_overwrite(&mut form, &jobs)
+            .unwrap_err()
+            .contains("already exists"));
+        assert!(confirm_download_overwrite(&mut form, &jobs).is_ok());
+        std::fs::remove_file(&existing).unwrap();
+
+        let mut form = upload_object_form("b", "logs/");
+        for c in "/tmp/report.csv".chars() {
+            form.field_mut("local_path").unwrap().input_char(c);
+        }
+        assert_eq!(upload_job(&form).unwrap().object, "logs/report.csv");
+        for c in "other/r.csv".chars() {
+            form.field_mut("name").unwrap().input_char(c);
+        }
+        let job = upload_job(&form).unwrap();
+        assert_eq!(job.object, "other/r.csv");
+        assert_eq!(job.kind, TransferKind::Upload);
+    }
+}
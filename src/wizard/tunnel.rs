@@ -0,0 +1,55 @@
+//! IAP Tunnel Form
+//!
+//! Starting an IAP tunnel to an instance ([`crate::tunnel`]).
+
+use super::str_field;
+use crate::form::{FormField, FormPurpose, FormState};
+use crate::tunnel::{parse_port, TunnelSpec};
+use serde_json::{json, Value};
+
+fn validate_port(value: &str) -> Result<(), String> {
+    parse_port(value).map(|_| ())
+}
+
+/// Build the form starting an IAP tunnel to an instance, suggesting a free
+/// local port
+pub fn tunnel_form(instance: &Value, local_port: Option<u16>) -> Result<FormState, String> {
+    let name = str_field(instance, "name");
+    let zone = str_field(instance, "zone_short");
+    if name.is_empty() || zone.is_empty() {
+        return Err("Instance name or zone unknown".to_string());
+    }
+    let fields = vec![
+        FormField::number("remote_port", "Instance port", 22)
+            .required()
+            .with_validator(validate_port)
+            .with_help("Port on the instance; the firewall must allow 35.235.240.0/20"),
+        FormField::number(
+            "local_port",
+            "Local port",
+            local_port.map_or(2222, u64::from),
+        )
+        .required()
+        .with_validator(validate_port)
+        .with_help("Port listening on localhost"),
+    ];
+    Ok(FormState::new(
+        &format!("IAP tunnel to {} ({})", name, zone),
+        FormPurpose::Tunnel,
+        &["Ports"],
+        fields,
+    )
+    .with_context(json!({ "name": name, "zone": zone })))
+}
+
+/// Tunnel described by the tunnel form
+pub fn tunnel_spec(form: &FormState, project: &str) -> Result<TunnelSpec, String> {
+    let port = |key: &str| parse_port(form.value(key));
+    Ok(TunnelSpec {
+        instance: form.context_str("name").to_string(),
+        zone: form.context_str("zone").to_string(),
+        project: project.to_string(),
+        remote_port: port("remote_port")?,
+        local_port: port("local_port")?,
+    })
+}